@@ -1,7 +1,21 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
 use coordtransform::*;
 use std::hint::black_box;
 
+/// 批量基准测试的点数 Number of points in the batch benchmarks
+const BATCH_SIZE: usize = 100_000;
+
+/// 生成覆盖中国东部的测试点 Generate test points spread over eastern China
+fn batch_points() -> Vec<(f64, f64)> {
+    (0..BATCH_SIZE)
+        .map(|i| {
+            let t = i as f64 / BATCH_SIZE as f64;
+            let u = ((i * 7919) % BATCH_SIZE) as f64 / BATCH_SIZE as f64;
+            (105.0 + 15.0 * t, 22.0 + 18.0 * u)
+        })
+        .collect()
+}
+
 fn bench_bd09_to_gcj02(c: &mut Criterion) {
     c.bench_function("bd09_to_gcj02", |b| {
         b.iter(|| bd09_to_gcj02(black_box(116.404), black_box(39.915)))
@@ -62,6 +76,83 @@ fn bench_bd09_to_epsg3857(c: &mut Criterion) {
     });
 }
 
+fn bench_batch(c: &mut Criterion) {
+    let points = batch_points();
+    let mut output = vec![(0.0, 0.0); points.len()];
+
+    let mut group = c.benchmark_group("batch");
+    group.throughput(Throughput::Elements(points.len() as u64));
+    group.bench_function("wgs84_to_gcj02", |b| {
+        b.iter(|| batch::wgs84_to_gcj02(black_box(&points), &mut output))
+    });
+    group.bench_function("gcj02_to_wgs84", |b| {
+        b.iter(|| batch::gcj02_to_wgs84(black_box(&points), &mut output))
+    });
+    group.bench_function("gcj02_to_bd09", |b| {
+        b.iter(|| batch::gcj02_to_bd09(black_box(&points), &mut output))
+    });
+    group.bench_function("wgs84_to_epsg3857", |b| {
+        b.iter(|| batch::wgs84_to_epsg3857(black_box(&points), &mut output))
+    });
+    group.bench_function("wgs84_to_bd09_in_place", |b| {
+        b.iter(|| {
+            output.copy_from_slice(&points);
+            batch::wgs84_to_bd09_in_place(black_box(&mut output));
+        })
+    });
+    group.finish();
+}
+
+#[cfg(feature = "simd")]
+fn bench_simd(c: &mut Criterion) {
+    let points = batch_points();
+    let mut output = vec![(0.0, 0.0); points.len()];
+
+    let mut group = c.benchmark_group("simd");
+    group.throughput(Throughput::Elements(points.len() as u64));
+    group.bench_function("wgs84_to_gcj02", |b| {
+        b.iter(|| simd::wgs84_to_gcj02(black_box(&points), &mut output))
+    });
+    group.bench_function("gcj02_to_wgs84", |b| {
+        b.iter(|| simd::gcj02_to_wgs84(black_box(&points), &mut output))
+    });
+    group.bench_function("gcj02_to_bd09", |b| {
+        b.iter(|| simd::gcj02_to_bd09(black_box(&points), &mut output))
+    });
+    group.bench_function("wgs84_to_bd09_in_place", |b| {
+        b.iter(|| {
+            output.copy_from_slice(&points);
+            simd::wgs84_to_bd09_in_place(black_box(&mut output));
+        })
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "simd"))]
+fn bench_simd(_: &mut Criterion) {}
+
+#[cfg(feature = "rayon")]
+fn bench_parallel(c: &mut Criterion) {
+    let points = batch_points();
+    let mut output = vec![(0.0, 0.0); points.len()];
+
+    let mut group = c.benchmark_group("parallel");
+    group.throughput(Throughput::Elements(points.len() as u64));
+    group.bench_function("wgs84_to_gcj02", |b| {
+        b.iter(|| parallel::wgs84_to_gcj02(black_box(&points), &mut output))
+    });
+    group.bench_function("wgs84_to_bd09_in_place", |b| {
+        b.iter(|| {
+            output.copy_from_slice(&points);
+            parallel::wgs84_to_bd09_in_place(black_box(&mut output));
+        })
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "rayon"))]
+fn bench_parallel(_: &mut Criterion) {}
+
 criterion_group!(
     benches,
     bench_bd09_to_gcj02,
@@ -73,6 +164,9 @@ criterion_group!(
     bench_wgs84_to_epsg3857,
     bench_epsg3857_to_wgs84,
     bench_gcj02_to_epsg3857,
-    bench_bd09_to_epsg3857
+    bench_bd09_to_epsg3857,
+    bench_batch,
+    bench_simd,
+    bench_parallel
 );
 criterion_main!(benches);
\ No newline at end of file
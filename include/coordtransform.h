@@ -0,0 +1,148 @@
+/* coordtransform C API. Generated from src/capi.rs; do not edit. */
+
+#ifndef COORDTRANSFORM_H
+#define COORDTRANSFORM_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define CT_OK 0
+#define CT_ERROR_NULL_POINTER 1
+#define CT_ERROR_UNKNOWN_SYSTEM 2
+#define CT_ERROR_NON_FINITE 3
+#define CT_ERROR_OUT_OF_RANGE 4
+#define CT_ERROR_NOT_CONVERGED 5
+
+#define CT_WGS84 0
+#define CT_GCJ02 1
+#define CT_BD09 2
+#define CT_EPSG3857 3
+
+/* Point passed to and from C: longitude/latitude or x/y. */
+typedef struct CtPoint {
+    double lon;
+    double lat;
+} CtPoint;
+
+/*
+ * Batch functions convert `len` interleaved lon, lat pairs. `input` and `output` may be
+ * the same buffer. They return CT_OK or CT_ERROR_NULL_POINTER.
+ */
+
+/* 百度坐标系 -> 火星坐标系 */
+CtPoint ct_bd09_to_gcj02(double lon, double lat);
+int32_t ct_bd09_to_gcj02_batch(const double *input, double *output, size_t len);
+
+/* 火星坐标系 -> 百度坐标系 */
+CtPoint ct_gcj02_to_bd09(double lon, double lat);
+int32_t ct_gcj02_to_bd09_batch(const double *input, double *output, size_t len);
+
+/* WGS84坐标系 -> 火星坐标系 */
+CtPoint ct_wgs84_to_gcj02(double lon, double lat);
+int32_t ct_wgs84_to_gcj02_batch(const double *input, double *output, size_t len);
+
+/* 火星坐标系 -> WGS84坐标系 */
+CtPoint ct_gcj02_to_wgs84(double lon, double lat);
+int32_t ct_gcj02_to_wgs84_batch(const double *input, double *output, size_t len);
+
+/* 百度坐标系 -> WGS84坐标系 */
+CtPoint ct_bd09_to_wgs84(double lon, double lat);
+int32_t ct_bd09_to_wgs84_batch(const double *input, double *output, size_t len);
+
+/* WGS84坐标系 -> 百度坐标系 */
+CtPoint ct_wgs84_to_bd09(double lon, double lat);
+int32_t ct_wgs84_to_bd09_batch(const double *input, double *output, size_t len);
+
+/* WGS84坐标系 -> EPSG:3857坐标系 */
+CtPoint ct_wgs84_to_epsg3857(double lon, double lat);
+int32_t ct_wgs84_to_epsg3857_batch(const double *input, double *output, size_t len);
+
+/* EPSG:3857坐标系 -> WGS84坐标系 */
+CtPoint ct_epsg3857_to_wgs84(double lon, double lat);
+int32_t ct_epsg3857_to_wgs84_batch(const double *input, double *output, size_t len);
+
+/* GCJ02坐标系 -> EPSG:3857坐标系 */
+CtPoint ct_gcj02_to_epsg3857(double lon, double lat);
+int32_t ct_gcj02_to_epsg3857_batch(const double *input, double *output, size_t len);
+
+/* EPSG:3857坐标系 -> GCJ02坐标系 */
+CtPoint ct_epsg3857_to_gcj02(double lon, double lat);
+int32_t ct_epsg3857_to_gcj02_batch(const double *input, double *output, size_t len);
+
+/* BD09坐标系 -> EPSG:3857坐标系 */
+CtPoint ct_bd09_to_epsg3857(double lon, double lat);
+int32_t ct_bd09_to_epsg3857_batch(const double *input, double *output, size_t len);
+
+/* EPSG:3857坐标系 -> BD09坐标系 */
+CtPoint ct_epsg3857_to_bd09(double lon, double lat);
+int32_t ct_epsg3857_to_bd09_batch(const double *input, double *output, size_t len);
+
+/* 火星坐标系 -> WGS84坐标系 (迭代求逆) */
+CtPoint ct_gcj02_to_wgs84_precise(double lon, double lat);
+int32_t ct_gcj02_to_wgs84_precise_batch(const double *input, double *output, size_t len);
+
+/* 百度坐标系 -> 火星坐标系 (迭代求逆) */
+CtPoint ct_bd09_to_gcj02_precise(double lon, double lat);
+int32_t ct_bd09_to_gcj02_precise_batch(const double *input, double *output, size_t len);
+
+/* 百度坐标系 -> WGS84坐标系 (迭代求逆) */
+CtPoint ct_bd09_to_wgs84_precise(double lon, double lat);
+int32_t ct_bd09_to_wgs84_precise_batch(const double *input, double *output, size_t len);
+
+/* BD09坐标系 -> EPSG:3857坐标系 (迭代求逆) */
+CtPoint ct_bd09_to_epsg3857_precise(double lon, double lat);
+int32_t ct_bd09_to_epsg3857_precise_batch(const double *input, double *output, size_t len);
+
+/* GCJ02坐标系 -> EPSG:3857坐标系 (迭代求逆) */
+CtPoint ct_gcj02_to_epsg3857_precise(double lon, double lat);
+int32_t ct_gcj02_to_epsg3857_precise_batch(const double *input, double *output, size_t len);
+
+/* 百度坐标系 -> 百度墨卡托坐标系 */
+CtPoint ct_bd09_to_bd09mc(double lon, double lat);
+int32_t ct_bd09_to_bd09mc_batch(const double *input, double *output, size_t len);
+
+/* 百度墨卡托坐标系 -> 百度坐标系 */
+CtPoint ct_bd09mc_to_bd09(double lon, double lat);
+int32_t ct_bd09mc_to_bd09_batch(const double *input, double *output, size_t len);
+
+/* WGS84坐标系 -> 百度墨卡托坐标系 */
+CtPoint ct_wgs84_to_bd09mc(double lon, double lat);
+int32_t ct_wgs84_to_bd09mc_batch(const double *input, double *output, size_t len);
+
+/* 百度墨卡托坐标系 -> WGS84坐标系 */
+CtPoint ct_bd09mc_to_wgs84(double lon, double lat);
+int32_t ct_bd09mc_to_wgs84_batch(const double *input, double *output, size_t len);
+
+/* 火星坐标系 -> 百度墨卡托坐标系 */
+CtPoint ct_gcj02_to_bd09mc(double lon, double lat);
+int32_t ct_gcj02_to_bd09mc_batch(const double *input, double *output, size_t len);
+
+/* 百度墨卡托坐标系 -> 火星坐标系 */
+CtPoint ct_bd09mc_to_gcj02(double lon, double lat);
+int32_t ct_bd09mc_to_gcj02_batch(const double *input, double *output, size_t len);
+
+/* Conversion between any two systems; returns CT_OK or an error code. */
+int32_t ct_convert(int32_t from, int32_t to, double lon, double lat, CtPoint *out);
+
+/* Validated conversion; `out` is left untouched on failure. */
+int32_t ct_try_convert(int32_t from, int32_t to, double lon, double lat, CtPoint *out);
+
+/* Batch conversion between any two systems. */
+int32_t ct_convert_batch(int32_t from, int32_t to, const double *input, double *output,
+                         size_t len);
+
+/* Static English description of an error code. */
+const char *ct_error_message(int32_t code);
+
+/* Static library version string. */
+const char *ct_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* COORDTRANSFORM_H */
@@ -0,0 +1,331 @@
+//! coordtransform的Python绑定
+//! Python bindings for coordtransform
+//!
+//! 每种转换都导出为三个函数：标量版本 `<转换>(lon, lat)`、返回新数组的 `<转换>_array(coords)`
+//! 以及原地转换的 `<转换>_in_place(coords)`。数组为形状 `(n, 2)` 的C连续 `float64` 数组，
+//! 直接读写NumPy缓冲区而不复制，计算期间释放GIL。`convert`、`convert_array` 与
+//! `convert_in_place` 按坐标系名称分派，接受与Rust `CoordSystem` 相同的名称。
+//!
+//! Every conversion is exported as three functions: the scalar `<conversion>(lon, lat)`,
+//! `<conversion>_array(coords)` returning a new array and `<conversion>_in_place(coords)`. Arrays
+//! are C-contiguous `float64` arrays of shape `(n, 2)`; the NumPy buffers are read and written
+//! directly without copying and the GIL is released while converting. `convert`, `convert_array`
+//! and `convert_in_place` dispatch on coordinate system names and accept the same names as the
+//! Rust `CoordSystem`.
+//!
+//! ```python
+//! import numpy as np
+//! import coordtransform as ct
+//!
+//! lon, lat = ct.wgs84_to_gcj02(116.404, 39.915)
+//! coords = np.array([[116.404, 39.915], [121.4737, 31.2304]])
+//! bd09 = ct.convert_array("wgs84", "bd09", coords)
+//! ct.gcj02_to_wgs84_in_place(coords)
+//! ```
+
+use coordtransform_rs::CoordSystem;
+use numpy::{PyArray1, PyArray2, PyArrayMethods, PyUntypedArrayMethods};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// 生成标量、数组及原地转换三个Python函数并注册到模块
+/// Generate the scalar, array and in-place Python functions and register them on the module
+macro_rules! py_conversions {
+    ($($name:ident, $array:ident, $in_place:ident, $title:literal;)*) => {
+        $(
+            #[doc = concat!($title, "\n\nScalar version of `", stringify!($name), "`.")]
+            #[pyfunction]
+            fn $name(lon: f64, lat: f64) -> (f64, f64) {
+                coordtransform_rs::$name(lon, lat)
+            }
+
+            #[doc = concat!($title, "，返回新数组\n\nConvert an `(n, 2)` array into a new array.")]
+            #[pyfunction]
+            fn $array<'py>(
+                py: Python<'py>,
+                coords: &Bound<'py, PyArray2<f64>>,
+            ) -> PyResult<Bound<'py, PyArray2<f64>>> {
+                map_array(py, coords, coordtransform_rs::$name)
+            }
+
+            #[doc = concat!($title, "，原地转换\n\nConvert an `(n, 2)` array in place.")]
+            #[pyfunction]
+            fn $in_place(py: Python<'_>, coords: &Bound<'_, PyArray2<f64>>) -> PyResult<()> {
+                map_in_place(py, coords, coordtransform_rs::$name)
+            }
+        )*
+
+        fn add_conversions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+            $(
+                m.add_function(wrap_pyfunction!($name, m)?)?;
+                m.add_function(wrap_pyfunction!($array, m)?)?;
+                m.add_function(wrap_pyfunction!($in_place, m)?)?;
+            )*
+            Ok(())
+        }
+
+        /// 导出的转换及对应的Rust函数 Exported conversions and their Rust functions
+        #[cfg(test)]
+        const CONVERSIONS: &[(&str, fn(f64, f64) -> (f64, f64))] = &[
+            $((stringify!($name), coordtransform_rs::$name)),*
+        ];
+    };
+}
+
+py_conversions! {
+    bd09_to_gcj02, bd09_to_gcj02_array, bd09_to_gcj02_in_place, "百度坐标系 -> 火星坐标系";
+    gcj02_to_bd09, gcj02_to_bd09_array, gcj02_to_bd09_in_place, "火星坐标系 -> 百度坐标系";
+    wgs84_to_gcj02, wgs84_to_gcj02_array, wgs84_to_gcj02_in_place, "WGS84坐标系 -> 火星坐标系";
+    gcj02_to_wgs84, gcj02_to_wgs84_array, gcj02_to_wgs84_in_place, "火星坐标系 -> WGS84坐标系";
+    bd09_to_wgs84, bd09_to_wgs84_array, bd09_to_wgs84_in_place, "百度坐标系 -> WGS84坐标系";
+    wgs84_to_bd09, wgs84_to_bd09_array, wgs84_to_bd09_in_place, "WGS84坐标系 -> 百度坐标系";
+    wgs84_to_epsg3857, wgs84_to_epsg3857_array, wgs84_to_epsg3857_in_place, "WGS84坐标系 -> EPSG:3857坐标系";
+    epsg3857_to_wgs84, epsg3857_to_wgs84_array, epsg3857_to_wgs84_in_place, "EPSG:3857坐标系 -> WGS84坐标系";
+    gcj02_to_epsg3857, gcj02_to_epsg3857_array, gcj02_to_epsg3857_in_place, "GCJ02坐标系 -> EPSG:3857坐标系";
+    epsg3857_to_gcj02, epsg3857_to_gcj02_array, epsg3857_to_gcj02_in_place, "EPSG:3857坐标系 -> GCJ02坐标系";
+    bd09_to_epsg3857, bd09_to_epsg3857_array, bd09_to_epsg3857_in_place, "BD09坐标系 -> EPSG:3857坐标系";
+    epsg3857_to_bd09, epsg3857_to_bd09_array, epsg3857_to_bd09_in_place, "EPSG:3857坐标系 -> BD09坐标系";
+    gcj02_to_wgs84_precise, gcj02_to_wgs84_precise_array, gcj02_to_wgs84_precise_in_place, "火星坐标系 -> WGS84坐标系 (迭代求逆)";
+    bd09_to_gcj02_precise, bd09_to_gcj02_precise_array, bd09_to_gcj02_precise_in_place, "百度坐标系 -> 火星坐标系 (迭代求逆)";
+    bd09_to_wgs84_precise, bd09_to_wgs84_precise_array, bd09_to_wgs84_precise_in_place, "百度坐标系 -> WGS84坐标系 (迭代求逆)";
+    bd09_to_epsg3857_precise, bd09_to_epsg3857_precise_array, bd09_to_epsg3857_precise_in_place, "BD09坐标系 -> EPSG:3857坐标系 (迭代求逆)";
+    gcj02_to_epsg3857_precise, gcj02_to_epsg3857_precise_array, gcj02_to_epsg3857_precise_in_place, "GCJ02坐标系 -> EPSG:3857坐标系 (迭代求逆)";
+    bd09_to_bd09mc, bd09_to_bd09mc_array, bd09_to_bd09mc_in_place, "百度坐标系 -> 百度墨卡托坐标系";
+    bd09mc_to_bd09, bd09mc_to_bd09_array, bd09mc_to_bd09_in_place, "百度墨卡托坐标系 -> 百度坐标系";
+    wgs84_to_bd09mc, wgs84_to_bd09mc_array, wgs84_to_bd09mc_in_place, "WGS84坐标系 -> 百度墨卡托坐标系";
+    bd09mc_to_wgs84, bd09mc_to_wgs84_array, bd09mc_to_wgs84_in_place, "百度墨卡托坐标系 -> WGS84坐标系";
+    gcj02_to_bd09mc, gcj02_to_bd09mc_array, gcj02_to_bd09mc_in_place, "火星坐标系 -> 百度墨卡托坐标系";
+    bd09mc_to_gcj02, bd09mc_to_gcj02_array, bd09mc_to_gcj02_in_place, "百度墨卡托坐标系 -> 火星坐标系";
+}
+
+/// 任意两个坐标系之间的转换，坐标系以名称指定，例如 `"wgs84"`、`"gcj02"`、`"bd09"`、`"epsg:3857"`
+///
+/// Conversion between any two coordinate systems given by name, e.g. `"wgs84"`, `"gcj02"`,
+/// `"bd09"` or `"epsg:3857"`.
+#[pyfunction]
+fn convert(source: &str, target: &str, lon: f64, lat: f64) -> PyResult<(f64, f64)> {
+    let (from, to) = (system(source)?, system(target)?);
+    Ok(coordtransform_rs::convert(from, to, lon, lat))
+}
+
+/// 按名称指定坐标系转换 `(n, 2)` 数组，返回新数组
+///
+/// Convert an `(n, 2)` array between systems given by name into a new array.
+#[pyfunction]
+fn convert_array<'py>(
+    py: Python<'py>,
+    source: &str,
+    target: &str,
+    coords: &Bound<'py, PyArray2<f64>>,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    let (from, to) = (system(source)?, system(target)?);
+    map_array(py, coords, move |lon, lat| {
+        coordtransform_rs::convert(from, to, lon, lat)
+    })
+}
+
+/// 按名称指定坐标系原地转换 `(n, 2)` 数组
+///
+/// Convert an `(n, 2)` array in place between systems given by name.
+#[pyfunction]
+fn convert_in_place(
+    py: Python<'_>,
+    source: &str,
+    target: &str,
+    coords: &Bound<'_, PyArray2<f64>>,
+) -> PyResult<()> {
+    let (from, to) = (system(source)?, system(target)?);
+    map_in_place(py, coords, move |lon, lat| {
+        coordtransform_rs::convert(from, to, lon, lat)
+    })
+}
+
+/// 规范化坐标系名称，例如 `"gps"` -> `"WGS84"`
+///
+/// Canonical name of a coordinate system, e.g. `"gps"` -> `"WGS84"`.
+#[pyfunction]
+fn normalize_system(name: &str) -> PyResult<String> {
+    Ok(system(name)?.to_string())
+}
+
+fn system(name: &str) -> PyResult<CoordSystem> {
+    name.parse()
+        .map_err(|_| PyValueError::new_err(format!("unknown coordinate system `{}`", name)))
+}
+
+/// 校验数组为形状 `(n, 2)` 的C连续数组；Fortran顺序的数组虽然连续，但经纬度不相邻
+/// Check that an array is C-contiguous with shape `(n, 2)`; Fortran-ordered arrays are contiguous
+/// too, but their longitudes and latitudes are not adjacent
+fn check_layout(shape: &[usize], c_contiguous: bool) -> PyResult<()> {
+    if !matches!(shape, [_, 2]) {
+        return Err(PyValueError::new_err(format!(
+            "expected an array of shape (n, 2), got {:?}",
+            shape
+        )));
+    }
+    if !c_contiguous {
+        return Err(PyValueError::new_err("expected a C-contiguous array"));
+    }
+    Ok(())
+}
+
+fn map_array<'py, F>(
+    py: Python<'py>,
+    coords: &Bound<'py, PyArray2<f64>>,
+    f: F,
+) -> PyResult<Bound<'py, PyArray2<f64>>>
+where
+    F: Fn(f64, f64) -> (f64, f64) + Send,
+{
+    check_layout(coords.shape(), coords.is_c_contiguous())?;
+    let coords = coords.try_readonly()?;
+    let input = coords.as_slice()?;
+    let output = py.detach(move || {
+        let mut output = Vec::with_capacity(input.len());
+        for point in input.chunks_exact(2) {
+            let (lon, lat) = f(point[0], point[1]);
+            output.extend([lon, lat]);
+        }
+        output
+    });
+    // 结果向量直接移交给NumPy The result vector is handed over to NumPy without copying
+    PyArray1::from_vec(py, output).reshape([input.len() / 2, 2])
+}
+
+fn map_in_place<F>(py: Python<'_>, coords: &Bound<'_, PyArray2<f64>>, f: F) -> PyResult<()>
+where
+    F: Fn(f64, f64) -> (f64, f64) + Send,
+{
+    check_layout(coords.shape(), coords.is_c_contiguous())?;
+    let mut coords = coords.try_readwrite()?;
+    let points = coords.as_slice_mut()?;
+    py.detach(move || {
+        for point in points.chunks_exact_mut(2) {
+            let (lon, lat) = f(point[0], point[1]);
+            point[0] = lon;
+            point[1] = lat;
+        }
+    });
+    Ok(())
+}
+
+#[pymodule]
+fn coordtransform(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    add_conversions(m)?;
+    m.add_function(wrap_pyfunction!(convert, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_array, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_in_place, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_system, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 由Rust结果生成pytest使用的参考数据
+    /// Generate the reference data used by pytest from the Rust results
+    fn reference() -> String {
+        let points = [
+            (116.404, 39.915),
+            (121.4737, 31.2304),
+            (113.2644, 23.1291),
+            (87.6168, 43.8256),
+            (126.5349, 45.8038),
+        ];
+        let systems = [
+            CoordSystem::Wgs84,
+            CoordSystem::Gcj02,
+            CoordSystem::Bd09,
+            CoordSystem::Epsg3857,
+        ];
+        let list = |f: &dyn Fn(f64, f64) -> (f64, f64)| {
+            let items: Vec<String> = points
+                .iter()
+                .map(|&(lon, lat)| {
+                    let (x, y) = f(lon, lat);
+                    format!("[{:?}, {:?}]", x, y)
+                })
+                .collect();
+            format!("[{}]", items.join(", "))
+        };
+
+        let mut entries = vec![format!("  \"points\": {}", list(&|lon, lat| (lon, lat)))];
+        let conversions: Vec<String> = CONVERSIONS
+            .iter()
+            .map(|(name, f)| format!("    \"{}\": {}", name, list(f)))
+            .collect();
+        entries.push(format!(
+            "  \"conversions\": {{\n{}\n  }}",
+            conversions.join(",\n")
+        ));
+        let mut pairs = Vec::new();
+        for from in systems {
+            for to in systems {
+                let f = |lon, lat| coordtransform_rs::convert(from, to, lon, lat);
+                pairs.push(format!("    \"{} {}\": {}", from, to, list(&f)));
+            }
+        }
+        entries.push(format!("  \"convert\": {{\n{}\n  }}", pairs.join(",\n")));
+        format!("{{\n{}\n}}\n", entries.join(",\n"))
+    }
+
+    /// 一行参考数据中的数值 Numbers on one line of the reference data
+    fn numbers(line: &str) -> Vec<f64> {
+        line.split(['[', ']', ',', ' '])
+            .filter_map(|token| token.parse().ok())
+            .collect()
+    }
+
+    /// 参考数据须与Rust结果一致；设置 `COORDTRANSFORM_BLESS=1` 重新生成。
+    /// 浮点函数的末位结果随平台及 `libm` 特性而异，因此按相对误差1e-12比较。
+    ///
+    /// The reference data must match the Rust results; set `COORDTRANSFORM_BLESS=1` to regenerate
+    /// it. The last bits of the floating-point functions vary with the platform and the `libm`
+    /// feature, so values are compared with a relative tolerance of 1e-12.
+    #[test]
+    fn test_reference_is_current() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/reference.json");
+        let generated = reference();
+        if std::env::var_os("COORDTRANSFORM_BLESS").is_some() {
+            std::fs::write(path, &generated).unwrap();
+        }
+        let current = std::fs::read_to_string(path).unwrap_or_default();
+        let stale = "python/tests/reference.json is out of date; rerun with COORDTRANSFORM_BLESS=1";
+        assert_eq!(
+            current.lines().count(),
+            generated.lines().count(),
+            "{}",
+            stale
+        );
+        for (current, generated) in current.lines().zip(generated.lines()) {
+            assert_eq!(
+                current.split(": ").next(),
+                generated.split(": ").next(),
+                "{}",
+                stale
+            );
+            let (current, generated) = (numbers(current), numbers(generated));
+            assert_eq!(current.len(), generated.len(), "{}", stale);
+            for (a, b) in current.into_iter().zip(generated) {
+                assert!(
+                    (a - b).abs() <= 1e-12 * b.abs().max(1.0),
+                    "{}: {} != {}",
+                    stale,
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_layout() {
+        assert!(check_layout(&[3, 2], true).is_ok());
+        assert!(check_layout(&[0, 2], true).is_ok());
+        assert!(check_layout(&[3, 2], false).is_err());
+        assert!(check_layout(&[3, 3], true).is_err());
+        assert!(check_layout(&[6], true).is_err());
+    }
+}
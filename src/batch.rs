@@ -0,0 +1,211 @@
+//! 批量坐标转换
+//! Batch coordinate conversion
+//!
+//! 每种转换都有两个批量版本：写入输出切片的版本，以及原地转换的 `_in_place` 版本。
+//! Every conversion has two batch variants: one writing into an output slice and an `_in_place`
+//! variant.
+//!
+//! ```rust
+//! use coordtransform::batch;
+//!
+//! let input = [(116.404, 39.915), (121.4737, 31.2304)];
+//! let mut output = [(0.0, 0.0); 2];
+//! batch::wgs84_to_gcj02(&input, &mut output);
+//!
+//! let mut points = input;
+//! batch::wgs84_to_gcj02_in_place(&mut points);
+//! assert_eq!(points, output);
+//! ```
+
+use crate::CoordSystem;
+
+/// 生成一对批量转换函数
+/// Generate a pair of batch conversion functions
+macro_rules! batch_conversion {
+    ($name:ident, $in_place:ident, $title:literal) => {
+        #[doc = concat!($title, "，批量转换到输出切片")]
+        #[doc = ""]
+        #[doc = concat!("Batch version of [`", stringify!($name), "`](crate::", stringify!($name), ") writing into an output slice.")]
+        #[doc = ""]
+        #[doc = "# Panics"]
+        #[doc = ""]
+        #[doc = "输入与输出长度不同时 panic Panics if `input` and `output` differ in length"]
+        pub fn $name(input: &[(f64, f64)], output: &mut [(f64, f64)]) {
+            map_into(input, output, crate::$name);
+        }
+
+        #[doc = concat!($title, "，原地批量转换")]
+        #[doc = ""]
+        #[doc = concat!("Batch version of [`", stringify!($name), "`](crate::", stringify!($name), ") converting in place.")]
+        pub fn $in_place(points: &mut [(f64, f64)]) {
+            map_in_place(points, crate::$name);
+        }
+    };
+}
+
+batch_conversion!(
+    bd09_to_gcj02,
+    bd09_to_gcj02_in_place,
+    "百度坐标系 -> 火星坐标系"
+);
+batch_conversion!(
+    gcj02_to_bd09,
+    gcj02_to_bd09_in_place,
+    "火星坐标系 -> 百度坐标系"
+);
+batch_conversion!(
+    wgs84_to_gcj02,
+    wgs84_to_gcj02_in_place,
+    "WGS84坐标系 -> 火星坐标系"
+);
+batch_conversion!(
+    gcj02_to_wgs84,
+    gcj02_to_wgs84_in_place,
+    "火星坐标系 -> WGS84坐标系"
+);
+batch_conversion!(
+    bd09_to_wgs84,
+    bd09_to_wgs84_in_place,
+    "百度坐标系 -> WGS84坐标系"
+);
+batch_conversion!(
+    wgs84_to_bd09,
+    wgs84_to_bd09_in_place,
+    "WGS84坐标系 -> 百度坐标系"
+);
+batch_conversion!(
+    wgs84_to_epsg3857,
+    wgs84_to_epsg3857_in_place,
+    "WGS84坐标系 -> EPSG:3857坐标系"
+);
+batch_conversion!(
+    epsg3857_to_wgs84,
+    epsg3857_to_wgs84_in_place,
+    "EPSG:3857坐标系 -> WGS84坐标系"
+);
+batch_conversion!(
+    gcj02_to_epsg3857,
+    gcj02_to_epsg3857_in_place,
+    "GCJ02坐标系 -> EPSG:3857坐标系"
+);
+batch_conversion!(
+    epsg3857_to_gcj02,
+    epsg3857_to_gcj02_in_place,
+    "EPSG:3857坐标系 -> GCJ02坐标系"
+);
+batch_conversion!(
+    bd09_to_epsg3857,
+    bd09_to_epsg3857_in_place,
+    "BD09坐标系 -> EPSG:3857坐标系"
+);
+batch_conversion!(
+    epsg3857_to_bd09,
+    epsg3857_to_bd09_in_place,
+    "EPSG:3857坐标系 -> BD09坐标系"
+);
+
+/// 任意两个坐标系之间的批量转换
+/// Batch conversion between any two coordinate systems
+///
+/// # Panics
+///
+/// 输入与输出长度不同时 panic Panics if `input` and `output` differ in length
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{batch, CoordSystem};
+///
+/// let input = [(116.404, 39.915)];
+/// let mut output = [(0.0, 0.0)];
+/// batch::convert(CoordSystem::Bd09, CoordSystem::Wgs84, &input, &mut output);
+/// ```
+pub fn convert(
+    from: CoordSystem,
+    to: CoordSystem,
+    input: &[(f64, f64)],
+    output: &mut [(f64, f64)],
+) {
+    map_into(input, output, |lon, lat| crate::convert(from, to, lon, lat));
+}
+
+/// 任意两个坐标系之间的原地批量转换
+/// In-place batch conversion between any two coordinate systems
+pub fn convert_in_place(from: CoordSystem, to: CoordSystem, points: &mut [(f64, f64)]) {
+    map_in_place(points, |lon, lat| crate::convert(from, to, lon, lat));
+}
+
+fn map_into<F>(input: &[(f64, f64)], output: &mut [(f64, f64)], f: F)
+where
+    F: Fn(f64, f64) -> (f64, f64),
+{
+    assert_eq!(
+        input.len(),
+        output.len(),
+        "input and output slices must have the same length"
+    );
+    for (out, &(lon, lat)) in output.iter_mut().zip(input) {
+        *out = f(lon, lat);
+    }
+}
+
+fn map_in_place<F>(points: &mut [(f64, f64)], f: F)
+where
+    F: Fn(f64, f64) -> (f64, f64),
+{
+    for point in points.iter_mut() {
+        *point = f(point.0, point.1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POINTS: [(f64, f64); 3] = [(116.404, 39.915), (121.4737, 31.2304), (0.0, 0.0)];
+
+    #[test]
+    fn test_batch_matches_scalar() {
+        let mut output = [(0.0, 0.0); 3];
+        wgs84_to_bd09(&POINTS, &mut output);
+        for (&(lon, lat), &out) in POINTS.iter().zip(output.iter()) {
+            assert_eq!(out, crate::wgs84_to_bd09(lon, lat));
+        }
+
+        let mut points = POINTS;
+        gcj02_to_wgs84_in_place(&mut points);
+        for (&(lon, lat), &out) in POINTS.iter().zip(points.iter()) {
+            assert_eq!(out, crate::gcj02_to_wgs84(lon, lat));
+        }
+    }
+
+    #[test]
+    fn test_batch_epsg3857() {
+        let mut meters = [(0.0, 0.0); 3];
+        bd09_to_epsg3857(&POINTS, &mut meters);
+        assert_eq!(meters[0], crate::bd09_to_epsg3857(116.404, 39.915));
+
+        epsg3857_to_bd09_in_place(&mut meters);
+        assert!((meters[0].0 - 116.404).abs() < 1e-4);
+        assert!((meters[0].1 - 39.915).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_batch_convert() {
+        let mut output = [(0.0, 0.0); 3];
+        convert(CoordSystem::Wgs84, CoordSystem::Gcj02, &POINTS, &mut output);
+        let mut points = POINTS;
+        wgs84_to_gcj02_in_place(&mut points);
+        assert_eq!(output, points);
+
+        convert_in_place(CoordSystem::Gcj02, CoordSystem::Gcj02, &mut points);
+        assert_eq!(output, points);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_batch_length_mismatch() {
+        let mut output = [(0.0, 0.0); 2];
+        wgs84_to_gcj02(&POINTS, &mut output);
+    }
+}
@@ -0,0 +1,456 @@
+//! 百度墨卡托投影 (BD09MC)
+//! Baidu Mercator projection (BD09MC)
+//!
+//! 百度地图API及瓦片服务使用的平面坐标并非EPSG:3857，而是百度按纬度分带拟合的多项式墨卡托投影。
+//! 本模块使用百度地图JavaScript API中的分带系数：正向按纬度 (75°、60°、45°、30°、15°、0°)
+//! 分带，逆向按y坐标对应的边界分带，每带为x的一次式与y的六次多项式。
+//!
+//! Baidu's map APIs and tile servers do not use EPSG:3857 but Baidu's own Mercator, fitted with
+//! polynomials per latitude band. This module uses the band coefficients of Baidu's JavaScript
+//! API: the forward projection picks the band by latitude (75°, 60°, 45°, 30°, 15°, 0°) and the
+//! inverse by the matching y boundaries; each band is linear in x and a sextic polynomial in y.
+//!
+//! 两个方向的多项式各自独立拟合，往返误差在中国范围内小于1e-6°；相邻分带在边界处不完全连续
+//! (60°处约15米)，与百度的实现一致。纬度限制在 ±74° 内。与百度JavaScript API的 `convertLL2MC`
+//! 一致，南半球纬度在正向转换中始终使用0°分带，逆向转换则按 `|y|` 选带，因此南半球中高纬度的往返
+//! 并不精确。
+//!
+//! The two directions are fitted independently, so round trips within China are accurate to
+//! 1e-6°; neighbouring bands are not exactly continuous at their boundaries (about 15 m at 60°),
+//! as in Baidu's implementation. Latitudes are clamped to ±74°. Like `convertLL2MC` in Baidu's
+//! JavaScript API, the forward projection always uses the 0° band for southern latitudes while
+//! the inverse picks its band by `|y|`, so round trips are inexact at mid and high southern
+//! latitudes.
+
+use crate::{bd09_to_gcj02, bd09_to_wgs84, gcj02_to_bd09, wgs84_to_bd09};
+
+/// 正向转换的纬度分带边界 (度)
+/// Latitude band boundaries of the forward projection in degrees
+const LL_BAND: [f64; 6] = [75.0, 60.0, 45.0, 30.0, 15.0, 0.0];
+
+/// 逆向转换的y分带边界 (米)
+/// y band boundaries of the inverse projection in meters
+const MC_BAND: [f64; 6] = [
+    12890594.86,
+    8362377.87,
+    5591021.0,
+    3481989.83,
+    1678043.12,
+    0.0,
+];
+
+/// 正向转换系数 Forward coefficients
+#[allow(clippy::excessive_precision)]
+const LL_TO_MC: [[f64; 10]; 6] = [
+    [
+        -0.0015702102444,
+        111320.7020616939,
+        1704480524535203.0,
+        -10338987376042340.0,
+        26112667856603880.0,
+        -35149669176653700.0,
+        26595700718403920.0,
+        -10725012454188240.0,
+        1800819912950474.0,
+        82.5,
+    ],
+    [
+        0.0008277824516172526,
+        111320.7020463578,
+        647795574.6671607,
+        -4082003173.641316,
+        10774905663.51142,
+        -15171875531.51559,
+        12053065338.62167,
+        -5124939663.577472,
+        913311935.9512032,
+        67.5,
+    ],
+    [
+        0.00337398766765,
+        111320.7020202162,
+        4481351.045890365,
+        -23393751.19931662,
+        79682215.47186455,
+        -115964993.2797253,
+        97236711.15602145,
+        -43661946.33752821,
+        8477230.501135234,
+        52.5,
+    ],
+    [
+        0.00220636496208,
+        111320.7020209128,
+        51751.86112841131,
+        3796837.749470245,
+        992013.7397791013,
+        -1221952.21711287,
+        1340652.697009075,
+        -620943.6990984312,
+        144416.9293806241,
+        37.5,
+    ],
+    [
+        -0.0003441963504368392,
+        111320.7020576856,
+        278.2353980772752,
+        2485758.690035394,
+        6070.750963243378,
+        54821.18345352118,
+        9540.606633304236,
+        -2710.55326746645,
+        1405.483844121726,
+        22.5,
+    ],
+    [
+        -0.0003218135878613132,
+        111320.7020701615,
+        0.00369383431289,
+        823725.6402795718,
+        0.46104986909093,
+        2351.343141331292,
+        1.58060784298199,
+        8.77738589078284,
+        0.37238884252424,
+        7.45,
+    ],
+];
+
+/// 逆向转换系数 Inverse coefficients
+#[allow(clippy::excessive_precision)]
+const MC_TO_LL: [[f64; 10]; 6] = [
+    [
+        1.410526172116255e-8,
+        0.00000898305509648872,
+        -1.9939833816331,
+        200.9824383106796,
+        -187.2403703815547,
+        91.6087516669843,
+        -23.38765649603339,
+        2.57121317296198,
+        -0.03801003308653,
+        17337981.2,
+    ],
+    [
+        -7.435856389565537e-9,
+        0.000008983055097726239,
+        -0.78625201886289,
+        96.32687599759846,
+        -1.85204757529826,
+        -59.36935905485877,
+        47.40033549296737,
+        -16.50741931063887,
+        2.28786674699375,
+        10260144.86,
+    ],
+    [
+        -3.030883460898826e-8,
+        0.00000898305509983578,
+        0.30071316287616,
+        59.74293618442277,
+        7.357984074871,
+        -25.38371002664745,
+        13.45380521110908,
+        -3.29883767235584,
+        0.32710905363475,
+        6856817.37,
+    ],
+    [
+        -1.981981304930552e-8,
+        0.000008983055099779535,
+        0.03278182852591,
+        40.31678527705744,
+        0.65659298677277,
+        -4.44255534477492,
+        0.85341911805263,
+        0.12923347998204,
+        -0.04625736007561,
+        4482777.06,
+    ],
+    [
+        3.09191371068437e-9,
+        0.000008983055096812155,
+        0.00006995724062,
+        23.10934304144901,
+        -0.00023663490511,
+        -0.6321817810242,
+        -0.00663494467273,
+        0.03430082397953,
+        -0.00466043876332,
+        2555164.4,
+    ],
+    [
+        2.890871144776878e-9,
+        0.000008983055095805407,
+        -3.068298e-8,
+        7.47137025468032,
+        -0.00000353937994,
+        -0.02145144861037,
+        -0.00001234426596,
+        0.00010322952773,
+        -0.00000323890364,
+        826088.5,
+    ],
+];
+
+/// 正向纬度限制 (度) Forward latitude limit in degrees
+const MAX_LATITUDE: f64 = 74.0;
+
+/// 按分带系数计算：x为一次式，y为 `|y| / c[9]` 的六次多项式，符号与输入相同
+/// Evaluate one band: linear in x, sextic in `|y| / c[9]`, keeping the signs of the input
+fn polynomial(x: f64, y: f64, c: &[f64; 10]) -> (f64, f64) {
+    let out_x = c[0] + c[1] * x.abs();
+    let t = y.abs() / c[9];
+    let out_y = c[2] + t * (c[3] + t * (c[4] + t * (c[5] + t * (c[6] + t * (c[7] + t * c[8])))));
+    (out_x.copysign(x), out_y.copysign(y))
+}
+
+/// 按边界选择分带，负值与NaN落入最后一带 (边界为0)
+/// Pick the band for a value; negative values and NaN fall into the last band (boundary 0)
+fn band(bounds: &[f64; 6], value: f64) -> usize {
+    bounds
+        .iter()
+        .position(|&bound| value >= bound)
+        .unwrap_or(bounds.len() - 1)
+}
+
+/// 百度坐标系 -> 百度墨卡托坐标系
+/// BD09 Coordinate System -> Baidu Mercator (BD09MC)
+///
+/// 经度规范到 [-180, 180]，纬度限制在 ±74° 内。
+/// The longitude is wrapped into [-180, 180] and the latitude clamped to ±74°.
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude (度 degrees)
+/// * `lat` - 纬度 Latitude (度 degrees)
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的 (X, Y) 元组 (米) Returns a tuple of (X, Y) in meters after conversion
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::bd09_to_bd09mc;
+///
+/// let (x, y) = bd09_to_bd09mc(116.404, 39.915);
+/// assert!((x - 12958175.0).abs() < 0.01 && (y - 4825923.77).abs() < 0.01);
+/// ```
+pub fn bd09_to_bd09mc(lon: f64, lat: f64) -> (f64, f64) {
+    let lon = if (-180.0..=180.0).contains(&lon) {
+        lon
+    } else {
+        (lon + 180.0) % 360.0 + if lon < -180.0 { 180.0 } else { -180.0 }
+    };
+    let lat = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE);
+    polynomial(lon, lat, &LL_TO_MC[band(&LL_BAND, lat)])
+}
+
+/// 百度墨卡托坐标系 -> 百度坐标系
+/// Baidu Mercator (BD09MC) -> BD09 Coordinate System
+///
+/// # Parameters 参数
+///
+/// * `x` - X坐标 X coordinate (米 meters)
+/// * `y` - Y坐标 Y coordinate (米 meters)
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的 (经度, 纬度) 元组 (度) Returns a tuple of (longitude, latitude) in degrees after conversion
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::bd09mc_to_bd09;
+///
+/// let (lon, lat) = bd09mc_to_bd09(12958175.0, 4825923.77);
+/// assert!((lon - 116.404).abs() < 1e-6 && (lat - 39.915).abs() < 1e-6);
+/// ```
+pub fn bd09mc_to_bd09(x: f64, y: f64) -> (f64, f64) {
+    polynomial(x, y, &MC_TO_LL[band(&MC_BAND, y.abs())])
+}
+
+/// WGS84坐标系 -> 百度墨卡托坐标系
+/// WGS84 Coordinate System -> Baidu Mercator (BD09MC)
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude (度 degrees)
+/// * `lat` - 纬度 Latitude (度 degrees)
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{bd09_to_bd09mc, wgs84_to_bd09, wgs84_to_bd09mc};
+///
+/// let (lon, lat) = wgs84_to_bd09(116.404, 39.915);
+/// assert_eq!(wgs84_to_bd09mc(116.404, 39.915), bd09_to_bd09mc(lon, lat));
+/// ```
+pub fn wgs84_to_bd09mc(lon: f64, lat: f64) -> (f64, f64) {
+    let (bd_lon, bd_lat) = wgs84_to_bd09(lon, lat);
+    bd09_to_bd09mc(bd_lon, bd_lat)
+}
+
+/// 百度墨卡托坐标系 -> WGS84坐标系
+/// Baidu Mercator (BD09MC) -> WGS84 Coordinate System
+///
+/// # Parameters 参数
+///
+/// * `x` - X坐标 X coordinate (米 meters)
+/// * `y` - Y坐标 Y coordinate (米 meters)
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::bd09mc_to_wgs84;
+///
+/// let (lon, lat) = bd09mc_to_wgs84(12958175.0, 4825923.77);
+/// ```
+pub fn bd09mc_to_wgs84(x: f64, y: f64) -> (f64, f64) {
+    let (bd_lon, bd_lat) = bd09mc_to_bd09(x, y);
+    bd09_to_wgs84(bd_lon, bd_lat)
+}
+
+/// 火星坐标系 -> 百度墨卡托坐标系
+/// GCJ02 Coordinate System -> Baidu Mercator (BD09MC)
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude (度 degrees)
+/// * `lat` - 纬度 Latitude (度 degrees)
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::gcj02_to_bd09mc;
+///
+/// let (x, y) = gcj02_to_bd09mc(116.404, 39.915);
+/// ```
+pub fn gcj02_to_bd09mc(lon: f64, lat: f64) -> (f64, f64) {
+    let (bd_lon, bd_lat) = gcj02_to_bd09(lon, lat);
+    bd09_to_bd09mc(bd_lon, bd_lat)
+}
+
+/// 百度墨卡托坐标系 -> 火星坐标系
+/// Baidu Mercator (BD09MC) -> GCJ02 Coordinate System
+///
+/// # Parameters 参数
+///
+/// * `x` - X坐标 X coordinate (米 meters)
+/// * `y` - Y坐标 Y coordinate (米 meters)
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::bd09mc_to_gcj02;
+///
+/// let (lon, lat) = bd09mc_to_gcj02(12958175.0, 4825923.77);
+/// ```
+pub fn bd09mc_to_gcj02(x: f64, y: f64) -> (f64, f64) {
+    let (bd_lon, bd_lat) = bd09mc_to_bd09(x, y);
+    bd09_to_gcj02(bd_lon, bd_lat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_points() {
+        // 百度地图API示例中心点 天安门 The Tiananmen center point of Baidu's API samples
+        let (x, y) = bd09_to_bd09mc(116.404, 39.915);
+        assert!((x - 12958175.0).abs() < 0.01, "x = {}", x);
+        assert!((y - 4825923.77).abs() < 0.01, "y = {}", y);
+
+        // 各纬度带的回归样本，由百度JavaScript API的算法独立计算
+        // Regression samples in each latitude band, computed independently with the algorithm
+        // of Baidu's JavaScript API
+        let samples = [
+            ((121.4737, 31.2304), (13522537.563284118, 3640349.706208589)),
+            (
+                (113.2644, 23.1291),
+                (12608672.525798328, 2630703.3815671867),
+            ),
+            ((126.5349, 45.8038), (14085953.901431842, 5718037.601469219)),
+            ((103.85, 1.29), (11560654.909664458, 142643.9155702586)),
+        ];
+        for ((lon, lat), (ex, ey)) in samples {
+            let (x, y) = bd09_to_bd09mc(lon, lat);
+            assert!(
+                (x - ex).abs() < 1e-3 && (y - ey).abs() < 1e-3,
+                "{} {}",
+                lon,
+                lat
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (lon, lat) in crate::tests::china_grid() {
+            let (x, y) = bd09_to_bd09mc(lon, lat);
+            let (back_lon, back_lat) = bd09mc_to_bd09(x, y);
+            assert!(
+                (back_lon - lon).abs() < 1e-6 && (back_lat - lat).abs() < 1e-6,
+                "{} {}",
+                lon,
+                lat
+            );
+        }
+    }
+
+    #[test]
+    fn test_symmetry_and_limits() {
+        let (x, y) = bd09_to_bd09mc(116.404, 39.915);
+        assert_eq!(bd09_to_bd09mc(-116.404, 39.915), (-x, y));
+        assert_eq!(bd09mc_to_bd09(-x, -y), {
+            let (lon, lat) = bd09mc_to_bd09(x, y);
+            (-lon, -lat)
+        });
+        assert_eq!(bd09_to_bd09mc(116.404 + 360.0, 39.915), (x, y));
+        assert_eq!(bd09_to_bd09mc(116.404, 80.0), bd09_to_bd09mc(116.404, 74.0));
+        let (x, _) = bd09_to_bd09mc(-116.404 - 720.0, 39.915);
+        assert!((x - bd09_to_bd09mc(-116.404, 39.915).0).abs() < 1e-6);
+        let (x, y) = bd09_to_bd09mc(f64::INFINITY, f64::NAN);
+        assert!(x.is_nan() && y.is_nan());
+    }
+
+    #[test]
+    fn test_southern_hemisphere() {
+        // 与百度的convertLL2MC一致，南半球正向转换始终使用0°分带
+        // As in Baidu's convertLL2MC, southern latitudes always use the 0° band forward
+        for lat in [-5.0, -20.0, -39.915, -74.0] {
+            assert_eq!(
+                bd09_to_bd09mc(116.404, lat),
+                polynomial(116.404, lat, &LL_TO_MC[LL_BAND.len() - 1])
+            );
+        }
+        let (_, north) = bd09_to_bd09mc(116.404, 39.915);
+        let (_, south) = bd09_to_bd09mc(116.404, -39.915);
+        assert!(south < 0.0 && (south + north).abs() > 1000.0);
+        // 逆向转换按 |y| 选带 The inverse picks its band by |y|
+        let (_, lat) = bd09mc_to_bd09(0.0, -north);
+        assert!((lat + 39.915).abs() < 1e-6);
+        // 低纬度的0°分带两个方向一致 Both directions share the 0° band at low latitudes
+        let (x, y) = bd09_to_bd09mc(-74.006, -10.0);
+        let (lon, lat) = bd09mc_to_bd09(x, y);
+        assert!((lon + 74.006).abs() < 1e-6 && (lat + 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_composites() {
+        let (lon, lat) = (116.404, 39.915);
+        let (bd_lon, bd_lat) = crate::wgs84_to_bd09(lon, lat);
+        assert_eq!(wgs84_to_bd09mc(lon, lat), bd09_to_bd09mc(bd_lon, bd_lat));
+        let (bd_lon, bd_lat) = crate::gcj02_to_bd09(lon, lat);
+        assert_eq!(gcj02_to_bd09mc(lon, lat), bd09_to_bd09mc(bd_lon, bd_lat));
+
+        let (x, y) = wgs84_to_bd09mc(lon, lat);
+        let (back_lon, back_lat) = bd09mc_to_wgs84(x, y);
+        assert!((back_lon - lon).abs() < 1e-4 && (back_lat - lat).abs() < 1e-4);
+        let (x, y) = gcj02_to_bd09mc(lon, lat);
+        let (back_lon, back_lat) = bd09mc_to_gcj02(x, y);
+        assert!((back_lon - lon).abs() < 1e-5 && (back_lat - lat).abs() < 1e-5);
+    }
+}
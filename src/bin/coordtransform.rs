@@ -0,0 +1,405 @@
+//! coordtransform 命令行工具 (需要 `cli` 特性)
+//! coordtransform command-line tool (requires the `cli` feature)
+//!
+//! ```text
+//! coordtransform --from bd09 --to wgs84 116.404,39.915
+//! echo "116.404 39.915" | coordtransform -f gcj02 -t wgs84 --precision 6 --format json
+//! coordtransform csv -f bd09 -t wgs84 --lon lng --lat lat --append wgs_lng wgs_lat poi.csv
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use coordtransform::csv::{Column, CsvError, CsvOptions, OutputMode};
+use coordtransform::{CoordSystem, convert};
+
+/// 在WGS84、GCJ02、BD09与EPSG:3857之间转换坐标
+/// Convert coordinates between WGS84, GCJ02, BD09 and EPSG:3857
+///
+/// 坐标以 "经度,纬度" 或 "经度 纬度" 给出；未给出坐标时从标准输入逐行读取。
+/// Coordinates are given as "lon,lat" or "lon lat"; they are read line by line from stdin when
+/// none are given.
+#[derive(Debug, Parser)]
+#[command(
+    name = "coordtransform",
+    version,
+    allow_negative_numbers = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// 源坐标系 Source system (wgs84, gcj02, bd09, epsg:3857, ...)
+    #[arg(short, long, required = true)]
+    from: Option<CoordSystem>,
+
+    /// 目标坐标系 Target system (wgs84, gcj02, bd09, epsg:3857, ...)
+    #[arg(short, long, required = true)]
+    to: Option<CoordSystem>,
+
+    /// 输出的小数位数，默认输出完整精度 Decimal places in the output; full precision by default
+    #[arg(short, long)]
+    precision: Option<usize>,
+
+    /// 输出格式 Output format
+    #[arg(long, value_enum, default_value_t = Format::Plain)]
+    format: Format,
+
+    /// 坐标，以 `-` 开头的 "经度,纬度" 需放在 `--` 之后
+    /// Coordinates; "lon,lat" values starting with `-` must follow `--`
+    coords: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 转换CSV文件中的经纬度列 Convert the longitude/latitude columns of a CSV file
+    Csv(CsvArgs),
+}
+
+#[derive(Debug, Args)]
+struct CsvArgs {
+    /// 源坐标系 Source system
+    #[arg(short, long)]
+    from: CoordSystem,
+
+    /// 目标坐标系 Target system
+    #[arg(short, long)]
+    to: CoordSystem,
+
+    /// 经度列名或从0开始的序号 Longitude column name or zero-based index
+    #[arg(long, default_value = "0", value_parser = str::parse::<Column>)]
+    lon: Column,
+
+    /// 纬度列名或从0开始的序号 Latitude column name or zero-based index
+    #[arg(long, default_value = "1", value_parser = str::parse::<Column>)]
+    lat: Column,
+
+    /// 追加为新列而非覆盖原列 Append new columns with these headers instead of overwriting
+    #[arg(long, num_args = 2, value_names = ["LON_HEADER", "LAT_HEADER"])]
+    append: Option<Vec<String>>,
+
+    /// 输入没有表头行 The input has no header row
+    #[arg(long)]
+    no_headers: bool,
+
+    /// 字段分隔符 Field delimiter
+    #[arg(short, long, default_value_t = ',')]
+    delimiter: char,
+
+    /// 输出的小数位数，默认输出完整精度 Decimal places in the output; full precision by default
+    #[arg(short, long)]
+    precision: Option<usize>,
+
+    /// 丢弃无法转换的行 Drop rows that cannot be converted
+    #[arg(long)]
+    skip_invalid: bool,
+
+    /// 输出文件，默认标准输出 Output file; stdout by default
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// 输入文件，默认或 `-` 时读取标准输入 Input file; stdin when omitted or `-`
+    input: Option<PathBuf>,
+}
+
+/// 输出格式 Output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// 空格分隔 Space-separated
+    Plain,
+    /// 带表头的CSV CSV with a header row
+    Csv,
+    /// 每行一个JSON对象 One JSON object per line
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Some(Command::Csv(args)) => run_csv(args),
+        None => run(&cli),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        // 下游关闭管道时静默退出 Exit quietly when the downstream pipe is closed
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("coordtransform: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 转换所有输入，全部成功时返回 `true`
+/// Convert every input, returning `true` when all of it succeeded
+fn run(cli: &Cli) -> io::Result<bool> {
+    let (Some(from), Some(to)) = (cli.from, cli.to) else {
+        unreachable!("clap requires --from and --to without a subcommand");
+    };
+    let mut out = BufWriter::new(io::stdout().lock());
+    if cli.format == Format::Csv {
+        let header = if to.is_projected() { "x,y" } else { "lon,lat" };
+        writeln!(out, "{}", header)?;
+    }
+
+    let mut ok = true;
+    if !cli.coords.is_empty() {
+        match parse_pairs(&cli.coords.join(" ")) {
+            Ok(pairs) => write_pairs(&mut out, cli, from, to, &pairs)?,
+            Err(err) => {
+                eprintln!("coordtransform: {}", err);
+                ok = false;
+            }
+        }
+    } else {
+        for (number, line) in io::stdin().lock().lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match parse_pairs(line) {
+                Ok(pairs) => write_pairs(&mut out, cli, from, to, &pairs)?,
+                Err(err) => {
+                    eprintln!("coordtransform: line {}: {}", number + 1, err);
+                    ok = false;
+                }
+            }
+        }
+    }
+    out.flush()?;
+    Ok(ok)
+}
+
+fn write_pairs(
+    out: &mut impl Write,
+    cli: &Cli,
+    from: CoordSystem,
+    to: CoordSystem,
+    pairs: &[(f64, f64)],
+) -> io::Result<()> {
+    for &(lon, lat) in pairs {
+        let (lon, lat) = convert(from, to, lon, lat);
+        writeln!(
+            out,
+            "{}",
+            format_record(cli.format, cli.precision, to, lon, lat)
+        )?;
+    }
+    Ok(())
+}
+
+/// 转换CSV文件，所有行均转换成功时返回 `true`
+/// Convert a CSV file, returning `true` when every row was converted
+fn run_csv(args: &CsvArgs) -> io::Result<bool> {
+    let options = csv_options(args)?;
+    let input: Box<dyn Read> = match &args.input {
+        Some(path) if path.as_os_str() != "-" => Box::new(File::open(path)?),
+        _ => Box::new(io::stdin().lock()),
+    };
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let report =
+        coordtransform::csv::convert(BufReader::new(input), BufWriter::new(output), &options)
+            .map_err(|err| match err {
+                CsvError::Csv(err) if err.is_io_error() => match err.into_kind() {
+                    ::csv::ErrorKind::Io(err) => err,
+                    _ => unreachable!(),
+                },
+                err => io::Error::other(err),
+            })?;
+    for err in &report.errors {
+        eprintln!("coordtransform: {}", err);
+    }
+    Ok(report.is_ok())
+}
+
+fn csv_options(args: &CsvArgs) -> io::Result<CsvOptions> {
+    let delimiter = u8::try_from(args.delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or_else(|| io::Error::other("the delimiter must be an ASCII character"))?;
+
+    let mut options = CsvOptions::new(args.from, args.to);
+    options.lon = args.lon.clone();
+    options.lat = args.lat.clone();
+    options.has_headers = !args.no_headers;
+    options.delimiter = delimiter;
+    options.precision = args.precision;
+    options.skip_invalid = args.skip_invalid;
+    if let Some([lon, lat]) = args.append.as_deref() {
+        options.output = OutputMode::Append {
+            lon: lon.clone(),
+            lat: lat.clone(),
+        };
+    }
+    Ok(options)
+}
+
+/// 解析以逗号、分号或空白分隔的坐标对
+/// Parse coordinate pairs separated by commas, semicolons or whitespace
+fn parse_pairs(input: &str) -> Result<Vec<(f64, f64)>, String> {
+    let numbers = input
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| match token.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => Err(format!("invalid number `{}`", token)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !numbers.len().is_multiple_of(2) {
+        return Err(format!(
+            "expected lon/lat pairs, got {} numbers",
+            numbers.len()
+        ));
+    }
+    Ok(numbers
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect())
+}
+
+fn format_record(
+    format: Format,
+    precision: Option<usize>,
+    system: CoordSystem,
+    lon: f64,
+    lat: f64,
+) -> String {
+    let number = |value: f64| match precision {
+        Some(precision) => format!("{:.*}", precision, value),
+        None => value.to_string(),
+    };
+    match format {
+        Format::Plain => format!("{} {}", number(lon), number(lat)),
+        Format::Csv => format!("{},{}", number(lon), number(lat)),
+        // 与 `TaggedCoord` 的serde格式一致 Same layout as the serde form of `TaggedCoord`
+        Format::Json => format!(
+            r#"{{"system":"{}","lon":{},"lat":{}}}"#,
+            system,
+            number(lon),
+            number(lat)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pairs() {
+        assert_eq!(parse_pairs("116.404,39.915"), Ok(vec![(116.404, 39.915)]));
+        assert_eq!(
+            parse_pairs(" 1 2; -3.5\t4 "),
+            Ok(vec![(1.0, 2.0), (-3.5, 4.0)])
+        );
+        assert!(parse_pairs("1 2 3").is_err());
+        assert!(parse_pairs("1 abc").is_err());
+        assert!(parse_pairs("NaN 1").is_err());
+    }
+
+    #[test]
+    fn test_format_record() {
+        let system = CoordSystem::Wgs84;
+        assert_eq!(
+            format_record(Format::Plain, Some(2), system, 1.0, 2.345),
+            "1.00 2.35"
+        );
+        assert_eq!(
+            format_record(Format::Csv, None, system, 1.5, -2.0),
+            "1.5,-2"
+        );
+        assert_eq!(
+            format_record(Format::Json, Some(1), system, 1.0, 2.0),
+            r#"{"system":"WGS84","lon":1.0,"lat":2.0}"#
+        );
+    }
+
+    #[test]
+    fn test_cli_arguments() {
+        let cli = Cli::try_parse_from([
+            "coordtransform",
+            "--from",
+            "bd09ll",
+            "-t",
+            "EPSG:4326",
+            "--format",
+            "csv",
+            "--",
+            "-74.006,40.713",
+        ])
+        .unwrap();
+        assert_eq!(cli.from, Some(CoordSystem::Bd09));
+        assert_eq!(cli.to, Some(CoordSystem::Wgs84));
+        assert_eq!(cli.format, Format::Csv);
+        assert_eq!(cli.coords, ["-74.006,40.713"]);
+
+        let cli = Cli::try_parse_from([
+            "coordtransform",
+            "-f",
+            "gcj02",
+            "-t",
+            "bd09",
+            "-74",
+            "40",
+            "-p",
+            "3",
+        ])
+        .unwrap();
+        assert_eq!(cli.coords, ["-74", "40"]);
+        assert_eq!(cli.precision, Some(3));
+
+        assert!(Cli::try_parse_from(["coordtransform", "--from", "utm", "--to", "wgs84"]).is_err());
+        assert!(Cli::try_parse_from(["coordtransform", "116.4", "39.9"]).is_err());
+    }
+
+    #[test]
+    fn test_csv_arguments() {
+        let cli = Cli::try_parse_from([
+            "coordtransform",
+            "csv",
+            "-f",
+            "bd09",
+            "-t",
+            "wgs84",
+            "--lon",
+            "lng",
+            "--lat",
+            "3",
+            "--append",
+            "wgs_lng",
+            "wgs_lat",
+            "-d",
+            ";",
+            "poi.csv",
+        ])
+        .unwrap();
+        let Some(Command::Csv(args)) = cli.command else {
+            panic!("expected the csv subcommand");
+        };
+        assert_eq!(args.input, Some(PathBuf::from("poi.csv")));
+
+        let options = csv_options(&args).unwrap();
+        assert_eq!(options.lon, Column::Name("lng".to_string()));
+        assert_eq!(options.lat, Column::Index(3));
+        assert_eq!(options.delimiter, b';');
+        assert_eq!(
+            options.output,
+            OutputMode::Append {
+                lon: "wgs_lng".to_string(),
+                lat: "wgs_lat".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,1062 @@
+//! 中国边界及特殊区域判断
+//! China boundary and special region tests
+//!
+//! 使用内嵌的简化中国边界多边形 (大陆、海南、台湾及南海诸岛) 判断坐标是否需要进行GCJ02偏移。
+//! 查询先经过外包矩形过滤，再通过编译期生成的网格索引直接判定绝大多数点，
+//! 只有落在边界网格内的点才需要进行精确的射线法判断。
+//!
+//! 各地图服务商对香港、澳门和台湾是否偏移的处理不一致，因此这些区域有单独的多边形。
+//! 是否偏移由 [`RegionPolicy`] 决定：可通过 [`convert_with_policy`](crate::convert_with_policy)
+//! 逐次指定，未指定时使用进程级默认策略 ([`set_region_policy`]、[`set_provider_policy`])。
+//!
+//! Uses embedded, simplified China boundary polygons (mainland, Hainan, Taiwan and the South China
+//! Sea islands) to decide whether coordinates are subject to the GCJ02 offset. Queries are first
+//! filtered by the bounding box, then most points are decided directly by a grid index built at
+//! compile time; only points in cells crossed by the boundary need an exact ray-casting test.
+//!
+//! Map providers disagree about whether Hong Kong, Macau and Taiwan are offset, so these regions
+//! have their own polygons. Whether they are shifted is decided by a [`RegionPolicy`], passed per
+//! call to [`convert_with_policy`](crate::convert_with_policy) or otherwise taken from the
+//! process-wide default ([`set_region_policy`], [`set_provider_policy`]).
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// 中国大陆 (含海南岛及近海岛屿) 简化边界，顶点为 (经度, 纬度)
+/// Simplified boundary of mainland China (including Hainan and offshore islands), vertices are
+/// (longitude, latitude)
+const MAINLAND: &[(f64, f64)] = &[
+    // 黑龙江、乌苏里江 (中俄边界) Amur and Ussuri rivers (China-Russia border)
+    (135.08, 48.44),
+    (134.00, 48.35),
+    (132.60, 47.72),
+    (131.00, 47.70),
+    (130.60, 48.40),
+    (130.00, 49.00),
+    (128.50, 49.60),
+    (127.62, 50.22),
+    (127.45, 50.30),
+    (127.30, 50.80),
+    (126.90, 51.30),
+    (126.40, 52.20),
+    (125.60, 53.05),
+    (124.30, 53.35),
+    (123.30, 53.55),
+    (122.40, 53.45),
+    (121.20, 53.28),
+    // 额尔古纳河 Argun river
+    (120.70, 52.60),
+    (120.10, 51.70),
+    (119.20, 50.40),
+    (118.00, 49.70),
+    // 中蒙边界 China-Mongolia border
+    (116.70, 49.85),
+    (116.20, 49.10),
+    (115.60, 48.15),
+    (116.60, 47.90),
+    (117.60, 47.70),
+    (118.50, 47.90),
+    (119.40, 47.40),
+    (119.90, 46.70),
+    (118.80, 46.60),
+    (117.60, 46.50),
+    (116.60, 46.30),
+    (115.80, 45.60),
+    (114.50, 45.40),
+    (113.60, 44.80),
+    (112.40, 44.90),
+    (111.90, 43.70),
+    (111.00, 43.30),
+    (110.00, 42.60),
+    (107.50, 42.40),
+    (105.00, 41.60),
+    (104.00, 41.80),
+    (101.80, 42.50),
+    (100.00, 42.65),
+    (97.20, 42.80),
+    (96.40, 42.70),
+    (95.30, 44.00),
+    (93.60, 44.90),
+    (91.00, 45.50),
+    (90.70, 46.10),
+    (91.00, 46.70),
+    (90.10, 47.90),
+    (88.90, 48.10),
+    (87.80, 49.17),
+    // 中哈、中吉、中塔边界 Borders with Kazakhstan, Kyrgyzstan and Tajikistan
+    (87.35, 49.15),
+    (86.20, 48.50),
+    (85.60, 47.10),
+    (83.00, 47.20),
+    (82.60, 45.20),
+    (81.60, 45.30),
+    (79.90, 44.90),
+    (80.50, 44.00),
+    (80.80, 43.20),
+    (80.20, 42.20),
+    (79.10, 41.80),
+    (78.10, 41.10),
+    (77.00, 41.00),
+    (76.30, 40.40),
+    (75.00, 40.50),
+    (74.00, 40.10),
+    (73.95, 39.70),
+    (73.60, 39.50),
+    (73.80, 38.60),
+    (74.90, 38.40),
+    (74.80, 37.30),
+    (74.90, 37.20),
+    // 中巴边界及中印实际控制线 Border with Pakistan and the China-India line of actual control
+    (75.40, 36.85),
+    (76.00, 36.80),
+    (76.90, 36.00),
+    (77.80, 35.50),
+    (78.20, 34.75),
+    (78.90, 34.40),
+    (78.60, 33.70),
+    (79.50, 32.70),
+    (78.90, 32.40),
+    (78.80, 31.50),
+    (79.30, 31.00),
+    (80.20, 30.60),
+    (80.80, 30.20),
+    // 中尼、中不边界 Borders with Nepal and Bhutan
+    (81.50, 30.40),
+    (82.20, 30.10),
+    (83.50, 29.20),
+    (84.50, 28.70),
+    (85.30, 28.30),
+    (86.00, 27.90),
+    (86.90, 27.95),
+    (88.00, 27.90),
+    (88.80, 28.00),
+    (88.90, 27.35),
+    (89.20, 27.80),
+    (89.60, 28.20),
+    (90.50, 28.00),
+    (91.60, 27.90),
+    (92.10, 27.80),
+    (92.70, 27.90),
+    (93.80, 28.30),
+    (95.00, 29.00),
+    (95.60, 29.30),
+    (96.10, 29.40),
+    (96.60, 29.00),
+    (97.30, 28.20),
+    // 中缅、中老、中越边界 Borders with Myanmar, Laos and Vietnam
+    (98.00, 27.70),
+    (98.70, 27.20),
+    (98.70, 26.00),
+    (97.70, 24.90),
+    (97.53, 24.10),
+    (97.70, 23.88),
+    (97.95, 23.97),
+    (98.10, 24.05),
+    (98.80, 24.10),
+    (99.50, 23.00),
+    (99.30, 22.20),
+    (100.20, 21.50),
+    (101.10, 21.40),
+    (101.80, 21.10),
+    (101.60, 22.20),
+    (102.10, 22.40),
+    (103.00, 22.50),
+    (103.60, 22.80),
+    (103.92, 22.56),
+    (103.94, 22.49),
+    (104.02, 22.48),
+    (104.10, 22.75),
+    (104.60, 23.10),
+    (105.30, 23.35),
+    (106.10, 22.90),
+    (106.70, 22.85),
+    (106.55, 22.45),
+    (106.68, 22.25),
+    (106.66, 22.05),
+    (106.72, 21.95),
+    (107.05, 21.85),
+    (107.35, 21.62),
+    (107.70, 21.62),
+    (107.95, 21.535),
+    (108.10, 21.45),
+    // 北部湾及海南岛 Gulf of Tonkin and Hainan island
+    (108.40, 20.00),
+    (108.40, 19.00),
+    (108.90, 18.20),
+    (109.50, 17.95),
+    (110.30, 18.30),
+    (110.80, 18.90),
+    (111.20, 19.60),
+    (111.10, 20.20),
+    (110.90, 20.70),
+    // 南海、东海及黄海沿岸 South China Sea, East China Sea and Yellow Sea coasts
+    (111.50, 21.30),
+    (112.30, 21.50),
+    (113.20, 21.75),
+    (113.90, 21.95),
+    (114.40, 22.05),
+    (114.70, 22.35),
+    (115.50, 22.60),
+    (116.50, 22.85),
+    (117.20, 23.40),
+    (118.00, 24.10),
+    (118.60, 24.45),
+    (119.20, 25.00),
+    (119.80, 25.40),
+    (120.10, 26.00),
+    (120.40, 26.70),
+    (121.25, 27.40),
+    (121.70, 28.10),
+    (122.10, 28.60),
+    (122.40, 29.20),
+    (122.90, 29.90),
+    (122.90, 30.90),
+    (122.20, 31.30),
+    (121.90, 31.90),
+    (121.20, 32.60),
+    (120.70, 33.60),
+    (120.40, 34.40),
+    (119.60, 34.80),
+    (119.80, 35.50),
+    (120.80, 36.00),
+    (121.60, 36.60),
+    (122.60, 36.90),
+    (122.75, 37.40),
+    (122.30, 37.60),
+    (121.00, 37.85),
+    (120.80, 38.45),
+    // 辽东半岛 Liaodong peninsula
+    (121.00, 38.60),
+    (121.70, 38.80),
+    (122.30, 39.20),
+    (123.20, 39.40),
+    (124.10, 39.75),
+    // 鸭绿江、图们江 (中朝边界) Yalu and Tumen rivers (China-North Korea border)
+    (124.40, 40.10),
+    (125.00, 40.50),
+    (125.50, 40.90),
+    (126.00, 41.30),
+    (126.60, 41.70),
+    (127.30, 41.50),
+    (128.10, 41.40),
+    (128.20, 41.90),
+    (128.90, 42.00),
+    (129.40, 42.40),
+    (129.80, 42.95),
+    (130.30, 42.90),
+    (130.25, 42.70),
+    (130.60, 42.40),
+    // 中俄东段边界 Eastern China-Russia border
+    (130.90, 42.60),
+    (131.10, 42.90),
+    (131.20, 43.40),
+    (131.30, 44.00),
+    (131.10, 44.90),
+    (131.85, 45.35),
+    (132.90, 45.05),
+    (133.10, 45.10),
+    (133.50, 45.90),
+    (134.00, 46.70),
+    (134.20, 47.30),
+    (134.70, 47.70),
+    (134.60, 48.10),
+];
+
+/// 台湾岛及澎湖列岛简化边界
+/// Simplified boundary of Taiwan island and the Penghu islands
+const TAIWAN: &[(f64, f64)] = &[
+    (119.20, 23.10),
+    (120.00, 22.60),
+    (120.60, 21.80),
+    (121.00, 21.80),
+    (121.70, 22.00),
+    (121.75, 23.50),
+    (122.10, 24.60),
+    (122.10, 25.10),
+    (121.70, 25.40),
+    (121.20, 25.30),
+    (120.70, 24.70),
+    (120.10, 24.10),
+    (119.40, 23.90),
+];
+
+/// 西沙群岛简化边界 Simplified boundary of the Xisha (Paracel) islands
+const XISHA: &[(f64, f64)] = &[
+    (111.00, 15.60),
+    (112.90, 15.60),
+    (112.90, 17.20),
+    (111.00, 17.20),
+];
+
+/// 中沙群岛简化边界 Simplified boundary of the Zhongsha islands (Macclesfield Bank)
+const ZHONGSHA: &[(f64, f64)] = &[
+    (113.60, 15.30),
+    (114.90, 15.30),
+    (114.90, 16.30),
+    (113.60, 16.30),
+];
+
+/// 黄岩岛简化边界 Simplified boundary of Huangyan Island (Scarborough Shoal)
+const HUANGYAN: &[(f64, f64)] = &[
+    (117.60, 15.00),
+    (117.90, 15.00),
+    (117.90, 15.30),
+    (117.60, 15.30),
+];
+
+/// 南沙群岛简化边界 Simplified boundary of the Nansha (Spratly) islands
+const NANSHA: &[(f64, f64)] = &[
+    (111.50, 7.00),
+    (114.80, 7.00),
+    (116.30, 8.30),
+    (116.90, 9.60),
+    (117.30, 11.30),
+    (115.00, 11.90),
+    (112.30, 11.90),
+    (111.60, 11.00),
+];
+
+/// 香港简化边界 Simplified boundary of Hong Kong
+const HONG_KONG: &[(f64, f64)] = &[
+    (113.82, 22.14),
+    (113.82, 22.30),
+    (113.88, 22.42),
+    (113.93, 22.48),
+    (114.02, 22.51),
+    (114.10, 22.535),
+    (114.17, 22.545),
+    (114.23, 22.555),
+    (114.33, 22.57),
+    (114.46, 22.56),
+    (114.52, 22.35),
+    (114.42, 22.14),
+    (114.20, 22.13),
+    (114.00, 22.13),
+];
+
+/// 澳门简化边界 Simplified boundary of Macau
+const MACAU: &[(f64, f64)] = &[
+    (113.525, 22.215),
+    (113.555, 22.215),
+    (113.565, 22.19),
+    (113.60, 22.14),
+    (113.60, 22.11),
+    (113.555, 22.105),
+    (113.53, 22.12),
+    (113.525, 22.17),
+];
+
+/// 金门简化边界 Simplified boundary of Kinmen
+const KINMEN: &[(f64, f64)] = &[
+    (118.22, 24.38),
+    (118.50, 24.38),
+    (118.50, 24.52),
+    (118.22, 24.52),
+];
+
+/// 马祖简化边界 Simplified boundary of Matsu
+const MATSU: &[(f64, f64)] = &[
+    (119.88, 25.95),
+    (120.02, 25.95),
+    (120.02, 26.28),
+    (119.88, 26.28),
+];
+
+/// 构成中国边界的全部多边形
+/// All polygons forming the China boundary
+const CHINA: &[&[(f64, f64)]] = &[MAINLAND, TAIWAN, XISHA, ZHONGSHA, HUANGYAN, NANSHA];
+
+/// 网格索引的列数 Number of grid index columns
+const GRID_COLS: usize = 128;
+
+/// 网格索引的行数 Number of grid index rows
+const GRID_ROWS: usize = 128;
+
+/// 编译期生成的中国边界网格索引
+/// China boundary grid index, built at compile time
+static CHINA_GRID: Grid = Grid::build(CHINA);
+
+/// 特殊区域及其多边形，按查询顺序排列；未命中任何特殊区域的境内坐标属于大陆
+/// Special regions and their polygons in lookup order; coordinates inside China that match none of
+/// them belong to the mainland
+static SPECIAL_REGIONS: [SpecialRegion; 3] = [
+    SpecialRegion::new(Region::HongKong, &[HONG_KONG]),
+    SpecialRegion::new(Region::Macau, &[MACAU]),
+    SpecialRegion::new(Region::Taiwan, &[TAIWAN, KINMEN, MATSU]),
+];
+
+/// 进程级默认偏移策略，下标为 [`Region`] 的序号，`true` 表示偏移
+/// Process-wide default shift policy indexed by [`Region`], `true` meaning shift
+static POLICIES: [AtomicBool; Region::ALL.len()] =
+    [const { AtomicBool::new(true) }; Region::ALL.len()];
+
+/// 区域 Region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Region {
+    /// 中国大陆 (含海南) Mainland China (including Hainan)
+    Mainland,
+    /// 香港 Hong Kong
+    HongKong,
+    /// 澳门 Macau
+    Macau,
+    /// 台湾 (含澎湖、金门、马祖) Taiwan (including Penghu, Kinmen and Matsu)
+    Taiwan,
+}
+
+impl Region {
+    /// 全部区域 All regions
+    pub const ALL: [Region; 4] = [
+        Region::Mainland,
+        Region::HongKong,
+        Region::Macau,
+        Region::Taiwan,
+    ];
+}
+
+/// 区域偏移策略 Region shift policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShiftPolicy {
+    /// 进行GCJ02偏移 Apply the GCJ02 offset
+    Shift,
+    /// 不偏移，坐标保持WGS84 Do not shift, coordinates stay in WGS84
+    NoShift,
+}
+
+/// 各区域的偏移策略
+/// Shift policy of every region
+///
+/// 作为参数传给 [`convert_with_policy`](crate::convert_with_policy)，只影响该次转换；
+/// [`RegionPolicy::global`] 返回当前的进程级默认策略。
+/// Passed to [`convert_with_policy`](crate::convert_with_policy), it only affects that
+/// conversion; [`RegionPolicy::global`] returns the current process-wide default.
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{convert_with_policy, CoordSystem, Provider, Region, RegionPolicy, ShiftPolicy};
+///
+/// let policy = RegionPolicy::from(Provider::Google);
+/// assert_eq!(policy.get(Region::HongKong), ShiftPolicy::NoShift);
+///
+/// // 香港 Hong Kong
+/// let (lon, lat) = (114.158, 22.285);
+/// let converted = convert_with_policy(&policy, CoordSystem::Wgs84, CoordSystem::Gcj02, lon, lat);
+/// assert_eq!(converted, (lon, lat));
+///
+/// let policy = RegionPolicy::SHIFT_ALL.with(Region::Taiwan, ShiftPolicy::NoShift);
+/// assert_eq!(policy.get(Region::Macau), ShiftPolicy::Shift);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegionPolicy {
+    shift: [bool; Region::ALL.len()],
+}
+
+impl RegionPolicy {
+    /// 所有区域均偏移 (默认) Every region is shifted (the default)
+    pub const SHIFT_ALL: RegionPolicy = RegionPolicy {
+        shift: [true; Region::ALL.len()],
+    };
+
+    /// 当前的进程级默认策略 The current process-wide default policy
+    pub fn global() -> Self {
+        let mut policy = Self::SHIFT_ALL;
+        for region in Region::ALL {
+            policy.set(region, region_policy(region));
+        }
+        policy
+    }
+
+    /// 返回区域的偏移策略 Returns the shift policy of a region
+    pub fn get(&self, region: Region) -> ShiftPolicy {
+        if self.shift[region as usize] {
+            ShiftPolicy::Shift
+        } else {
+            ShiftPolicy::NoShift
+        }
+    }
+
+    /// 设置区域的偏移策略 Set the shift policy of a region
+    pub fn set(&mut self, region: Region, policy: ShiftPolicy) {
+        self.shift[region as usize] = policy == ShiftPolicy::Shift;
+    }
+
+    /// 返回设置了区域偏移策略的副本 Returns a copy with the shift policy of a region set
+    pub const fn with(mut self, region: Region, policy: ShiftPolicy) -> Self {
+        self.shift[region as usize] = matches!(policy, ShiftPolicy::Shift);
+        self
+    }
+
+    /// 判断坐标按该策略是否需要进行GCJ02偏移
+    /// Determine whether the coordinates are shifted under this policy
+    pub fn is_shifted(&self, lon: f64, lat: f64) -> bool {
+        region_of(lon, lat).is_some_and(|region| self.shift[region as usize])
+    }
+}
+
+impl Default for RegionPolicy {
+    fn default() -> Self {
+        Self::SHIFT_ALL
+    }
+}
+
+impl From<Provider> for RegionPolicy {
+    fn from(provider: Provider) -> Self {
+        let mut policy = Self::SHIFT_ALL;
+        for region in Region::ALL {
+            policy.set(region, provider.policy(region));
+        }
+        policy
+    }
+}
+
+/// 地图服务商 Map provider
+///
+/// 可转换为 [`RegionPolicy`]，按服务商的实际表现设置各区域的偏移策略。
+/// Converts into a [`RegionPolicy`] matching the provider's behaviour per region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Provider {
+    /// 高德地图：所有区域均偏移 (默认)
+    /// Amap: every region is shifted (the default)
+    Amap,
+    /// 苹果地图：大陆、香港、澳门偏移，台湾不偏移
+    /// Apple Maps: mainland, Hong Kong and Macau are shifted, Taiwan is not
+    Apple,
+    /// 谷歌地图：仅大陆偏移
+    /// Google Maps: only the mainland is shifted
+    Google,
+}
+
+impl Provider {
+    /// 返回该服务商在指定区域的偏移策略
+    /// Returns the provider's shift policy for the given region
+    pub fn policy(self, region: Region) -> ShiftPolicy {
+        let shift = match (self, region) {
+            (_, Region::Mainland) | (Provider::Amap, _) => true,
+            (Provider::Apple, Region::HongKong | Region::Macau) => true,
+            (Provider::Apple, Region::Taiwan) | (Provider::Google, _) => false,
+        };
+        if shift {
+            ShiftPolicy::Shift
+        } else {
+            ShiftPolicy::NoShift
+        }
+    }
+}
+
+/// 设置区域的进程级默认偏移策略，影响所有未指定策略的GCJ02与BD09转换函数
+/// Set the process-wide default shift policy of a region; affects every GCJ02 and BD09 conversion
+/// function not given an explicit policy
+///
+/// 该设置为全局设置，会影响同一进程中的所有调用方 (包括其他依赖本库的crate)，默认所有区域均偏移。
+/// 库代码应使用 [`convert_with_policy`](crate::convert_with_policy) 逐次指定策略。
+/// The setting is global and affects every caller in the process, including other crates depending
+/// on this one; by default every region is shifted. Library code should pass a policy per call
+/// with [`convert_with_policy`](crate::convert_with_policy) instead.
+///
+/// # Parameters 参数
+///
+/// * `region` - 区域 Region
+/// * `policy` - 偏移策略 Shift policy
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{set_region_policy, wgs84_to_gcj02, Region, ShiftPolicy};
+///
+/// set_region_policy(Region::Taiwan, ShiftPolicy::NoShift);
+/// // 台北 Taipei
+/// assert_eq!(wgs84_to_gcj02(121.5654, 25.0330), (121.5654, 25.0330));
+/// set_region_policy(Region::Taiwan, ShiftPolicy::Shift);
+/// ```
+pub fn set_region_policy(region: Region, policy: ShiftPolicy) {
+    POLICIES[region as usize].store(policy == ShiftPolicy::Shift, Ordering::Relaxed);
+}
+
+/// 获取区域的进程级默认偏移策略
+/// Get the process-wide default shift policy of a region
+///
+/// # Parameters 参数
+///
+/// * `region` - 区域 Region
+///
+/// # Return Value 返回值
+///
+/// 返回该区域当前的默认偏移策略 Returns the current default shift policy of the region
+pub fn region_policy(region: Region) -> ShiftPolicy {
+    if POLICIES[region as usize].load(Ordering::Relaxed) {
+        ShiftPolicy::Shift
+    } else {
+        ShiftPolicy::NoShift
+    }
+}
+
+/// 按地图服务商的表现设置所有区域的进程级默认偏移策略
+/// Set the process-wide default shift policy of every region after a map provider's behaviour
+///
+/// 与 [`set_region_policy`] 一样影响整个进程，库代码应改用 `RegionPolicy::from(provider)`。
+/// Like [`set_region_policy`] this affects the whole process; library code should use
+/// `RegionPolicy::from(provider)` instead.
+///
+/// # Parameters 参数
+///
+/// * `provider` - 地图服务商 Map provider
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{region_policy, set_provider_policy, Provider, Region, ShiftPolicy};
+///
+/// set_provider_policy(Provider::Google);
+/// assert_eq!(region_policy(Region::HongKong), ShiftPolicy::NoShift);
+/// set_provider_policy(Provider::Amap);
+/// ```
+pub fn set_provider_policy(provider: Provider) {
+    for region in Region::ALL {
+        set_region_policy(region, provider.policy(region));
+    }
+}
+
+/// 判断坐标所在区域
+/// Determine the region containing the coordinates
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+///
+/// # Return Value 返回值
+///
+/// 返回坐标所在区域，中国境外返回 `None`
+/// Returns the region containing the coordinates, or `None` outside China
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{region_of, Region};
+///
+/// assert_eq!(region_of(116.404, 39.915), Some(Region::Mainland));
+/// assert_eq!(region_of(114.158, 22.285), Some(Region::HongKong));
+/// ```
+pub fn region_of(lon: f64, lat: f64) -> Option<Region> {
+    if !is_in_china(lon, lat) {
+        return None;
+    }
+
+    let region = SPECIAL_REGIONS
+        .iter()
+        .find(|special| special.contains(lon, lat))
+        .map_or(Region::Mainland, |special| special.region);
+    Some(region)
+}
+
+/// 判断坐标按进程级默认策略是否需要进行GCJ02偏移
+/// Determine whether the coordinates are shifted under the process-wide default policy
+pub(crate) fn is_shifted(lon: f64, lat: f64) -> bool {
+    region_of(lon, lat).is_some_and(|region| region_policy(region) == ShiftPolicy::Shift)
+}
+
+/// 判断坐标是否在中国境内 (需要进行GCJ02偏移的区域)
+/// Determine whether the coordinates are inside China (the area subject to the GCJ02 offset)
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+///
+/// # Return Value 返回值
+///
+/// 在中国境内返回 `true` Returns `true` if the coordinates are inside China
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::is_in_china;
+///
+/// assert!(is_in_china(116.404, 39.915));
+/// // 首尔 Seoul
+/// assert!(!is_in_china(126.978, 37.566));
+/// ```
+pub fn is_in_china(lon: f64, lat: f64) -> bool {
+    match CHINA_GRID.cell(lon, lat) {
+        Some(Cell::Inside) => true,
+        Some(Cell::Boundary) => point_in_rings(CHINA, lon, lat),
+        Some(Cell::Outside) | None => false,
+    }
+}
+
+/// 带外包矩形的特殊区域 Special region with its bounding box
+struct SpecialRegion {
+    region: Region,
+    rings: &'static [&'static [(f64, f64)]],
+    bbox: (f64, f64, f64, f64),
+}
+
+impl SpecialRegion {
+    const fn new(region: Region, rings: &'static [&'static [(f64, f64)]]) -> Self {
+        Self {
+            region,
+            rings,
+            bbox: bounding_box(rings),
+        }
+    }
+
+    fn contains(&self, lon: f64, lat: f64) -> bool {
+        let (min_lon, min_lat, max_lon, max_lat) = self.bbox;
+        lon >= min_lon
+            && lon <= max_lon
+            && lat >= min_lat
+            && lat <= max_lat
+            && point_in_rings(self.rings, lon, lat)
+    }
+}
+
+/// 网格单元状态 Grid cell state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    /// 单元完全在多边形外 The cell is entirely outside the polygons
+    Outside,
+    /// 单元完全在多边形内 The cell is entirely inside the polygons
+    Inside,
+    /// 单元与边界相交，需要精确判断 The cell touches the boundary and needs an exact test
+    Boundary,
+}
+
+/// 覆盖多边形外包矩形的均匀网格
+/// Uniform grid covering the bounding box of the polygons
+struct Grid {
+    min_lon: f64,
+    min_lat: f64,
+    max_lon: f64,
+    max_lat: f64,
+    cell_width: f64,
+    cell_height: f64,
+    cells: [[Cell; GRID_COLS]; GRID_ROWS],
+}
+
+impl Grid {
+    /// 构建网格：先标记与任意边的外包矩形相交的单元为边界单元，
+    /// 再按行扫描，每段连续的非边界单元只需对一个单元中心做射线法判断。
+    ///
+    /// Build the grid: cells overlapping the bounding box of any edge are marked as boundary cells,
+    /// then each row is scanned and every run of non-boundary cells needs a single ray-casting test
+    /// at one cell center.
+    const fn build(rings: &[&[(f64, f64)]]) -> Self {
+        let (min_lon, min_lat, max_lon, max_lat) = bounding_box(rings);
+        let mut grid = Grid {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+            cell_width: (max_lon - min_lon) / GRID_COLS as f64,
+            cell_height: (max_lat - min_lat) / GRID_ROWS as f64,
+            cells: [[Cell::Outside; GRID_COLS]; GRID_ROWS],
+        };
+
+        let mut r = 0;
+        while r < rings.len() {
+            let ring = rings[r];
+            let mut i = 0;
+            while i < ring.len() {
+                let (x1, y1) = ring[i];
+                let (x2, y2) = ring[(i + 1) % ring.len()];
+                let (col_start, row_start) = grid.index(min_f64(x1, x2), min_f64(y1, y2));
+                let (col_end, row_end) = grid.index(max_f64(x1, x2), max_f64(y1, y2));
+
+                let mut row = row_start;
+                while row <= row_end {
+                    let mut col = col_start;
+                    while col <= col_end {
+                        grid.cells[row][col] = Cell::Boundary;
+                        col += 1;
+                    }
+                    row += 1;
+                }
+                i += 1;
+            }
+            r += 1;
+        }
+
+        let mut row = 0;
+        while row < GRID_ROWS {
+            let lat = min_lat + (row as f64 + 0.5) * grid.cell_height;
+            let mut state = Cell::Outside;
+            let mut previous_boundary = true;
+            let mut col = 0;
+            while col < GRID_COLS {
+                if matches!(grid.cells[row][col], Cell::Boundary) {
+                    previous_boundary = true;
+                } else {
+                    if previous_boundary {
+                        let lon = min_lon + (col as f64 + 0.5) * grid.cell_width;
+                        state = if point_in_rings(rings, lon, lat) {
+                            Cell::Inside
+                        } else {
+                            Cell::Outside
+                        };
+                        previous_boundary = false;
+                    }
+                    grid.cells[row][col] = state;
+                }
+                col += 1;
+            }
+            row += 1;
+        }
+
+        grid
+    }
+
+    /// 返回坐标所在单元的 (列, 行)，超出范围的坐标被钳制到边缘单元
+    /// Returns the (column, row) of the cell containing the coordinates, clamped to the edge cells
+    const fn index(&self, lon: f64, lat: f64) -> (usize, usize) {
+        let col = ((lon - self.min_lon) / self.cell_width) as usize;
+        let row = ((lat - self.min_lat) / self.cell_height) as usize;
+        let col = if col >= GRID_COLS { GRID_COLS - 1 } else { col };
+        let row = if row >= GRID_ROWS { GRID_ROWS - 1 } else { row };
+        (col, row)
+    }
+
+    /// 返回坐标所在单元的状态，外包矩形之外返回 `None`
+    /// Returns the state of the cell containing the coordinates, or `None` outside the bounding box
+    fn cell(&self, lon: f64, lat: f64) -> Option<Cell> {
+        if !(lon >= self.min_lon
+            && lon <= self.max_lon
+            && lat >= self.min_lat
+            && lat <= self.max_lat)
+        {
+            return None;
+        }
+
+        let (col, row) = self.index(lon, lat);
+        Some(self.cells[row][col])
+    }
+}
+
+/// 计算多边形的外包矩形 (最小经度, 最小纬度, 最大经度, 最大纬度)
+/// Compute the bounding box of the polygons as (min lon, min lat, max lon, max lat)
+const fn bounding_box(rings: &[&[(f64, f64)]]) -> (f64, f64, f64, f64) {
+    let (mut min_lon, mut min_lat) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_lon, mut max_lat) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+
+    let mut r = 0;
+    while r < rings.len() {
+        let ring = rings[r];
+        let mut i = 0;
+        while i < ring.len() {
+            let (lon, lat) = ring[i];
+            min_lon = min_f64(min_lon, lon);
+            min_lat = min_f64(min_lat, lat);
+            max_lon = max_f64(max_lon, lon);
+            max_lat = max_f64(max_lat, lat);
+            i += 1;
+        }
+        r += 1;
+    }
+
+    (min_lon, min_lat, max_lon, max_lat)
+}
+
+/// 射线法判断点是否在任一多边形内
+/// Ray-casting test of whether the point is inside any of the polygons
+const fn point_in_rings(rings: &[&[(f64, f64)]], lon: f64, lat: f64) -> bool {
+    let mut r = 0;
+    while r < rings.len() {
+        if point_in_ring(rings[r], lon, lat) {
+            return true;
+        }
+        r += 1;
+    }
+    false
+}
+
+/// 射线法判断点是否在多边形内
+/// Ray-casting test of whether the point is inside the polygon
+const fn point_in_ring(ring: &[(f64, f64)], lon: f64, lat: f64) -> bool {
+    let mut inside = false;
+    let mut j = ring.len() - 1;
+    let mut i = 0;
+    while i < ring.len() {
+        let (xi, yi) = ring[i];
+        let (xj, yj) = ring[j];
+        if (yi > lat) != (yj > lat) && lon < (xj - xi) * (lat - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+        i += 1;
+    }
+    inside
+}
+
+const fn min_f64(a: f64, b: f64) -> f64 {
+    if a < b { a } else { b }
+}
+
+const fn max_f64(a: f64, b: f64) -> f64 {
+    if a > b { a } else { b }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_in_china() {
+        let cities = [
+            ("Beijing", 116.404, 39.915),
+            ("Shanghai", 121.4737, 31.2304),
+            ("Harbin", 126.6424, 45.7567),
+            ("Urumqi", 87.6168, 43.8256),
+            ("Kashgar", 75.9898, 39.4677),
+            ("Lhasa", 91.1409, 29.6456),
+            ("Jinghong", 100.7979, 22.0094),
+            ("Sanya", 109.5119, 18.2528),
+            ("Guangzhou", 113.2644, 23.1291),
+            ("Dalian", 121.6147, 38.9140),
+            ("Taipei", 121.5654, 25.0330),
+            // 贴近国界或海岸的城市和岛屿 Cities and islands close to the border or the coast
+            ("Mohe", 122.5369, 52.9720),
+            ("Heihe", 127.5, 50.25),
+            ("Fuyuan", 134.2890, 48.3647),
+            ("Suifenhe", 131.1517, 44.3964),
+            ("Hunchun", 130.3658, 42.8629),
+            ("Tumen", 129.8434, 42.9667),
+            ("Dandong", 124.3830, 40.1290),
+            ("Manzhouli", 117.4329, 49.5978),
+            ("Erenhot", 111.9777, 43.6532),
+            ("Khorgas", 80.4200, 44.2100),
+            ("Alashankou", 82.5700, 45.1700),
+            ("Yadong", 88.9070, 27.4830),
+            ("Zhangmu", 85.9800, 27.9900),
+            ("Ruili", 97.85, 24.01),
+            ("Mohan", 101.6800, 21.1900),
+            ("Hekou", 103.9500, 22.5100),
+            ("Pingxiang", 106.75, 22.1),
+            ("Dongxing", 107.97, 21.55),
+            ("Weizhou Island", 109.1100, 21.0300),
+            ("Nanji Island", 121.0800, 27.4600),
+            ("Dachen Island", 121.9000, 28.4500),
+            ("Zhoushan", 122.2072, 29.9853),
+            ("Shengsi", 122.45, 30.72),
+            ("Changshan Islands", 122.6000, 39.2700),
+            ("Yongxing Island", 112.34, 16.83),
+            ("Huangyan Island", 117.7500, 15.1500),
+            ("Nansha", 115.53, 9.9),
+            ("Yongshu Reef", 112.8900, 9.5500),
+        ];
+        for (city, lon, lat) in cities {
+            assert!(is_in_china(lon, lat), "{} should be inside China", city);
+        }
+    }
+
+    #[test]
+    fn test_is_not_in_china() {
+        let cities = [
+            ("Ulaanbaatar", 106.9057, 47.8864),
+            ("Seoul", 126.9780, 37.5665),
+            ("Pyongyang", 125.7625, 39.0392),
+            ("Vladivostok", 131.8869, 43.1155),
+            ("Tokyo", 139.6917, 35.6895),
+            ("Hanoi", 105.8342, 21.0278),
+            ("Bangkok", 100.5018, 13.7563),
+            ("Almaty", 76.8512, 43.2220),
+            ("Kathmandu", 85.3240, 27.7172),
+            ("Thimphu", 89.6386, 27.4728),
+            ("Manila", 120.9842, 14.5995),
+            ("Blagoveshchensk", 127.5405, 50.2904),
+            ("Lang Son", 106.7610, 21.8537),
+            ("Ho Chi Minh City", 106.6297, 10.8231),
+            ("Puerto Princesa", 118.7384, 9.7392),
+            ("Kota Kinabalu", 116.0735, 5.9804),
+            ("Jeju", 126.5312, 33.4996),
+            ("Null Island", 0.0, 0.0),
+        ];
+        for (city, lon, lat) in cities {
+            assert!(!is_in_china(lon, lat), "{} should be outside China", city);
+        }
+    }
+
+    #[test]
+    fn test_is_in_china_non_finite() {
+        assert!(!is_in_china(f64::NAN, 39.915));
+        assert!(!is_in_china(116.404, f64::INFINITY));
+    }
+
+    #[test]
+    fn test_grid_matches_exact_test() {
+        // 网格索引不应改变判断结果 The grid index must not change any result
+        let (min_lon, min_lat, max_lon, max_lat) = bounding_box(CHINA);
+        let steps = 400;
+        for i in 0..=steps {
+            for j in 0..=steps {
+                let lon = min_lon + (max_lon - min_lon) * i as f64 / steps as f64;
+                let lat = min_lat + (max_lat - min_lat) * j as f64 / steps as f64;
+                assert_eq!(is_in_china(lon, lat), point_in_rings(CHINA, lon, lat));
+            }
+        }
+    }
+
+    #[test]
+    fn test_region_of() {
+        let places = [
+            ("Beijing", 116.404, 39.915, Some(Region::Mainland)),
+            ("Shenzhen", 114.0579, 22.6000, Some(Region::Mainland)),
+            ("Zhuhai", 113.5767, 22.2707, Some(Region::Mainland)),
+            ("Xiamen", 118.0894, 24.4798, Some(Region::Mainland)),
+            ("Hong Kong", 114.1580, 22.2850, Some(Region::HongKong)),
+            ("Lantau", 113.9440, 22.2540, Some(Region::HongKong)),
+            ("Macau", 113.5430, 22.1870, Some(Region::Macau)),
+            ("Taipei", 121.5654, 25.0330, Some(Region::Taiwan)),
+            ("Kaohsiung", 120.3014, 22.6273, Some(Region::Taiwan)),
+            ("Kinmen", 118.3200, 24.4500, Some(Region::Taiwan)),
+            ("Seoul", 126.9780, 37.5665, None),
+        ];
+        for (place, lon, lat, region) in places {
+            assert_eq!(region_of(lon, lat), region, "{}", place);
+        }
+    }
+
+    #[test]
+    fn test_provider_policy() {
+        assert!(
+            Region::ALL
+                .iter()
+                .all(|&region| Provider::Amap.policy(region) == ShiftPolicy::Shift)
+        );
+        assert_eq!(Provider::Apple.policy(Region::Macau), ShiftPolicy::Shift);
+        assert_eq!(Provider::Apple.policy(Region::Taiwan), ShiftPolicy::NoShift);
+        assert_eq!(
+            Provider::Google.policy(Region::Mainland),
+            ShiftPolicy::Shift
+        );
+        assert_eq!(
+            Provider::Google.policy(Region::HongKong),
+            ShiftPolicy::NoShift
+        );
+    }
+
+    #[test]
+    fn test_region_policy() {
+        // 单元测试并行运行，因此不修改进程级默认策略，只测试逐次指定的策略
+        // Unit tests run in parallel, so the process-wide default is left alone and only per-call
+        // policies are tested
+        assert_eq!(RegionPolicy::global(), RegionPolicy::SHIFT_ALL);
+        assert_eq!(RegionPolicy::default(), RegionPolicy::SHIFT_ALL);
+
+        let (lon, lat) = (114.1580, 22.2850);
+        assert!(RegionPolicy::SHIFT_ALL.is_shifted(lon, lat));
+        assert!(!RegionPolicy::SHIFT_ALL.is_shifted(126.9780, 37.5665));
+
+        let mut policy = RegionPolicy::SHIFT_ALL.with(Region::HongKong, ShiftPolicy::NoShift);
+        assert!(!policy.is_shifted(lon, lat));
+        // 大陆不受影响 The mainland is unaffected
+        assert!(policy.is_shifted(116.404, 39.915));
+        policy.set(Region::HongKong, ShiftPolicy::Shift);
+        assert_eq!(policy, RegionPolicy::SHIFT_ALL);
+
+        for provider in [Provider::Amap, Provider::Apple, Provider::Google] {
+            let policy = RegionPolicy::from(provider);
+            for region in Region::ALL {
+                assert_eq!(policy.get(region), provider.policy(region));
+            }
+        }
+        assert_eq!(RegionPolicy::from(Provider::Amap), RegionPolicy::SHIFT_ALL);
+    }
+}
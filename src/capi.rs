@@ -0,0 +1,460 @@
+//! C ABI (需要 `capi` 特性)
+//! C ABI (requires the `capi` feature)
+//!
+//! 每种转换都导出为单点函数 `ct_<转换>` 和处理调用方缓冲区的批量函数 `ct_<转换>_batch`，
+//! 另有按坐标系编号转换的 `ct_convert`、`ct_try_convert` 与 `ct_convert_batch`。
+//! 头文件位于 `include/coordtransform.h`，由本模块的转换表生成并由测试保持同步。
+//!
+//! Every conversion is exported as a single-point function `ct_<conversion>` and a batch function
+//! `ct_<conversion>_batch` over caller-provided buffers, plus `ct_convert`, `ct_try_convert` and
+//! `ct_convert_batch` taking coordinate system ids. The header lives in
+//! `include/coordtransform.h`; it is generated from this module's conversion table and kept in
+//! sync by a test.
+//!
+//! 构建动态库或静态库 Build a shared or static library:
+//!
+//! ```text
+//! cargo rustc --release --lib --features capi --crate-type cdylib
+//! cargo rustc --release --lib --features capi --crate-type staticlib
+//! ```
+//!
+//! 批量函数的缓冲区为交错排列的 `经度, 纬度` 对，`len` 为点数；输入与输出可以是同一缓冲区
+//! (原地转换)，但不能部分重叠。
+//! Batch buffers hold interleaved `lon, lat` pairs and `len` counts points; input and output may
+//! be the same buffer (in-place conversion) but must not otherwise overlap.
+
+use core::ffi::c_char;
+
+use crate::{CoordSystem, TransformError};
+
+/// C中的坐标点
+/// Point passed to and from C
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CtPoint {
+    /// 经度或x Longitude or x
+    pub lon: f64,
+    /// 纬度或y Latitude or y
+    pub lat: f64,
+}
+
+impl From<(f64, f64)> for CtPoint {
+    fn from((lon, lat): (f64, f64)) -> Self {
+        CtPoint { lon, lat }
+    }
+}
+
+/// 成功 Success
+pub const CT_OK: i32 = 0;
+/// 空指针 A null pointer was passed
+pub const CT_ERROR_NULL_POINTER: i32 = 1;
+/// 未知的坐标系编号 Unknown coordinate system id
+pub const CT_ERROR_UNKNOWN_SYSTEM: i32 = 2;
+/// 坐标为NaN或无穷大 A coordinate is NaN or infinite
+pub const CT_ERROR_NON_FINITE: i32 = 3;
+/// 坐标超出有效范围 A coordinate is out of range
+pub const CT_ERROR_OUT_OF_RANGE: i32 = 4;
+/// 迭代求逆未收敛 The iterative inverse did not converge
+pub const CT_ERROR_NOT_CONVERGED: i32 = 5;
+
+/// WGS84坐标系编号 WGS84 system id
+pub const CT_WGS84: i32 = 0;
+/// GCJ02坐标系编号 GCJ02 system id
+pub const CT_GCJ02: i32 = 1;
+/// BD09坐标系编号 BD09 system id
+pub const CT_BD09: i32 = 2;
+/// EPSG:3857坐标系编号 EPSG:3857 system id
+pub const CT_EPSG3857: i32 = 3;
+
+/// 生成导出函数及头文件所用的转换表
+/// Generate the exported functions and the conversion table used for the header
+macro_rules! capi_conversions {
+    ($($name:ident => $single:ident, $batch:ident, $title:literal;)*) => {
+        $(
+            #[doc = concat!($title, "，单点转换")]
+            #[doc = ""]
+            #[doc = concat!("C version of [`", stringify!($name), "`](crate::", stringify!($name), ").")]
+            #[unsafe(no_mangle)]
+            pub extern "C" fn $single(lon: f64, lat: f64) -> CtPoint {
+                crate::$name(lon, lat).into()
+            }
+
+            #[doc = concat!($title, "，批量转换")]
+            #[doc = ""]
+            #[doc = concat!("Batch C version of [`", stringify!($name), "`](crate::", stringify!($name), ").")]
+            #[doc = ""]
+            #[doc = "# Safety"]
+            #[doc = ""]
+            #[doc = "`input` 与 `output` 须各指向 `2 * len` 个有效的 `double`"]
+            #[doc = "`input` and `output` must each point to `2 * len` valid `double`s"]
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn $batch(input: *const f64, output: *mut f64, len: usize) -> i32 {
+                // SAFETY: 由调用方保证 Guaranteed by the caller
+                unsafe { map_buffer(input, output, len, crate::$name) }
+            }
+        )*
+
+        /// 导出的转换函数及其说明 Exported conversion functions and their titles
+        #[cfg(test)]
+        const CONVERSIONS: &[(&str, &str, &str)] = &[
+            $((stringify!($single), stringify!($batch), $title)),*
+        ];
+    };
+}
+
+capi_conversions! {
+    bd09_to_gcj02 => ct_bd09_to_gcj02, ct_bd09_to_gcj02_batch, "百度坐标系 -> 火星坐标系";
+    gcj02_to_bd09 => ct_gcj02_to_bd09, ct_gcj02_to_bd09_batch, "火星坐标系 -> 百度坐标系";
+    wgs84_to_gcj02 => ct_wgs84_to_gcj02, ct_wgs84_to_gcj02_batch, "WGS84坐标系 -> 火星坐标系";
+    gcj02_to_wgs84 => ct_gcj02_to_wgs84, ct_gcj02_to_wgs84_batch, "火星坐标系 -> WGS84坐标系";
+    bd09_to_wgs84 => ct_bd09_to_wgs84, ct_bd09_to_wgs84_batch, "百度坐标系 -> WGS84坐标系";
+    wgs84_to_bd09 => ct_wgs84_to_bd09, ct_wgs84_to_bd09_batch, "WGS84坐标系 -> 百度坐标系";
+    wgs84_to_epsg3857 => ct_wgs84_to_epsg3857, ct_wgs84_to_epsg3857_batch, "WGS84坐标系 -> EPSG:3857坐标系";
+    epsg3857_to_wgs84 => ct_epsg3857_to_wgs84, ct_epsg3857_to_wgs84_batch, "EPSG:3857坐标系 -> WGS84坐标系";
+    gcj02_to_epsg3857 => ct_gcj02_to_epsg3857, ct_gcj02_to_epsg3857_batch, "GCJ02坐标系 -> EPSG:3857坐标系";
+    epsg3857_to_gcj02 => ct_epsg3857_to_gcj02, ct_epsg3857_to_gcj02_batch, "EPSG:3857坐标系 -> GCJ02坐标系";
+    bd09_to_epsg3857 => ct_bd09_to_epsg3857, ct_bd09_to_epsg3857_batch, "BD09坐标系 -> EPSG:3857坐标系";
+    epsg3857_to_bd09 => ct_epsg3857_to_bd09, ct_epsg3857_to_bd09_batch, "EPSG:3857坐标系 -> BD09坐标系";
+    gcj02_to_wgs84_precise => ct_gcj02_to_wgs84_precise, ct_gcj02_to_wgs84_precise_batch, "火星坐标系 -> WGS84坐标系 (迭代求逆)";
+    bd09_to_gcj02_precise => ct_bd09_to_gcj02_precise, ct_bd09_to_gcj02_precise_batch, "百度坐标系 -> 火星坐标系 (迭代求逆)";
+    bd09_to_wgs84_precise => ct_bd09_to_wgs84_precise, ct_bd09_to_wgs84_precise_batch, "百度坐标系 -> WGS84坐标系 (迭代求逆)";
+    bd09_to_epsg3857_precise => ct_bd09_to_epsg3857_precise, ct_bd09_to_epsg3857_precise_batch, "BD09坐标系 -> EPSG:3857坐标系 (迭代求逆)";
+    gcj02_to_epsg3857_precise => ct_gcj02_to_epsg3857_precise, ct_gcj02_to_epsg3857_precise_batch, "GCJ02坐标系 -> EPSG:3857坐标系 (迭代求逆)";
+    bd09_to_bd09mc => ct_bd09_to_bd09mc, ct_bd09_to_bd09mc_batch, "百度坐标系 -> 百度墨卡托坐标系";
+    bd09mc_to_bd09 => ct_bd09mc_to_bd09, ct_bd09mc_to_bd09_batch, "百度墨卡托坐标系 -> 百度坐标系";
+    wgs84_to_bd09mc => ct_wgs84_to_bd09mc, ct_wgs84_to_bd09mc_batch, "WGS84坐标系 -> 百度墨卡托坐标系";
+    bd09mc_to_wgs84 => ct_bd09mc_to_wgs84, ct_bd09mc_to_wgs84_batch, "百度墨卡托坐标系 -> WGS84坐标系";
+    gcj02_to_bd09mc => ct_gcj02_to_bd09mc, ct_gcj02_to_bd09mc_batch, "火星坐标系 -> 百度墨卡托坐标系";
+    bd09mc_to_gcj02 => ct_bd09mc_to_gcj02, ct_bd09mc_to_gcj02_batch, "百度墨卡托坐标系 -> 火星坐标系";
+}
+
+/// 任意两个坐标系之间的转换
+/// Conversion between any two coordinate systems
+///
+/// # Safety
+///
+/// `out` 须为空或指向有效的 `CtPoint` `out` must be null or point to a valid `CtPoint`
+///
+/// # Return Value 返回值
+///
+/// `CT_OK`、`CT_ERROR_NULL_POINTER` 或 `CT_ERROR_UNKNOWN_SYSTEM`
+/// `CT_OK`, `CT_ERROR_NULL_POINTER` or `CT_ERROR_UNKNOWN_SYSTEM`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_convert(
+    from: i32,
+    to: i32,
+    lon: f64,
+    lat: f64,
+    out: *mut CtPoint,
+) -> i32 {
+    let (Some(from), Some(to)) = (system(from), system(to)) else {
+        return CT_ERROR_UNKNOWN_SYSTEM;
+    };
+    if out.is_null() {
+        return CT_ERROR_NULL_POINTER;
+    }
+    // SAFETY: `out` 非空且由调用方保证有效 `out` is non-null and valid per the caller
+    unsafe { out.write(crate::convert(from, to, lon, lat).into()) };
+    CT_OK
+}
+
+/// 校验输入的任意两个坐标系之间的转换，失败时不写入 `out`
+/// Validated conversion between any two coordinate systems; `out` is left untouched on failure
+///
+/// # Safety
+///
+/// `out` 须为空或指向有效的 `CtPoint` `out` must be null or point to a valid `CtPoint`
+///
+/// # Return Value 返回值
+///
+/// `CT_OK` 或错误码 `CT_OK` or an error code
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_try_convert(
+    from: i32,
+    to: i32,
+    lon: f64,
+    lat: f64,
+    out: *mut CtPoint,
+) -> i32 {
+    let (Some(from), Some(to)) = (system(from), system(to)) else {
+        return CT_ERROR_UNKNOWN_SYSTEM;
+    };
+    if out.is_null() {
+        return CT_ERROR_NULL_POINTER;
+    }
+    match crate::try_convert(from, to, lon, lat) {
+        Ok(point) => {
+            // SAFETY: `out` 非空且由调用方保证有效 `out` is non-null and valid per the caller
+            unsafe { out.write(point.into()) };
+            CT_OK
+        }
+        Err(err) => error_code(err),
+    }
+}
+
+/// 任意两个坐标系之间的批量转换
+/// Batch conversion between any two coordinate systems
+///
+/// # Safety
+///
+/// `input` 与 `output` 须各指向 `2 * len` 个有效的 `double`
+/// `input` and `output` must each point to `2 * len` valid `double`s
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_convert_batch(
+    from: i32,
+    to: i32,
+    input: *const f64,
+    output: *mut f64,
+    len: usize,
+) -> i32 {
+    let (Some(from), Some(to)) = (system(from), system(to)) else {
+        return CT_ERROR_UNKNOWN_SYSTEM;
+    };
+    // SAFETY: 由调用方保证 Guaranteed by the caller
+    unsafe {
+        map_buffer(input, output, len, |lon, lat| {
+            crate::convert(from, to, lon, lat)
+        })
+    }
+}
+
+/// 错误码的英文说明，返回静态字符串
+/// English description of an error code, as a static string
+#[unsafe(no_mangle)]
+pub extern "C" fn ct_error_message(code: i32) -> *const c_char {
+    let message = match code {
+        CT_OK => c"success",
+        CT_ERROR_NULL_POINTER => c"null pointer",
+        CT_ERROR_UNKNOWN_SYSTEM => c"unknown coordinate system",
+        CT_ERROR_NON_FINITE => c"coordinate is NaN or infinite",
+        CT_ERROR_OUT_OF_RANGE => c"coordinate is out of range",
+        CT_ERROR_NOT_CONVERGED => c"inverse did not converge",
+        _ => c"unknown error",
+    };
+    message.as_ptr()
+}
+
+/// 库版本号，返回静态字符串
+/// Library version, as a static string
+#[unsafe(no_mangle)]
+pub extern "C" fn ct_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+fn system(id: i32) -> Option<CoordSystem> {
+    match id {
+        CT_WGS84 => Some(CoordSystem::Wgs84),
+        CT_GCJ02 => Some(CoordSystem::Gcj02),
+        CT_BD09 => Some(CoordSystem::Bd09),
+        CT_EPSG3857 => Some(CoordSystem::Epsg3857),
+        _ => None,
+    }
+}
+
+fn error_code(err: TransformError) -> i32 {
+    match err {
+        TransformError::NonFinite => CT_ERROR_NON_FINITE,
+        TransformError::LatitudeOutOfRange(_)
+        | TransformError::LongitudeOutOfRange(_)
+        | TransformError::MercatorLatitudeClamped(_) => CT_ERROR_OUT_OF_RANGE,
+        TransformError::NotConverged { .. } => CT_ERROR_NOT_CONVERGED,
+    }
+}
+
+/// 逐点转换交错缓冲区；按元素读写，因此输入与输出可以相同
+/// Convert an interleaved buffer point by point; elements are read and written individually so
+/// input and output may be the same buffer
+///
+/// # Safety
+///
+/// `input` 与 `output` 须各指向 `2 * len` 个有效的 `f64`
+/// `input` and `output` must each point to `2 * len` valid `f64`s
+unsafe fn map_buffer<F>(input: *const f64, output: *mut f64, len: usize, f: F) -> i32
+where
+    F: Fn(f64, f64) -> (f64, f64),
+{
+    if len == 0 {
+        return CT_OK;
+    }
+    if input.is_null() || output.is_null() {
+        return CT_ERROR_NULL_POINTER;
+    }
+    for index in 0..len {
+        // SAFETY: `2 * index + 1 < 2 * len`，由调用方保证有效
+        // `2 * index + 1 < 2 * len`, valid per the caller
+        unsafe {
+            let (lon, lat) = f(*input.add(2 * index), *input.add(2 * index + 1));
+            *output.add(2 * index) = lon;
+            *output.add(2 * index + 1) = lat;
+        }
+    }
+    CT_OK
+}
+
+/// 由转换表生成C头文件
+/// Generate the C header from the conversion table
+#[cfg(test)]
+fn header() -> String {
+    use std::fmt::Write;
+
+    let mut header = String::from(
+        "/* coordtransform C API. Generated from src/capi.rs; do not edit. */\n\
+         \n\
+         #ifndef COORDTRANSFORM_H\n\
+         #define COORDTRANSFORM_H\n\
+         \n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\
+         \n\
+         #ifdef __cplusplus\n\
+         extern \"C\" {\n\
+         #endif\n\
+         \n",
+    );
+    let constants = [
+        ("CT_OK", CT_OK),
+        ("CT_ERROR_NULL_POINTER", CT_ERROR_NULL_POINTER),
+        ("CT_ERROR_UNKNOWN_SYSTEM", CT_ERROR_UNKNOWN_SYSTEM),
+        ("CT_ERROR_NON_FINITE", CT_ERROR_NON_FINITE),
+        ("CT_ERROR_OUT_OF_RANGE", CT_ERROR_OUT_OF_RANGE),
+        ("CT_ERROR_NOT_CONVERGED", CT_ERROR_NOT_CONVERGED),
+        ("", 0),
+        ("CT_WGS84", CT_WGS84),
+        ("CT_GCJ02", CT_GCJ02),
+        ("CT_BD09", CT_BD09),
+        ("CT_EPSG3857", CT_EPSG3857),
+    ];
+    for (name, value) in constants {
+        match name {
+            "" => header.push('\n'),
+            name => writeln!(header, "#define {} {}", name, value).unwrap(),
+        }
+    }
+    header.push_str(
+        "\n\
+         /* Point passed to and from C: longitude/latitude or x/y. */\n\
+         typedef struct CtPoint {\n\
+         \x20   double lon;\n\
+         \x20   double lat;\n\
+         } CtPoint;\n\
+         \n\
+         /*\n\
+         \x20* Batch functions convert `len` interleaved lon, lat pairs. `input` and `output` may be\n\
+         \x20* the same buffer. They return CT_OK or CT_ERROR_NULL_POINTER.\n\
+         \x20*/\n",
+    );
+    for (single, batch, title) in CONVERSIONS {
+        writeln!(header, "\n/* {} */", title).unwrap();
+        writeln!(header, "CtPoint {}(double lon, double lat);", single).unwrap();
+        writeln!(
+            header,
+            "int32_t {}(const double *input, double *output, size_t len);",
+            batch
+        )
+        .unwrap();
+    }
+    header.push_str(
+        "\n\
+         /* Conversion between any two systems; returns CT_OK or an error code. */\n\
+         int32_t ct_convert(int32_t from, int32_t to, double lon, double lat, CtPoint *out);\n\
+         \n\
+         /* Validated conversion; `out` is left untouched on failure. */\n\
+         int32_t ct_try_convert(int32_t from, int32_t to, double lon, double lat, CtPoint *out);\n\
+         \n\
+         /* Batch conversion between any two systems. */\n\
+         int32_t ct_convert_batch(int32_t from, int32_t to, const double *input, double *output,\n\
+         \x20                        size_t len);\n\
+         \n\
+         /* Static English description of an error code. */\n\
+         const char *ct_error_message(int32_t code);\n\
+         \n\
+         /* Static library version string. */\n\
+         const char *ct_version(void);\n\
+         \n\
+         #ifdef __cplusplus\n\
+         }\n\
+         #endif\n\
+         \n\
+         #endif /* COORDTRANSFORM_H */\n",
+    );
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 头文件须与转换表一致；设置 `COORDTRANSFORM_BLESS=1` 重新生成
+    /// The header must match the conversion table; set `COORDTRANSFORM_BLESS=1` to regenerate it
+    #[test]
+    fn test_header_is_current() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/coordtransform.h");
+        let generated = header();
+        if std::env::var_os("COORDTRANSFORM_BLESS").is_some() {
+            std::fs::write(path, &generated).unwrap();
+        }
+        let current = std::fs::read_to_string(path).unwrap_or_default();
+        assert!(
+            current == generated,
+            "include/coordtransform.h is out of date; rerun with COORDTRANSFORM_BLESS=1"
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(
+            ct_wgs84_to_gcj02(116.404, 39.915),
+            crate::wgs84_to_gcj02(116.404, 39.915).into()
+        );
+
+        let mut buffer = [116.404, 39.915, 121.4737, 31.2304];
+        let ptr = buffer.as_mut_ptr();
+        let status = unsafe { ct_bd09_to_wgs84_batch(ptr.cast_const(), ptr, 2) };
+        assert_eq!(status, CT_OK);
+        let (lon, lat) = crate::bd09_to_wgs84(121.4737, 31.2304);
+        assert_eq!(&buffer[2..], &[lon, lat]);
+
+        let mut out = CtPoint::default();
+        assert_eq!(
+            unsafe { ct_convert(CT_BD09, CT_EPSG3857, 116.404, 39.915, &mut out) },
+            CT_OK
+        );
+        assert_eq!(out, crate::bd09_to_epsg3857(116.404, 39.915).into());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut out = CtPoint::default();
+        unsafe {
+            assert_eq!(
+                ct_convert(CT_WGS84, 9, 0.0, 0.0, &mut out),
+                CT_ERROR_UNKNOWN_SYSTEM
+            );
+            assert_eq!(
+                ct_convert(CT_WGS84, CT_GCJ02, 0.0, 0.0, core::ptr::null_mut()),
+                CT_ERROR_NULL_POINTER
+            );
+            assert_eq!(
+                ct_try_convert(CT_WGS84, CT_GCJ02, f64::NAN, 0.0, &mut out),
+                CT_ERROR_NON_FINITE
+            );
+            assert_eq!(
+                ct_try_convert(CT_WGS84, CT_GCJ02, 0.0, 91.0, &mut out),
+                CT_ERROR_OUT_OF_RANGE
+            );
+            assert_eq!(
+                ct_wgs84_to_gcj02_batch(core::ptr::null(), core::ptr::null_mut(), 1),
+                CT_ERROR_NULL_POINTER
+            );
+            assert_eq!(
+                ct_wgs84_to_gcj02_batch(core::ptr::null(), core::ptr::null_mut(), 0),
+                CT_OK
+            );
+            let message = core::ffi::CStr::from_ptr(ct_error_message(CT_ERROR_NON_FINITE));
+            assert_eq!(message.to_str(), Ok("coordinate is NaN or infinite"));
+            let version = core::ffi::CStr::from_ptr(ct_version());
+            assert_eq!(version.to_str(), Ok(env!("CARGO_PKG_VERSION")));
+        }
+        assert_eq!(out, CtPoint::default());
+    }
+}
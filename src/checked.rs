@@ -0,0 +1,509 @@
+//! 带输入校验的可失败转换
+//! Fallible conversions with input validation
+//!
+//! 普通转换函数对NaN、无穷大和超出范围的经纬度不做检查，
+//! [`wgs84_to_epsg3857`](crate::wgs84_to_epsg3857) 还会静默截断纬度。
+//! 本模块的 `try_*` 函数在这些情况下返回 [`TransformError`]。
+//!
+//! The plain conversion functions accept NaN, infinities and out-of-range coordinates, and
+//! [`wgs84_to_epsg3857`](crate::wgs84_to_epsg3857) silently clamps latitude. The `try_*`
+//! functions in this module return a [`TransformError`] in these cases instead.
+
+use core::f64::consts::PI;
+
+use crate::{
+    CoordSystem, EARTH_RADIUS, InverseOptions, InverseResult, MAX_LATITUDE, TransformError,
+    bd09_to_gcj02, bd09_to_gcj02_iterative, bd09_to_wgs84, epsg3857_to_wgs84, gcj02_to_bd09,
+    gcj02_to_wgs84, gcj02_to_wgs84_iterative, wgs84_to_bd09, wgs84_to_epsg3857, wgs84_to_gcj02,
+};
+
+/// EPSG:3857 X坐标的最大绝对值 (米)，对应经度180度
+/// Maximum absolute EPSG:3857 X coordinate in meters, matching 180 degrees of longitude
+const MAX_MERCATOR_X: f64 = EARTH_RADIUS * PI;
+
+/// 百度坐标系 -> 火星坐标系 (校验输入)
+/// Baidu Coordinate System -> Mars Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 经纬度非有限值或超出范围时返回错误
+/// Returns an error if the coordinates are not finite or out of range
+pub fn try_bd09_to_gcj02(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    Ok(bd09_to_gcj02(lon, lat))
+}
+
+/// 火星坐标系 -> 百度坐标系 (校验输入)
+/// Mars Coordinate System -> Baidu Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 经纬度非有限值或超出范围时返回错误
+/// Returns an error if the coordinates are not finite or out of range
+pub fn try_gcj02_to_bd09(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    Ok(gcj02_to_bd09(lon, lat))
+}
+
+/// WGS84坐标系 -> 火星坐标系 (校验输入)
+/// WGS84 Coordinate System -> Mars Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 经纬度非有限值或超出范围时返回错误
+/// Returns an error if the coordinates are not finite or out of range
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{try_wgs84_to_gcj02, TransformError};
+///
+/// assert!(try_wgs84_to_gcj02(116.404, 39.915).is_ok());
+/// assert_eq!(
+///     try_wgs84_to_gcj02(116.404, 139.915),
+///     Err(TransformError::LatitudeOutOfRange(139.915))
+/// );
+/// ```
+pub fn try_wgs84_to_gcj02(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    Ok(wgs84_to_gcj02(lon, lat))
+}
+
+/// 火星坐标系 -> WGS84坐标系 (校验输入)
+/// Mars Coordinate System -> WGS84 Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 经纬度非有限值或超出范围时返回错误
+/// Returns an error if the coordinates are not finite or out of range
+pub fn try_gcj02_to_wgs84(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    Ok(gcj02_to_wgs84(lon, lat))
+}
+
+/// 百度坐标系 -> WGS84坐标系 (校验输入)
+/// Baidu Coordinate System -> WGS84 Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 经纬度非有限值或超出范围时返回错误
+/// Returns an error if the coordinates are not finite or out of range
+pub fn try_bd09_to_wgs84(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    Ok(bd09_to_wgs84(lon, lat))
+}
+
+/// WGS84坐标系 -> 百度坐标系 (校验输入)
+/// WGS84 Coordinate System -> Baidu Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 经纬度非有限值或超出范围时返回错误
+/// Returns an error if the coordinates are not finite or out of range
+pub fn try_wgs84_to_bd09(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    Ok(wgs84_to_bd09(lon, lat))
+}
+
+/// WGS84坐标系 -> EPSG:3857坐标系 (校验输入)
+/// WGS84 Coordinate System -> EPSG:3857 Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 经纬度非有限值或超出范围时返回错误；纬度超出Web墨卡托投影范围时返回
+/// [`TransformError::MercatorLatitudeClamped`] 而不是截断纬度。
+/// Returns an error if the coordinates are not finite or out of range; a latitude beyond the Web
+/// Mercator range yields [`TransformError::MercatorLatitudeClamped`] instead of being clamped.
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{try_wgs84_to_epsg3857, TransformError};
+///
+/// assert_eq!(
+///     try_wgs84_to_epsg3857(0.0, 89.0),
+///     Err(TransformError::MercatorLatitudeClamped(89.0))
+/// );
+/// ```
+pub fn try_wgs84_to_epsg3857(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    check_mercator_latitude(lat)?;
+    Ok(wgs84_to_epsg3857(lon, lat))
+}
+
+/// EPSG:3857坐标系 -> WGS84坐标系 (校验输入)
+/// EPSG:3857 Coordinate System -> WGS84 Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 坐标非有限值或X坐标对应的经度超出范围时返回错误
+/// Returns an error if the coordinates are not finite or X maps to a longitude out of range
+pub fn try_epsg3857_to_wgs84(x: f64, y: f64) -> Result<(f64, f64), TransformError> {
+    check_mercator(x, y)?;
+    Ok(epsg3857_to_wgs84(x, y))
+}
+
+/// GCJ02坐标系 -> EPSG:3857坐标系 (校验输入)
+/// GCJ02 Coordinate System -> EPSG:3857 Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_wgs84_to_epsg3857`] Same as [`try_wgs84_to_epsg3857`]
+pub fn try_gcj02_to_epsg3857(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    let (lon, lat) = try_gcj02_to_wgs84(lon, lat)?;
+    try_wgs84_to_epsg3857(lon, lat)
+}
+
+/// EPSG:3857坐标系 -> GCJ02坐标系 (校验输入)
+/// EPSG:3857 Coordinate System -> GCJ02 Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_epsg3857_to_wgs84`] Same as [`try_epsg3857_to_wgs84`]
+pub fn try_epsg3857_to_gcj02(x: f64, y: f64) -> Result<(f64, f64), TransformError> {
+    let (lon, lat) = try_epsg3857_to_wgs84(x, y)?;
+    Ok(wgs84_to_gcj02(lon, lat))
+}
+
+/// BD09坐标系 -> EPSG:3857坐标系 (校验输入)
+/// BD09 Coordinate System -> EPSG:3857 Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_wgs84_to_epsg3857`] Same as [`try_wgs84_to_epsg3857`]
+pub fn try_bd09_to_epsg3857(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    let (lon, lat) = try_bd09_to_wgs84(lon, lat)?;
+    try_wgs84_to_epsg3857(lon, lat)
+}
+
+/// EPSG:3857坐标系 -> BD09坐标系 (校验输入)
+/// EPSG:3857 Coordinate System -> BD09 Coordinate System (validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_epsg3857_to_wgs84`] Same as [`try_epsg3857_to_wgs84`]
+pub fn try_epsg3857_to_bd09(x: f64, y: f64) -> Result<(f64, f64), TransformError> {
+    let (lon, lat) = try_epsg3857_to_wgs84(x, y)?;
+    Ok(wgs84_to_bd09(lon, lat))
+}
+
+/// gcj02火星坐标系 -> WGS84坐标系 (高精度，校验输入与收敛)
+/// GCJ02 Coordinate System -> WGS84 Coordinate System (high precision, validated)
+///
+/// 使用默认迭代参数，见 [`try_gcj02_to_wgs84_precise_with`]
+/// Uses the default iteration options, see [`try_gcj02_to_wgs84_precise_with`]
+///
+/// # Errors 错误
+///
+/// 经纬度非有限值或超出范围时返回错误；迭代未收敛时返回 [`TransformError::NotConverged`]
+/// Returns an error if the coordinates are not finite or out of range, and
+/// [`TransformError::NotConverged`] if the iteration does not converge
+pub fn try_gcj02_to_wgs84_precise(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    try_gcj02_to_wgs84_precise_with(lon, lat, InverseOptions::default())
+}
+
+/// gcj02火星坐标系 -> WGS84坐标系 (高精度，指定迭代参数，校验输入与收敛)
+/// GCJ02 Coordinate System -> WGS84 Coordinate System (high precision with the given options,
+/// validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_gcj02_to_wgs84_precise`] Same as [`try_gcj02_to_wgs84_precise`]
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{try_gcj02_to_wgs84_precise_with, InverseOptions, TransformError};
+///
+/// let options = InverseOptions::new(1e-12, 1);
+/// let result = try_gcj02_to_wgs84_precise_with(116.404, 39.915, options);
+/// assert!(matches!(result, Err(TransformError::NotConverged { iterations: 1, .. })));
+/// ```
+pub fn try_gcj02_to_wgs84_precise_with(
+    lon: f64,
+    lat: f64,
+    options: InverseOptions,
+) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    converged(gcj02_to_wgs84_iterative(lon, lat, options))
+}
+
+/// 百度坐标系 -> 火星坐标系 (高精度，校验输入与收敛)
+/// Baidu Coordinate System -> Mars Coordinate System (high precision, validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_gcj02_to_wgs84_precise`] Same as [`try_gcj02_to_wgs84_precise`]
+pub fn try_bd09_to_gcj02_precise(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    try_bd09_to_gcj02_precise_with(lon, lat, InverseOptions::default())
+}
+
+/// 百度坐标系 -> 火星坐标系 (高精度，指定迭代参数，校验输入与收敛)
+/// Baidu Coordinate System -> Mars Coordinate System (high precision with the given options,
+/// validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_gcj02_to_wgs84_precise`] Same as [`try_gcj02_to_wgs84_precise`]
+pub fn try_bd09_to_gcj02_precise_with(
+    lon: f64,
+    lat: f64,
+    options: InverseOptions,
+) -> Result<(f64, f64), TransformError> {
+    check_lon_lat(lon, lat)?;
+    converged(bd09_to_gcj02_iterative(lon, lat, options))
+}
+
+/// 百度坐标系 -> WGS84坐标系 (高精度，校验输入与收敛)
+/// Baidu Coordinate System -> WGS84 Coordinate System (high precision, validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_gcj02_to_wgs84_precise`] Same as [`try_gcj02_to_wgs84_precise`]
+pub fn try_bd09_to_wgs84_precise(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    try_bd09_to_wgs84_precise_with(lon, lat, InverseOptions::default())
+}
+
+/// 百度坐标系 -> WGS84坐标系 (高精度，指定迭代参数，校验输入与收敛)
+/// Baidu Coordinate System -> WGS84 Coordinate System (high precision with the given options,
+/// validated)
+///
+/// 两个迭代步骤使用相同的参数。
+/// Both iterative steps use the same options.
+///
+/// # Errors 错误
+///
+/// 同 [`try_gcj02_to_wgs84_precise`] Same as [`try_gcj02_to_wgs84_precise`]
+pub fn try_bd09_to_wgs84_precise_with(
+    lon: f64,
+    lat: f64,
+    options: InverseOptions,
+) -> Result<(f64, f64), TransformError> {
+    let (lon, lat) = try_bd09_to_gcj02_precise_with(lon, lat, options)?;
+    try_gcj02_to_wgs84_precise_with(lon, lat, options)
+}
+
+/// BD09坐标系 -> EPSG:3857坐标系 (高精度，校验输入与收敛)
+/// BD09 Coordinate System -> EPSG:3857 Coordinate System (high precision, validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_gcj02_to_wgs84_precise`] 及 [`try_wgs84_to_epsg3857`]
+/// Same as [`try_gcj02_to_wgs84_precise`] and [`try_wgs84_to_epsg3857`]
+pub fn try_bd09_to_epsg3857_precise(lon: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    try_bd09_to_epsg3857_precise_with(lon, lat, InverseOptions::default())
+}
+
+/// BD09坐标系 -> EPSG:3857坐标系 (高精度，指定迭代参数，校验输入与收敛)
+/// BD09 Coordinate System -> EPSG:3857 Coordinate System (high precision with the given options,
+/// validated)
+///
+/// # Errors 错误
+///
+/// 同 [`try_gcj02_to_wgs84_precise`] 及 [`try_wgs84_to_epsg3857`]
+/// Same as [`try_gcj02_to_wgs84_precise`] and [`try_wgs84_to_epsg3857`]
+pub fn try_bd09_to_epsg3857_precise_with(
+    lon: f64,
+    lat: f64,
+    options: InverseOptions,
+) -> Result<(f64, f64), TransformError> {
+    let (lon, lat) = try_bd09_to_wgs84_precise_with(lon, lat, options)?;
+    try_wgs84_to_epsg3857(lon, lat)
+}
+
+/// 任意两个坐标系之间的转换 (校验输入)
+/// Conversion between any two coordinate systems (validated)
+///
+/// # Errors 错误
+///
+/// 同对应的 `try_*` 函数 Same as the matching `try_*` function
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{try_convert, CoordSystem, TransformError};
+///
+/// let result = try_convert(CoordSystem::Gcj02, CoordSystem::Wgs84, f64::NAN, 39.915);
+/// assert_eq!(result, Err(TransformError::NonFinite));
+/// ```
+pub fn try_convert(
+    from: CoordSystem,
+    to: CoordSystem,
+    lon: f64,
+    lat: f64,
+) -> Result<(f64, f64), TransformError> {
+    use CoordSystem::*;
+
+    match (from, to) {
+        (Epsg3857, Epsg3857) => check_mercator(lon, lat).map(|_| (lon, lat)),
+        (Wgs84, Wgs84) | (Gcj02, Gcj02) | (Bd09, Bd09) => {
+            check_lon_lat(lon, lat).map(|_| (lon, lat))
+        }
+        (Wgs84, Gcj02) => try_wgs84_to_gcj02(lon, lat),
+        (Wgs84, Bd09) => try_wgs84_to_bd09(lon, lat),
+        (Wgs84, Epsg3857) => try_wgs84_to_epsg3857(lon, lat),
+        (Gcj02, Wgs84) => try_gcj02_to_wgs84(lon, lat),
+        (Gcj02, Bd09) => try_gcj02_to_bd09(lon, lat),
+        (Gcj02, Epsg3857) => try_gcj02_to_epsg3857(lon, lat),
+        (Bd09, Wgs84) => try_bd09_to_wgs84(lon, lat),
+        (Bd09, Gcj02) => try_bd09_to_gcj02(lon, lat),
+        (Bd09, Epsg3857) => try_bd09_to_epsg3857(lon, lat),
+        (Epsg3857, Wgs84) => try_epsg3857_to_wgs84(lon, lat),
+        (Epsg3857, Gcj02) => try_epsg3857_to_gcj02(lon, lat),
+        (Epsg3857, Bd09) => try_epsg3857_to_bd09(lon, lat),
+    }
+}
+
+/// 校验经纬度 Validate longitude and latitude
+fn check_lon_lat(lon: f64, lat: f64) -> Result<(), TransformError> {
+    if !lon.is_finite() || !lat.is_finite() {
+        return Err(TransformError::NonFinite);
+    }
+    if !(-90.0..=90.0).contains(&lat) {
+        return Err(TransformError::LatitudeOutOfRange(lat));
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        return Err(TransformError::LongitudeOutOfRange(lon));
+    }
+    Ok(())
+}
+
+/// 校验纬度是否在Web墨卡托投影范围内 Validate that latitude is within the Web Mercator range
+fn check_mercator_latitude(lat: f64) -> Result<(), TransformError> {
+    if lat.abs() > MAX_LATITUDE {
+        return Err(TransformError::MercatorLatitudeClamped(lat));
+    }
+    Ok(())
+}
+
+/// 校验EPSG:3857坐标 Validate EPSG:3857 coordinates
+fn check_mercator(x: f64, y: f64) -> Result<(), TransformError> {
+    if !x.is_finite() || !y.is_finite() {
+        return Err(TransformError::NonFinite);
+    }
+    if x.abs() > MAX_MERCATOR_X {
+        return Err(TransformError::LongitudeOutOfRange(
+            x / EARTH_RADIUS * 180.0 / PI,
+        ));
+    }
+    Ok(())
+}
+
+/// 将迭代结果转换为 `Result` Turn an iteration result into a `Result`
+fn converged(result: InverseResult) -> Result<(f64, f64), TransformError> {
+    if result.converged {
+        Ok(result.coords())
+    } else {
+        Err(TransformError::NotConverged {
+            residual: result.residual,
+            iterations: result.iterations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_functions_match_plain_functions() {
+        let (lon, lat) = (116.404, 39.915);
+        assert_eq!(try_wgs84_to_gcj02(lon, lat), Ok(wgs84_to_gcj02(lon, lat)));
+        assert_eq!(try_bd09_to_wgs84(lon, lat), Ok(bd09_to_wgs84(lon, lat)));
+        assert_eq!(
+            try_convert(CoordSystem::Gcj02, CoordSystem::Epsg3857, lon, lat),
+            Ok(crate::gcj02_to_epsg3857(lon, lat))
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            try_wgs84_to_gcj02(f64::NAN, 39.915),
+            Err(TransformError::NonFinite)
+        );
+        assert_eq!(
+            try_gcj02_to_bd09(116.404, f64::NEG_INFINITY),
+            Err(TransformError::NonFinite)
+        );
+        assert_eq!(
+            try_bd09_to_gcj02(116.404, -90.5),
+            Err(TransformError::LatitudeOutOfRange(-90.5))
+        );
+        assert_eq!(
+            try_gcj02_to_wgs84(216.404, 39.915),
+            Err(TransformError::LongitudeOutOfRange(216.404))
+        );
+        assert_eq!(
+            try_epsg3857_to_wgs84(f64::INFINITY, 0.0),
+            Err(TransformError::NonFinite)
+        );
+        assert!(matches!(
+            try_epsg3857_to_gcj02(3.0e7, 0.0),
+            Err(TransformError::LongitudeOutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_mercator_latitude_clamped() {
+        assert_eq!(
+            try_wgs84_to_epsg3857(0.0, -86.0),
+            Err(TransformError::MercatorLatitudeClamped(-86.0))
+        );
+        assert!(try_wgs84_to_epsg3857(0.0, MAX_LATITUDE).is_ok());
+        assert!(try_convert(CoordSystem::Wgs84, CoordSystem::Epsg3857, 0.0, 88.0).is_err());
+    }
+
+    #[test]
+    fn test_not_converged() {
+        let result = converged(gcj02_to_wgs84_iterative(
+            116.404,
+            39.915,
+            InverseOptions::new(0.0, 0),
+        ));
+        assert!(matches!(
+            result,
+            Err(TransformError::NotConverged { iterations: 0, .. })
+        ));
+        assert!(try_bd09_to_wgs84_precise(116.404, 39.915).is_ok());
+    }
+
+    #[test]
+    fn test_precise_with_options() {
+        let (lon, lat) = (116.404, 39.915);
+        let one = InverseOptions {
+            max_iterations: 1,
+            ..InverseOptions::default()
+        };
+        assert!(matches!(
+            try_gcj02_to_wgs84_precise_with(lon, lat, one),
+            Err(TransformError::NotConverged { iterations: 1, .. })
+        ));
+        assert!(matches!(
+            try_bd09_to_gcj02_precise_with(lon, lat, one),
+            Err(TransformError::NotConverged { iterations: 1, .. })
+        ));
+        assert!(matches!(
+            try_bd09_to_wgs84_precise_with(lon, lat, one),
+            Err(TransformError::NotConverged { .. })
+        ));
+        assert!(matches!(
+            try_bd09_to_epsg3857_precise_with(lon, lat, one),
+            Err(TransformError::NotConverged { .. })
+        ));
+        assert_eq!(
+            try_gcj02_to_wgs84_precise_with(f64::NAN, lat, one),
+            Err(TransformError::NonFinite)
+        );
+
+        let default = InverseOptions::default();
+        assert_eq!(
+            try_gcj02_to_wgs84_precise_with(lon, lat, default),
+            try_gcj02_to_wgs84_precise(lon, lat)
+        );
+        assert_eq!(
+            try_bd09_to_epsg3857_precise_with(lon, lat, default),
+            try_bd09_to_epsg3857_precise(lon, lat)
+        );
+    }
+}
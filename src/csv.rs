@@ -0,0 +1,466 @@
+//! CSV文件坐标转换 (需要 `csv` 特性)
+//! CSV file conversion (requires the `csv` feature)
+//!
+//! 逐行流式读取CSV，按列名或列序号定位经纬度列并转换，结果覆盖原列或追加为新列。
+//! 引号、转义及列数不一致的行均按CSV规则处理；无法解析的行原样输出 (追加模式下新列留空)，
+//! 并记录在 [`CsvReport`] 中，不会中断整个文件的转换。
+//!
+//! Rows are streamed one at a time; the longitude/latitude columns are located by name or index,
+//! converted, and either overwritten or appended as new columns. Quoting, escapes and rows with
+//! differing field counts follow the CSV rules. Rows that cannot be parsed are written through
+//! unchanged (with empty new columns in append mode) and recorded in the [`CsvReport`] instead of
+//! aborting the whole file.
+//!
+//! ```rust
+//! use coordtransform::csv::{self, Column, CsvOptions, OutputMode};
+//! use coordtransform::CoordSystem;
+//!
+//! let input = "name,lng,lat\n\"天安门, 北京\",116.404,39.915\nunknown,,\n";
+//! let mut options = CsvOptions::new(CoordSystem::Bd09, CoordSystem::Wgs84);
+//! options.lon = Column::from("lng");
+//! options.lat = Column::from("lat");
+//! options.output = OutputMode::Append { lon: "wgs_lng".into(), lat: "wgs_lat".into() };
+//! options.precision = Some(6);
+//!
+//! let mut output = Vec::new();
+//! let report = csv::convert(input.as_bytes(), &mut output, &options).unwrap();
+//! assert_eq!(report.rows, 2);
+//! assert_eq!(report.converted, 1);
+//! assert_eq!(report.errors[0].line, 3);
+//!
+//! let output = String::from_utf8(output).unwrap();
+//! let mut lines = output.lines();
+//! assert_eq!(lines.next(), Some("name,lng,lat,wgs_lng,wgs_lat"));
+//! assert!(lines.next().unwrap().starts_with("\"天安门, 北京\",116.404,39.915,116.39"));
+//! assert_eq!(lines.next(), Some("unknown,,,,"));
+//! ```
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use ::csv::{ByteRecord, ReaderBuilder, WriterBuilder};
+
+use crate::{CoordSystem, convert as convert_coord};
+
+/// 列选择器，按表头名称或从0开始的序号定位列
+/// Column selector, locating a column by header name or zero-based index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// 表头名称，需要表头行 Header name; requires a header row
+    Name(String),
+    /// 从0开始的列序号 Zero-based column index
+    Index(usize),
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.to_string())
+    }
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+impl FromStr for Column {
+    type Err = std::convert::Infallible;
+
+    /// 纯数字解析为列序号，其余解析为列名
+    /// Digits parse as an index, anything else as a name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse::<usize>() {
+            Ok(index) => Column::Index(index),
+            Err(_) => Column::Name(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Column::Name(name) => write!(f, "`{}`", name),
+            Column::Index(index) => write!(f, "#{}", index),
+        }
+    }
+}
+
+/// 转换结果的写出方式
+/// How converted coordinates are written
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// 覆盖原经纬度列 Overwrite the source columns
+    #[default]
+    Overwrite,
+    /// 在行尾追加两列，参数为新列的表头名称
+    /// Append two columns at the end of each row, named by the given headers
+    Append {
+        /// 新经度列的表头 Header of the new longitude column
+        lon: String,
+        /// 新纬度列的表头 Header of the new latitude column
+        lat: String,
+    },
+}
+
+/// CSV转换参数
+/// CSV conversion options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// 源坐标系 Source system
+    pub from: CoordSystem,
+    /// 目标坐标系 Target system
+    pub to: CoordSystem,
+    /// 经度 (或x) 列，默认第0列 Longitude (or x) column, column 0 by default
+    pub lon: Column,
+    /// 纬度 (或y) 列，默认第1列 Latitude (or y) column, column 1 by default
+    pub lat: Column,
+    /// 首行是否为表头，默认是 Whether the first row is a header, true by default
+    pub has_headers: bool,
+    /// 字段分隔符，默认 `,` Field delimiter, `,` by default
+    pub delimiter: u8,
+    /// 写出方式 How results are written
+    pub output: OutputMode,
+    /// 输出的小数位数，默认输出完整精度 Decimal places in the output; full precision by default
+    pub precision: Option<usize>,
+    /// 是否丢弃无法解析的行，默认原样输出 Drop bad rows instead of passing them through
+    pub skip_invalid: bool,
+}
+
+impl CsvOptions {
+    /// 以默认设置创建转换参数
+    /// Create conversion options with the default settings
+    pub fn new(from: CoordSystem, to: CoordSystem) -> Self {
+        Self {
+            from,
+            to,
+            lon: Column::Index(0),
+            lat: Column::Index(1),
+            has_headers: true,
+            delimiter: b',',
+            output: OutputMode::Overwrite,
+            precision: None,
+            skip_invalid: false,
+        }
+    }
+}
+
+/// 单行转换失败的记录
+/// Record of a row that failed to convert
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// 该行在输入中的起始行号 (从1开始) Line the row starts on in the input (1-based)
+    pub line: u64,
+    /// 失败原因 Reason for the failure
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// 转换统计及逐行错误报告
+/// Conversion statistics and per-row error report
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CsvReport {
+    /// 数据行数 (不含表头) Number of data rows, excluding the header
+    pub rows: u64,
+    /// 成功转换的行数 Number of rows converted
+    pub converted: u64,
+    /// 转换失败的行 Rows that failed to convert
+    pub errors: Vec<RowError>,
+}
+
+impl CsvReport {
+    /// 是否所有行均转换成功
+    /// Whether every row was converted
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// CSV转换错误，发生时整个转换中止
+/// CSV conversion error that aborts the whole conversion
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CsvError {
+    /// 读写失败 Reading or writing failed
+    Csv(::csv::Error),
+    /// 表头中找不到该列，或未启用表头时按名称选择列
+    /// The column is not in the header, or was selected by name without a header row
+    ColumnNotFound(Column),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Csv(err) => write!(f, "CSV error: {}", err),
+            CsvError::ColumnNotFound(column) => write!(f, "column {} not found", column),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Csv(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<::csv::Error> for CsvError {
+    fn from(err: ::csv::Error) -> Self {
+        CsvError::Csv(err)
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(err: io::Error) -> Self {
+        CsvError::Csv(err.into())
+    }
+}
+
+/// 流式转换CSV数据
+/// Stream-convert CSV data
+///
+/// # Parameters 参数
+///
+/// * `reader` - CSV输入 CSV input
+/// * `writer` - CSV输出 CSV output
+/// * `options` - 转换参数 Conversion options
+///
+/// # Return Value 返回值
+///
+/// 转换报告；读写失败或找不到列时返回错误，单行错误仅记录在报告中
+/// The conversion report; an error is returned only when reading or writing fails or a column
+/// cannot be found, while bad rows are recorded in the report
+pub fn convert<R: io::Read, W: io::Write>(
+    reader: R,
+    writer: W,
+    options: &CsvOptions,
+) -> Result<CsvReport, CsvError> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(options.has_headers)
+        .delimiter(options.delimiter)
+        .flexible(true)
+        .from_reader(reader);
+    let mut writer = WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .flexible(true)
+        .from_writer(writer);
+
+    let (lon_index, lat_index) = if options.has_headers {
+        let mut headers = reader.byte_headers()?.clone();
+        let lon_index = find_column(&headers, &options.lon)?;
+        let lat_index = find_column(&headers, &options.lat)?;
+        if let OutputMode::Append { lon, lat } = &options.output {
+            headers.push_field(lon.as_bytes());
+            headers.push_field(lat.as_bytes());
+        }
+        writer.write_byte_record(&headers)?;
+        (lon_index, lat_index)
+    } else {
+        match (&options.lon, &options.lat) {
+            (Column::Index(lon), Column::Index(lat)) => (*lon, *lat),
+            (Column::Name(_), _) => return Err(CsvError::ColumnNotFound(options.lon.clone())),
+            (_, Column::Name(_)) => return Err(CsvError::ColumnNotFound(options.lat.clone())),
+        }
+    };
+
+    let mut report = CsvReport::default();
+    let mut record = ByteRecord::new();
+    let mut output = ByteRecord::new();
+    while reader.read_byte_record(&mut record)? {
+        report.rows += 1;
+        let line = record.position().map_or(0, |position| position.line());
+
+        match convert_row(&record, lon_index, lat_index, options) {
+            Ok((lon, lat)) => {
+                report.converted += 1;
+                let number = |value: f64| match options.precision {
+                    Some(precision) => format!("{:.*}", precision, value),
+                    None => value.to_string(),
+                };
+                output.clear();
+                match options.output {
+                    OutputMode::Overwrite => {
+                        for (index, field) in record.iter().enumerate() {
+                            if index == lon_index {
+                                output.push_field(number(lon).as_bytes());
+                            } else if index == lat_index {
+                                output.push_field(number(lat).as_bytes());
+                            } else {
+                                output.push_field(field);
+                            }
+                        }
+                    }
+                    OutputMode::Append { .. } => {
+                        output.extend(record.iter());
+                        output.push_field(number(lon).as_bytes());
+                        output.push_field(number(lat).as_bytes());
+                    }
+                }
+                writer.write_byte_record(&output)?;
+            }
+            Err(message) => {
+                report.errors.push(RowError { line, message });
+                if options.skip_invalid {
+                    continue;
+                }
+                match options.output {
+                    OutputMode::Overwrite => writer.write_byte_record(&record)?,
+                    OutputMode::Append { .. } => {
+                        output.clear();
+                        output.extend(record.iter());
+                        output.push_field(b"");
+                        output.push_field(b"");
+                        writer.write_byte_record(&output)?;
+                    }
+                }
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(report)
+}
+
+/// 转换CSV文本
+/// Convert a CSV text
+///
+/// # Parameters 参数
+///
+/// * `input` - CSV文本 CSV text
+/// * `options` - 转换参数 Conversion options
+///
+/// # Return Value 返回值
+///
+/// 转换后的CSV文本及转换报告
+/// The converted CSV text and the conversion report
+pub fn convert_str(input: &str, options: &CsvOptions) -> Result<(String, CsvReport), CsvError> {
+    let mut output = Vec::with_capacity(input.len());
+    let report = convert(input.as_bytes(), &mut output, options)?;
+    // 输入为UTF-8，未改动的字段及写入的数字均为UTF-8
+    // The input is UTF-8 and both untouched fields and written numbers stay UTF-8
+    let output = String::from_utf8(output).expect("CSV output is UTF-8");
+    Ok((output, report))
+}
+
+fn find_column(headers: &ByteRecord, column: &Column) -> Result<usize, CsvError> {
+    match column {
+        Column::Index(index) => Ok(*index),
+        Column::Name(name) => headers
+            .iter()
+            .position(|header| header.trim_ascii() == name.as_bytes())
+            .ok_or_else(|| CsvError::ColumnNotFound(column.clone())),
+    }
+}
+
+fn convert_row(
+    record: &ByteRecord,
+    lon_index: usize,
+    lat_index: usize,
+    options: &CsvOptions,
+) -> Result<(f64, f64), String> {
+    let lon = parse_field(record, lon_index)?;
+    let lat = parse_field(record, lat_index)?;
+    let (lon, lat) = convert_coord(options.from, options.to, lon, lat);
+    if lon.is_finite() && lat.is_finite() {
+        Ok((lon, lat))
+    } else {
+        Err(format!("({}, {}) cannot be converted", lon, lat))
+    }
+}
+
+fn parse_field(record: &ByteRecord, index: usize) -> Result<f64, String> {
+    let field = record
+        .get(index)
+        .ok_or_else(|| format!("missing column #{}", index))?;
+    let text = std::str::from_utf8(field)
+        .map_err(|_| format!("column #{} is not valid UTF-8", index))?
+        .trim();
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ if text.is_empty() => Err(format!("column #{} is empty", index)),
+        _ => Err(format!("invalid number `{}` in column #{}", text, index)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_overwrite() {
+        let input = "id;lat;lon\n1;39.915;116.404\n2;\"39.9\";\"116.3\"\n";
+        let mut options = CsvOptions::new(CoordSystem::Gcj02, CoordSystem::Bd09);
+        options.lon = Column::from("lon");
+        options.lat = Column::from("lat");
+        options.delimiter = b';';
+
+        let (output, report) = convert_str(input, &options).unwrap();
+        assert!(report.is_ok());
+        assert_eq!((report.rows, report.converted), (2, 2));
+
+        let (lon, lat) = crate::gcj02_to_bd09(116.404, 39.915);
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("id;lat;lon"));
+        assert_eq!(lines.next(), Some(format!("1;{};{}", lat, lon).as_str()));
+        assert!(lines.next().unwrap().starts_with("2;39.90"));
+    }
+
+    #[test]
+    fn test_convert_without_headers() {
+        let input = "116.404,39.915,\"a,b\"\n";
+        let mut options = CsvOptions::new(CoordSystem::Wgs84, CoordSystem::Gcj02);
+        options.has_headers = false;
+        options.precision = Some(2);
+
+        let (output, report) = convert_str(input, &options).unwrap();
+        assert_eq!(report.converted, 1);
+        assert_eq!(output, "116.41,39.92,\"a,b\"\n");
+
+        options.lon = Column::from("lon");
+        assert!(matches!(
+            convert_str(input, &options),
+            Err(CsvError::ColumnNotFound(Column::Name(_)))
+        ));
+    }
+
+    #[test]
+    fn test_bad_rows() {
+        let input = "lon,lat,name\n116.404,39.915,ok\nabc,39.9,bad\n116.4\n\"116.4\",\"\",empty\n";
+        let mut options = CsvOptions::new(CoordSystem::Bd09, CoordSystem::Gcj02);
+        options.output = OutputMode::Append {
+            lon: "x".to_string(),
+            lat: "y".to_string(),
+        };
+
+        let (output, report) = convert_str(input, &options).unwrap();
+        assert_eq!((report.rows, report.converted), (4, 1));
+        let lines: Vec<u64> = report.errors.iter().map(|err| err.line).collect();
+        assert_eq!(lines, [3, 4, 5]);
+        assert!(report.errors[0].message.contains("`abc`"));
+        assert!(output.contains("\nabc,39.9,bad,,\n"));
+
+        options.skip_invalid = true;
+        let (output, report) = convert_str(input, &options).unwrap();
+        assert_eq!(report.errors.len(), 3);
+        assert_eq!(output.lines().count(), 2);
+
+        options.lat = Column::from("latitude");
+        assert!(matches!(
+            convert_str(input, &options),
+            Err(CsvError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_column_from_str() {
+        assert_eq!("2".parse::<Column>(), Ok(Column::Index(2)));
+        assert_eq!("lng".parse::<Column>(), Ok(Column::Name("lng".to_string())));
+    }
+}
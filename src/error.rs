@@ -0,0 +1,54 @@
+//! 错误类型
+//! Error types
+
+use core::fmt;
+
+/// 坐标转换错误
+/// Coordinate transformation error
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum TransformError {
+    /// 输入包含NaN或无穷大 The input contains NaN or infinity
+    NonFinite,
+    /// 纬度超出 [-90, 90] Latitude outside [-90, 90]
+    LatitudeOutOfRange(f64),
+    /// 经度超出 [-180, 180] Longitude outside [-180, 180]
+    LongitudeOutOfRange(f64),
+    /// 纬度超出Web墨卡托投影范围，投影时会被截断
+    /// Latitude outside the Web Mercator range, it would be clamped by the projection
+    MercatorLatitudeClamped(f64),
+    /// 迭代求逆未收敛 The iterative inverse did not converge
+    NotConverged {
+        /// 达到的残差 (度) Achieved residual in degrees
+        residual: f64,
+        /// 实际迭代次数 Number of iterations performed
+        iterations: u32,
+    },
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::NonFinite => f.write_str("coordinate is NaN or infinite"),
+            TransformError::LatitudeOutOfRange(lat) => {
+                write!(f, "latitude {} is outside [-90, 90]", lat)
+            }
+            TransformError::LongitudeOutOfRange(lon) => {
+                write!(f, "longitude {} is outside [-180, 180]", lon)
+            }
+            TransformError::MercatorLatitudeClamped(lat) => {
+                write!(f, "latitude {} is outside the Web Mercator range", lat)
+            }
+            TransformError::NotConverged {
+                residual,
+                iterations,
+            } => write!(
+                f,
+                "inverse did not converge after {} iterations (residual {:e} degrees)",
+                iterations, residual
+            ),
+        }
+    }
+}
+
+impl core::error::Error for TransformError {}
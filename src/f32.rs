@@ -0,0 +1,186 @@
+//! 单精度 (`f32`) 坐标转换
+//! Single-precision (`f32`) coordinate conversion
+//!
+//! 所有转换先将输入扩展为 `f64`，按 `f64` 路径计算，再将结果舍入为 `f32`，因此结果恰好等于
+//! `f64` 结果的最近 `f32` 值。误差主要来自 `f32` 本身的分辨率：
+//!
+//! Every conversion widens its input to `f64`, runs the `f64` path and rounds the result back to
+//! `f32`, so the result is exactly the `f32` nearest to the `f64` result. The error is dominated by
+//! the resolution of `f32` itself:
+//!
+//! | 量 Quantity | `f32` 分辨率 resolution | 相对 `f64` 输入的误差上限 bound against `f64` input |
+//! |---|---|---|
+//! | 经度 Longitude (\|lon\| < 180°) | 1.5e-5° (~1.7 m) | 2e-5° (~2.2 m) |
+//! | 纬度 Latitude (\|lat\| < 64°) | 3.8e-6° (~0.4 m) | 5e-6° (~0.6 m) |
+//! | EPSG:3857 (\|x\|, \|y\| < 2.1e7 m) | 2 m | 3 m |
+//!
+//! 上限包括输入舍入为 `f32` 的误差及其经转换后的传播。需要亚米级精度时请使用 `f64` 版本。
+//! The bounds include rounding the input to `f32` and its propagation through the conversion. Use
+//! the `f64` functions when sub-meter accuracy matters.
+//!
+//! ```rust
+//! use coordtransform::CoordSystem;
+//!
+//! let (lon, lat) = coordtransform::f32::wgs84_to_gcj02(116.404, 39.915);
+//! let (lon64, lat64) = coordtransform::wgs84_to_gcj02(116.404, 39.915);
+//! assert!((lon as f64 - lon64).abs() < 2e-5 && (lat as f64 - lat64).abs() < 5e-6);
+//!
+//! // 顶点缓冲区：每个顶点依次为 经度、纬度、高度 Vertex buffer of lon, lat, altitude
+//! let mut vertices = [116.404, 39.915, 44.5, 121.4737, 31.2304, 4.0];
+//! coordtransform::f32::convert_vertices(CoordSystem::Wgs84, CoordSystem::Gcj02, &mut vertices, 3);
+//! assert_eq!((vertices[0], vertices[1]), (lon, lat));
+//! assert_eq!(vertices[2], 44.5);
+//! ```
+
+use crate::CoordSystem;
+
+/// 生成 `f32` 版本的转换函数
+/// Generate the `f32` version of a conversion function
+macro_rules! f32_conversion {
+    ($name:ident, $title:literal) => {
+        #[doc = concat!($title, " (`f32`)")]
+        #[doc = ""]
+        #[doc = concat!("`f32` version of [`", stringify!($name), "`](crate::", stringify!($name), "), computed in `f64`.")]
+        #[inline]
+        pub fn $name(lon: f32, lat: f32) -> (f32, f32) {
+            let (lon, lat) = crate::$name(f64::from(lon), f64::from(lat));
+            (lon as f32, lat as f32)
+        }
+    };
+}
+
+f32_conversion!(bd09_to_gcj02, "百度坐标系 -> 火星坐标系");
+f32_conversion!(gcj02_to_bd09, "火星坐标系 -> 百度坐标系");
+f32_conversion!(wgs84_to_gcj02, "WGS84坐标系 -> 火星坐标系");
+f32_conversion!(gcj02_to_wgs84, "火星坐标系 -> WGS84坐标系");
+f32_conversion!(bd09_to_wgs84, "百度坐标系 -> WGS84坐标系");
+f32_conversion!(wgs84_to_bd09, "WGS84坐标系 -> 百度坐标系");
+f32_conversion!(wgs84_to_epsg3857, "WGS84坐标系 -> EPSG:3857坐标系");
+f32_conversion!(epsg3857_to_wgs84, "EPSG:3857坐标系 -> WGS84坐标系");
+f32_conversion!(gcj02_to_epsg3857, "GCJ02坐标系 -> EPSG:3857坐标系");
+f32_conversion!(epsg3857_to_gcj02, "EPSG:3857坐标系 -> GCJ02坐标系");
+f32_conversion!(bd09_to_epsg3857, "BD09坐标系 -> EPSG:3857坐标系");
+f32_conversion!(epsg3857_to_bd09, "EPSG:3857坐标系 -> BD09坐标系");
+f32_conversion!(
+    gcj02_to_wgs84_precise,
+    "火星坐标系 -> WGS84坐标系 (迭代求逆)"
+);
+f32_conversion!(bd09_to_gcj02_precise, "百度坐标系 -> 火星坐标系 (迭代求逆)");
+f32_conversion!(
+    bd09_to_wgs84_precise,
+    "百度坐标系 -> WGS84坐标系 (迭代求逆)"
+);
+
+/// 任意两个坐标系之间的 `f32` 转换
+/// `f32` conversion between any two coordinate systems
+///
+/// # Parameters 参数
+///
+/// * `from` - 源坐标系 Source system
+/// * `to` - 目标坐标系 Target system
+/// * `lon` - 经度或x Longitude or x
+/// * `lat` - 纬度或y Latitude or y
+pub fn convert(from: CoordSystem, to: CoordSystem, lon: f32, lat: f32) -> (f32, f32) {
+    let (lon, lat) = crate::convert(from, to, f64::from(lon), f64::from(lat));
+    (lon as f32, lat as f32)
+}
+
+/// 任意两个坐标系之间的原地批量 `f32` 转换
+/// In-place batch `f32` conversion between any two coordinate systems
+pub fn convert_in_place(from: CoordSystem, to: CoordSystem, points: &mut [(f32, f32)]) {
+    for point in points.iter_mut() {
+        *point = convert(from, to, point.0, point.1);
+    }
+}
+
+/// 原地转换交错排列的顶点缓冲区，每个顶点的前两个分量为经度和纬度
+/// Convert an interleaved vertex buffer in place; the first two components of every vertex are
+/// the longitude and latitude
+///
+/// # Parameters 参数
+///
+/// * `from` - 源坐标系 Source system
+/// * `to` - 目标坐标系 Target system
+/// * `vertices` - 顶点缓冲区 Vertex buffer
+/// * `stride` - 每个顶点的分量数 Number of components per vertex
+///
+/// # Panics
+///
+/// `stride` 小于2或缓冲区长度不是 `stride` 的整数倍时 panic
+/// Panics if `stride` is less than 2 or the buffer length is not a multiple of `stride`
+pub fn convert_vertices(from: CoordSystem, to: CoordSystem, vertices: &mut [f32], stride: usize) {
+    assert!(stride >= 2, "a vertex needs at least two components");
+    assert!(
+        vertices.len().is_multiple_of(stride),
+        "the buffer length must be a multiple of the stride"
+    );
+    for vertex in vertices.chunks_exact_mut(stride) {
+        let (lon, lat) = convert(from, to, vertex[0], vertex[1]);
+        vertex[0] = lon;
+        vertex[1] = lat;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::china_grid;
+
+    #[test]
+    fn test_matches_f64_path() {
+        let systems = [
+            CoordSystem::Wgs84,
+            CoordSystem::Gcj02,
+            CoordSystem::Bd09,
+            CoordSystem::Epsg3857,
+        ];
+        for (lon, lat) in china_grid() {
+            for from in systems {
+                let (x, y) = crate::convert(CoordSystem::Wgs84, from, lon, lat);
+                let (x32, y32) = (x as f32, y as f32);
+                for to in systems {
+                    // 恰好是相同 f32 输入经 f64 路径的舍入结果
+                    // Exactly the rounded f64 result for the same f32 input
+                    let (ex, ey) = crate::convert(from, to, f64::from(x32), f64::from(y32));
+                    assert_eq!(convert(from, to, x32, y32), (ex as f32, ey as f32));
+
+                    // 相对原始 f64 输入的误差上限 Bound against the original f64 input
+                    let (ex, ey) = crate::convert(from, to, x, y);
+                    let (rx, ry) = convert(from, to, x32, y32);
+                    let (dx, dy) = ((f64::from(rx) - ex).abs(), (f64::from(ry) - ey).abs());
+                    if to.is_projected() {
+                        assert!(dx < 3.0 && dy < 3.0, "{:?} -> {:?}", from, to);
+                    } else {
+                        assert!(dx < 2e-5 && dy < 5e-6, "{:?} -> {:?}", from, to);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pair_functions() {
+        let (lon, lat) = (116.404_f32, 39.915_f32);
+        let expected = crate::bd09_to_wgs84(f64::from(lon), f64::from(lat));
+        assert_eq!(
+            bd09_to_wgs84(lon, lat),
+            (expected.0 as f32, expected.1 as f32)
+        );
+        let expected = crate::gcj02_to_wgs84_precise(f64::from(lon), f64::from(lat));
+        assert_eq!(
+            gcj02_to_wgs84_precise(lon, lat),
+            (expected.0 as f32, expected.1 as f32)
+        );
+
+        let mut points = [(lon, lat)];
+        convert_in_place(CoordSystem::Wgs84, CoordSystem::Epsg3857, &mut points);
+        assert_eq!(points[0], wgs84_to_epsg3857(lon, lat));
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of the stride")]
+    fn test_convert_vertices_panics() {
+        let mut vertices = [116.404, 39.915, 0.0, 121.4];
+        convert_vertices(CoordSystem::Wgs84, CoordSystem::Gcj02, &mut vertices, 3);
+    }
+}
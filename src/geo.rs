@@ -0,0 +1,300 @@
+//! geo-types 集成 (需要 `geo-types` 特性)
+//! geo-types integration (requires the `geo-types` feature)
+
+use geo_types::{
+    Coord, Geometry, GeometryCollection, Line, LineString, MultiLineString, MultiPoint,
+    MultiPolygon, Point, Polygon, Rect, Triangle,
+};
+
+use crate::CoordSystem;
+
+/// 生成逐坐标转换的方法
+/// Generate the per-coordinate conversion methods
+macro_rules! transform_methods {
+    ($($name:ident => $title:literal),* $(,)?) => {
+        $(
+            #[doc = concat!($title, "，返回转换后的几何对象")]
+            #[doc = ""]
+            #[doc = concat!("Returns a copy with every coordinate mapped through [`", stringify!($name), "`](crate::", stringify!($name), ").")]
+            fn $name(&self) -> Self
+            where
+                Self: Clone,
+            {
+                self.transform_with(crate::$name)
+            }
+        )*
+    };
+}
+
+/// 对 geo-types 几何对象的每个坐标进行坐标系转换
+/// Map every coordinate of a geo-types geometry through a coordinate conversion
+///
+/// 转换保留几何结构，包括多边形的内环 (洞)。[`Rect`] 转换其两个角点后重新构造，
+/// 因此结果是转换后角点的外包矩形。
+///
+/// The structure is preserved, including polygon interiors (holes). A [`Rect`] is rebuilt from its
+/// two converted corners, so the result is the rectangle spanned by the converted corners.
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::ChinaTransform;
+/// use geo_types::{polygon, Polygon};
+///
+/// let poly: Polygon = polygon!(
+///     exterior: [(x: 116.0, y: 39.0), (x: 117.0, y: 39.0), (x: 117.0, y: 40.0), (x: 116.0, y: 39.0)],
+///     interiors: [[(x: 116.4, y: 39.4), (x: 116.6, y: 39.4), (x: 116.6, y: 39.6), (x: 116.4, y: 39.4)]],
+/// );
+/// let gcj = poly.wgs84_to_gcj02();
+///
+/// assert_eq!(gcj.interiors().len(), 1);
+/// let (lon, lat) = coordtransform::wgs84_to_gcj02(116.0, 39.0);
+/// assert_eq!(gcj.exterior().0[0], (lon, lat).into());
+/// ```
+pub trait ChinaTransform {
+    /// 使用给定函数原地转换每个坐标
+    /// Convert every coordinate in place with the given function
+    ///
+    /// # Parameters 参数
+    ///
+    /// * `f` - 接收经度、纬度，返回转换后坐标的函数
+    ///   Function taking longitude and latitude and returning the converted coordinates
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64);
+
+    /// 使用给定函数转换每个坐标，返回新的几何对象
+    /// Convert every coordinate with the given function, returning a new geometry
+    fn transform_with<F>(&self, f: F) -> Self
+    where
+        Self: Clone,
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        let mut geometry = self.clone();
+        geometry.transform_in_place(&f);
+        geometry
+    }
+
+    /// 任意两个坐标系之间的转换，返回新的几何对象
+    /// Conversion between any two coordinate systems, returning a new geometry
+    ///
+    /// # Example 示例
+    ///
+    /// ```rust
+    /// use coordtransform::{ChinaTransform, CoordSystem};
+    /// use geo_types::line_string;
+    ///
+    /// let line = line_string![(x: 116.404, y: 39.915), (x: 121.4737, y: 31.2304)];
+    /// let bd09 = line.convert(CoordSystem::Wgs84, CoordSystem::Bd09);
+    /// assert_eq!(bd09.0.len(), 2);
+    /// ```
+    fn convert(&self, from: CoordSystem, to: CoordSystem) -> Self
+    where
+        Self: Clone,
+    {
+        self.transform_with(|lon, lat| crate::convert(from, to, lon, lat))
+    }
+
+    transform_methods!(
+        bd09_to_gcj02 => "百度坐标系 -> 火星坐标系",
+        gcj02_to_bd09 => "火星坐标系 -> 百度坐标系",
+        wgs84_to_gcj02 => "WGS84坐标系 -> 火星坐标系",
+        gcj02_to_wgs84 => "火星坐标系 -> WGS84坐标系",
+        bd09_to_wgs84 => "百度坐标系 -> WGS84坐标系",
+        wgs84_to_bd09 => "WGS84坐标系 -> 百度坐标系",
+        wgs84_to_epsg3857 => "WGS84坐标系 -> EPSG:3857坐标系",
+        epsg3857_to_wgs84 => "EPSG:3857坐标系 -> WGS84坐标系",
+        gcj02_to_epsg3857 => "GCJ02坐标系 -> EPSG:3857坐标系",
+        epsg3857_to_gcj02 => "EPSG:3857坐标系 -> GCJ02坐标系",
+        bd09_to_epsg3857 => "BD09坐标系 -> EPSG:3857坐标系",
+        epsg3857_to_bd09 => "EPSG:3857坐标系 -> BD09坐标系",
+    );
+}
+
+impl ChinaTransform for Coord {
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        let (x, y) = f(self.x, self.y);
+        self.x = x;
+        self.y = y;
+    }
+}
+
+impl ChinaTransform for Point {
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        self.0.transform_in_place(f);
+    }
+}
+
+impl ChinaTransform for Line {
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        self.start.transform_in_place(f);
+        self.end.transform_in_place(f);
+    }
+}
+
+impl ChinaTransform for LineString {
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        for coord in self.coords_mut() {
+            coord.transform_in_place(f);
+        }
+    }
+}
+
+impl ChinaTransform for Polygon {
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        self.exterior_mut(|exterior| exterior.transform_in_place(f));
+        self.interiors_mut(|interiors| {
+            for interior in interiors {
+                interior.transform_in_place(f);
+            }
+        });
+    }
+}
+
+impl ChinaTransform for Rect {
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        let (mut min, mut max) = (self.min(), self.max());
+        min.transform_in_place(f);
+        max.transform_in_place(f);
+        *self = Rect::new(min, max);
+    }
+}
+
+impl ChinaTransform for Triangle {
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        let (mut v1, mut v2, mut v3) = (self.v1(), self.v2(), self.v3());
+        v1.transform_in_place(f);
+        v2.transform_in_place(f);
+        v3.transform_in_place(f);
+        *self = Triangle::new(v1, v2, v3);
+    }
+}
+
+/// 为元素类型已实现 `ChinaTransform` 的集合类型实现该trait
+/// Implement `ChinaTransform` for collections whose members already implement it
+macro_rules! impl_collection {
+    ($($collection:ty),*) => {
+        $(
+            impl ChinaTransform for $collection {
+                fn transform_in_place<F>(&mut self, f: &F)
+                where
+                    F: Fn(f64, f64) -> (f64, f64),
+                {
+                    for member in self.0.iter_mut() {
+                        member.transform_in_place(f);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_collection!(
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    GeometryCollection
+);
+
+impl ChinaTransform for Geometry {
+    fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        match self {
+            Geometry::Point(g) => g.transform_in_place(f),
+            Geometry::Line(g) => g.transform_in_place(f),
+            Geometry::LineString(g) => g.transform_in_place(f),
+            Geometry::Polygon(g) => g.transform_in_place(f),
+            Geometry::MultiPoint(g) => g.transform_in_place(f),
+            Geometry::MultiLineString(g) => g.transform_in_place(f),
+            Geometry::MultiPolygon(g) => g.transform_in_place(f),
+            Geometry::GeometryCollection(g) => g.transform_in_place(f),
+            Geometry::Rect(g) => g.transform_in_place(f),
+            Geometry::Triangle(g) => g.transform_in_place(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo_types::{coord, line_string, point, polygon};
+
+    fn assert_coord(actual: Coord, lon: f64, lat: f64, f: fn(f64, f64) -> (f64, f64)) {
+        let (x, y) = f(lon, lat);
+        assert_eq!(actual, coord! { x: x, y: y });
+    }
+
+    #[test]
+    fn test_polygon_keeps_holes() {
+        let poly = polygon!(
+            exterior: [(x: 116.0, y: 39.0), (x: 117.0, y: 39.0), (x: 117.0, y: 40.0)],
+            interiors: [
+                [(x: 116.4, y: 39.4), (x: 116.6, y: 39.4), (x: 116.6, y: 39.6)],
+                [(x: 116.7, y: 39.7), (x: 116.8, y: 39.7), (x: 116.8, y: 39.8)],
+            ],
+        );
+        let bd09 = poly.wgs84_to_bd09();
+
+        assert_eq!(bd09.exterior().0.len(), poly.exterior().0.len());
+        assert_eq!(bd09.interiors().len(), 2);
+        assert!(bd09.exterior().is_closed());
+        assert_coord(bd09.interiors()[1].0[0], 116.7, 39.7, crate::wgs84_to_bd09);
+    }
+
+    #[test]
+    fn test_geometry_collection() {
+        let collection = Geometry::GeometryCollection(GeometryCollection(vec![
+            point!(x: 116.404, y: 39.915).into(),
+            line_string![(x: 121.4737, y: 31.2304), (x: 113.2644, y: 23.1291)].into(),
+            Rect::new(coord! { x: 116.0, y: 39.0 }, coord! { x: 117.0, y: 40.0 }).into(),
+        ]));
+        let Geometry::GeometryCollection(converted) = collection.gcj02_to_wgs84() else {
+            panic!("geometry type changed");
+        };
+
+        let Geometry::Point(p) = converted.0[0] else {
+            panic!("geometry type changed");
+        };
+        assert_coord(p.0, 116.404, 39.915, crate::gcj02_to_wgs84);
+        let Geometry::LineString(line) = &converted.0[1] else {
+            panic!("geometry type changed");
+        };
+        assert_coord(line.0[1], 113.2644, 23.1291, crate::gcj02_to_wgs84);
+        let Geometry::Rect(rect) = converted.0[2] else {
+            panic!("geometry type changed");
+        };
+        assert_coord(rect.max(), 117.0, 40.0, crate::gcj02_to_wgs84);
+    }
+
+    #[test]
+    fn test_convert_matches_pair_method() {
+        let line = line_string![(x: 116.404, y: 39.915), (x: 0.0, y: 0.0)];
+        assert_eq!(
+            line.convert(CoordSystem::Bd09, CoordSystem::Epsg3857),
+            line.bd09_to_epsg3857()
+        );
+    }
+}
@@ -0,0 +1,430 @@
+//! GeoJSON文档坐标转换 (需要 `geojson` 特性)
+//! GeoJSON document conversion (requires the `geojson` feature)
+//!
+//! 支持 `FeatureCollection`、`Feature` 及全部几何类型 (RFC 7946)。文档以 [`serde_json::Value`]
+//! 原地修改，因此属性、`id`、外部成员 (foreign members) 及成员顺序均保持不变；
+//! 位置中的高度等额外分量原样保留，已有的 `bbox` 成员按转换后的坐标重新计算；没有任何位置的对象
+//! (如 `geometry` 为 `null` 的Feature) 无法重新计算，其 `bbox` 会被删除。
+//!
+//! Supports `FeatureCollection`, `Feature` and every geometry type (RFC 7946). The document is
+//! modified in place as a [`serde_json::Value`], so properties, `id`s, foreign members and member
+//! order are left untouched; altitudes and other extra position elements are kept as they are and
+//! existing `bbox` members are recomputed from the converted positions. Objects without any
+//! position (such as a Feature whose `geometry` is `null`) have nothing to recompute from, so their
+//! `bbox` is removed.
+//!
+//! ```rust
+//! use coordtransform::{geojson, CoordSystem};
+//!
+//! let input = r#"{"type":"Feature","id":7,"properties":{"name":"天安门"},
+//!     "geometry":{"type":"Point","coordinates":[116.404,39.915,44.5]}}"#;
+//! let output = geojson::convert_str(input, CoordSystem::Gcj02, CoordSystem::Wgs84).unwrap();
+//!
+//! let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+//! let (lon, lat) = coordtransform::gcj02_to_wgs84(116.404, 39.915);
+//! assert_eq!(value["geometry"]["coordinates"], serde_json::json!([lon, lat, 44.5]));
+//! assert_eq!(value["properties"]["name"], "天安门");
+//! ```
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::CoordSystem;
+
+/// GeoJSON转换错误
+/// GeoJSON conversion error
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum GeoJsonError {
+    /// JSON解析或序列化失败 JSON parsing or serialization failed
+    Json(serde_json::Error),
+    /// 期望GeoJSON对象 A GeoJSON object was expected
+    NotAnObject,
+    /// 缺少必需成员 A required member is missing
+    MissingMember(&'static str),
+    /// 无法识别的 `type` Unrecognised `type`
+    UnknownType(String),
+    /// 位置不是至少包含两个数字的数组，或转换结果不是有限数
+    /// A position is not an array of at least two numbers, or converts to a non-finite number
+    InvalidPosition,
+}
+
+impl fmt::Display for GeoJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeoJsonError::Json(err) => write!(f, "invalid JSON: {}", err),
+            GeoJsonError::NotAnObject => f.write_str("expected a GeoJSON object"),
+            GeoJsonError::MissingMember(name) => write!(f, "missing `{}` member", name),
+            GeoJsonError::UnknownType(name) => write!(f, "unknown GeoJSON type `{}`", name),
+            GeoJsonError::InvalidPosition => f.write_str("invalid position"),
+        }
+    }
+}
+
+impl std::error::Error for GeoJsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GeoJsonError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for GeoJsonError {
+    fn from(err: serde_json::Error) -> Self {
+        GeoJsonError::Json(err)
+    }
+}
+
+/// 转换GeoJSON文本中的所有位置
+/// Convert every position of a GeoJSON text
+///
+/// # Parameters 参数
+///
+/// * `input` - GeoJSON文本 GeoJSON text
+/// * `from` - 源坐标系 Source coordinate system
+/// * `to` - 目标坐标系 Target coordinate system
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的GeoJSON文本 Returns the converted GeoJSON text
+pub fn convert_str(
+    input: &str,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<String, GeoJsonError> {
+    let mut value: Value = serde_json::from_str(input)?;
+    convert_value(&mut value, from, to)?;
+    Ok(serde_json::to_string(&value)?)
+}
+
+/// 原地转换GeoJSON值中的所有位置
+/// Convert every position of a GeoJSON value in place
+///
+/// 出错时文档可能已被部分转换。
+/// The document may be partially converted when an error is returned.
+///
+/// # Parameters 参数
+///
+/// * `value` - `FeatureCollection`、`Feature` 或几何对象 A `FeatureCollection`, `Feature` or geometry
+/// * `from` - 源坐标系 Source coordinate system
+/// * `to` - 目标坐标系 Target coordinate system
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{geojson, CoordSystem};
+/// use serde_json::json;
+///
+/// let mut line = json!({
+///     "type": "LineString",
+///     "bbox": [0.0, 0.0, 0.0, 0.0],
+///     "coordinates": [[116.404, 39.915], [121.4737, 31.2304]],
+/// });
+/// geojson::convert_value(&mut line, CoordSystem::Bd09, CoordSystem::Gcj02).unwrap();
+///
+/// let (min_lon, _) = coordtransform::bd09_to_gcj02(116.404, 39.915);
+/// assert_eq!(line["bbox"][0], json!(min_lon));
+/// ```
+pub fn convert_value(
+    value: &mut Value,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<(), GeoJsonError> {
+    convert_object(value, from, to).map(|_| ())
+}
+
+/// 转换后坐标的范围 Extent of the converted positions
+#[derive(Debug, Clone, Copy)]
+struct Extent {
+    min: [f64; 3],
+    max: [f64; 3],
+    /// 所有位置都带有高度 Every position has an altitude
+    has_z: bool,
+}
+
+impl Extent {
+    fn new(x: f64, y: f64, z: Option<f64>) -> Self {
+        let z_value = z.unwrap_or(0.0);
+        Self {
+            min: [x, y, z_value],
+            max: [x, y, z_value],
+            has_z: z.is_some(),
+        }
+    }
+
+    fn union(self, other: Self) -> Self {
+        let mut extent = self;
+        for i in 0..3 {
+            extent.min[i] = extent.min[i].min(other.min[i]);
+            extent.max[i] = extent.max[i].max(other.max[i]);
+        }
+        extent.has_z &= other.has_z;
+        extent
+    }
+
+    /// 生成bbox，仅当原bbox为三维且所有位置都有高度时输出三维bbox
+    /// Build a bbox; a 3D bbox is produced only when the original one was 3D and every position has
+    /// an altitude
+    fn to_bbox(self, three_dimensional: bool) -> Result<Value, GeoJsonError> {
+        let values: &[f64] = if three_dimensional && self.has_z {
+            &[
+                self.min[0],
+                self.min[1],
+                self.min[2],
+                self.max[0],
+                self.max[1],
+                self.max[2],
+            ]
+        } else {
+            &[self.min[0], self.min[1], self.max[0], self.max[1]]
+        };
+        values
+            .iter()
+            .map(|&v| number(v))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array)
+    }
+}
+
+fn merge(a: Option<Extent>, b: Option<Extent>) -> Option<Extent> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.union(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn convert_object(
+    value: &mut Value,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<Option<Extent>, GeoJsonError> {
+    let object = value.as_object_mut().ok_or(GeoJsonError::NotAnObject)?;
+    let kind = object
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or(GeoJsonError::MissingMember("type"))?
+        .to_owned();
+
+    let extent = match kind.as_str() {
+        "FeatureCollection" => convert_members(object, "features", from, to)?,
+        "GeometryCollection" => convert_members(object, "geometries", from, to)?,
+        "Feature" => match object.get_mut("geometry") {
+            Some(Value::Null) => None,
+            Some(geometry) => convert_object(geometry, from, to)?,
+            None => return Err(GeoJsonError::MissingMember("geometry")),
+        },
+        "Point" => convert_coordinates(object, 0, from, to)?,
+        "MultiPoint" | "LineString" => convert_coordinates(object, 1, from, to)?,
+        "MultiLineString" | "Polygon" => convert_coordinates(object, 2, from, to)?,
+        "MultiPolygon" => convert_coordinates(object, 3, from, to)?,
+        _ => return Err(GeoJsonError::UnknownType(kind)),
+    };
+
+    match (extent, object.get_mut("bbox")) {
+        (Some(extent), Some(bbox)) => {
+            let three_dimensional = bbox.as_array().is_some_and(|b| b.len() == 6);
+            *bbox = extent.to_bbox(three_dimensional)?;
+        }
+        // 没有位置时原bbox仍是源坐标系，删除以免混用
+        // Without positions the old bbox is still in the source system, so drop it
+        (None, Some(_)) => {
+            object.shift_remove("bbox");
+        }
+        _ => {}
+    }
+    Ok(extent)
+}
+
+fn convert_members(
+    object: &mut Map<String, Value>,
+    name: &'static str,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<Option<Extent>, GeoJsonError> {
+    let members = object
+        .get_mut(name)
+        .and_then(Value::as_array_mut)
+        .ok_or(GeoJsonError::MissingMember(name))?;
+
+    let mut extent = None;
+    for member in members {
+        extent = merge(extent, convert_object(member, from, to)?);
+    }
+    Ok(extent)
+}
+
+fn convert_coordinates(
+    object: &mut Map<String, Value>,
+    depth: usize,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<Option<Extent>, GeoJsonError> {
+    let coordinates = object
+        .get_mut("coordinates")
+        .ok_or(GeoJsonError::MissingMember("coordinates"))?;
+    convert_nested(coordinates, depth, from, to)
+}
+
+/// 转换嵌套 `depth` 层的位置数组 Convert positions nested `depth` arrays deep
+fn convert_nested(
+    value: &mut Value,
+    depth: usize,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<Option<Extent>, GeoJsonError> {
+    if depth == 0 {
+        return convert_position(value, from, to).map(Some);
+    }
+
+    let items = value.as_array_mut().ok_or(GeoJsonError::InvalidPosition)?;
+    let mut extent = None;
+    for item in items {
+        extent = merge(extent, convert_nested(item, depth - 1, from, to)?);
+    }
+    Ok(extent)
+}
+
+fn convert_position(
+    value: &mut Value,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<Extent, GeoJsonError> {
+    let position = value
+        .as_array_mut()
+        .filter(|position| position.len() >= 2)
+        .ok_or(GeoJsonError::InvalidPosition)?;
+    let lon = position[0].as_f64().ok_or(GeoJsonError::InvalidPosition)?;
+    let lat = position[1].as_f64().ok_or(GeoJsonError::InvalidPosition)?;
+
+    let (x, y) = crate::convert(from, to, lon, lat);
+    position[0] = number(x)?;
+    position[1] = number(y)?;
+
+    Ok(Extent::new(x, y, position.get(2).and_then(Value::as_f64)))
+}
+
+fn number(value: f64) -> Result<Value, GeoJsonError> {
+    serde_json::Number::from_f64(value)
+        .map(Value::Number)
+        .ok_or(GeoJsonError::InvalidPosition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_feature_collection_preserves_members() {
+        let mut doc = json!({
+            "type": "FeatureCollection",
+            "name": "pois",
+            "bbox": [0, 0, 0, 0],
+            "features": [
+                {
+                    "type": "Feature",
+                    "id": "a",
+                    "properties": {"rank": 1},
+                    "source": "amap",
+                    "geometry": {"type": "Point", "coordinates": [116.404, 39.915, 50.0]}
+                },
+                {
+                    "type": "Feature",
+                    "properties": null,
+                    "geometry": null
+                },
+                {
+                    "type": "Feature",
+                    "properties": {},
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [
+                            [[121.0, 31.0], [122.0, 31.0], [122.0, 32.0], [121.0, 31.0]],
+                            [[121.4, 31.4], [121.6, 31.4], [121.6, 31.6], [121.4, 31.4]]
+                        ]
+                    }
+                }
+            ]
+        });
+        convert_value(&mut doc, CoordSystem::Gcj02, CoordSystem::Wgs84).unwrap();
+
+        let feature = &doc["features"][0];
+        assert_eq!(feature["id"], "a");
+        assert_eq!(feature["source"], "amap");
+        assert_eq!(feature["properties"]["rank"], 1);
+        let (lon, lat) = crate::gcj02_to_wgs84(116.404, 39.915);
+        assert_eq!(feature["geometry"]["coordinates"], json!([lon, lat, 50.0]));
+
+        assert_eq!(doc["features"][1]["geometry"], Value::Null);
+        let (hole_lon, hole_lat) = crate::gcj02_to_wgs84(121.6, 31.6);
+        assert_eq!(
+            doc["features"][2]["geometry"]["coordinates"][1][2],
+            json!([hole_lon, hole_lat])
+        );
+
+        let (min_lon, _) = crate::gcj02_to_wgs84(116.404, 39.915);
+        let (max_lon, _) = crate::gcj02_to_wgs84(122.0, 32.0);
+        assert_eq!(doc["bbox"][0], json!(min_lon));
+        assert_eq!(doc["bbox"][2], json!(max_lon));
+        assert_eq!(doc["bbox"].as_array().unwrap().len(), 4);
+
+        let keys: Vec<_> = doc.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["type", "name", "bbox", "features"]);
+    }
+
+    #[test]
+    fn test_three_dimensional_bbox() {
+        let mut line = json!({
+            "type": "LineString",
+            "bbox": [0, 0, 0, 0, 0, 0],
+            "coordinates": [[116.0, 39.0, 10.0], [117.0, 40.0, 30.0]]
+        });
+        convert_value(&mut line, CoordSystem::Wgs84, CoordSystem::Bd09).unwrap();
+
+        let bbox = line["bbox"].as_array().unwrap();
+        assert_eq!(bbox.len(), 6);
+        assert_eq!(bbox[2], json!(10.0));
+        assert_eq!(bbox[5], json!(30.0));
+    }
+
+    #[test]
+    fn test_bbox_without_positions() {
+        let mut doc = json!({
+            "type": "FeatureCollection",
+            "bbox": [116.0, 39.0, 117.0, 40.0],
+            "features": [
+                {"type": "Feature", "bbox": [116.0, 39.0, 117.0, 40.0], "geometry": null, "properties": {}},
+                {"type": "Feature", "geometry": {"type": "MultiPoint", "bbox": [116.0, 39.0, 117.0, 40.0], "coordinates": []}, "properties": {}}
+            ]
+        });
+        convert_value(&mut doc, CoordSystem::Wgs84, CoordSystem::Gcj02).unwrap();
+
+        assert!(doc.get("bbox").is_none());
+        assert!(doc["features"][0].get("bbox").is_none());
+        assert!(doc["features"][1]["geometry"].get("bbox").is_none());
+        let keys: Vec<_> = doc["features"][0].as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["type", "geometry", "properties"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let convert = |doc: &str| convert_str(doc, CoordSystem::Wgs84, CoordSystem::Gcj02);
+
+        assert!(matches!(convert("{"), Err(GeoJsonError::Json(_))));
+        assert!(matches!(convert("[]"), Err(GeoJsonError::NotAnObject)));
+        assert!(matches!(
+            convert(r#"{"type":"Circle"}"#),
+            Err(GeoJsonError::UnknownType(kind)) if kind == "Circle"
+        ));
+        assert!(matches!(
+            convert(r#"{"type":"Point"}"#),
+            Err(GeoJsonError::MissingMember("coordinates"))
+        ));
+        assert!(matches!(
+            convert(r#"{"type":"LineString","coordinates":[[1.0]]}"#),
+            Err(GeoJsonError::InvalidPosition)
+        ));
+    }
+}
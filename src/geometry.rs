@@ -0,0 +1,382 @@
+//! WKT与WKB共用的几何模型
+//! Geometry model shared by WKT and WKB
+//!
+//! 覆盖 OGC Simple Features 中从 `Point` 到 `GeometryCollection` 的七种几何类型，
+//! 支持Z (高度) 与M (度量) 分量以及PostGIS扩展格式的SRID。
+//! 坐标转换只改变X、Y，Z与M原样保留。
+//!
+//! Covers the seven OGC Simple Features types from `Point` to `GeometryCollection`, with Z
+//! (altitude) and M (measure) ordinates and the SRID of the PostGIS extended formats.
+//! Conversion only changes X and Y; Z and M are kept as they are.
+
+use std::fmt;
+
+use crate::CoordSystem;
+
+/// 坐标维度 Coordinate dimension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Dimension {
+    /// 二维 (X, Y) Two-dimensional (X, Y)
+    #[default]
+    Xy,
+    /// 带高度 (X, Y, Z) With altitude (X, Y, Z)
+    Xyz,
+    /// 带度量 (X, Y, M) With measure (X, Y, M)
+    Xym,
+    /// 带高度和度量 (X, Y, Z, M) With altitude and measure (X, Y, Z, M)
+    Xyzm,
+}
+
+impl Dimension {
+    /// 每个坐标的分量个数 Number of ordinates per coordinate
+    pub fn size(self) -> usize {
+        match self {
+            Dimension::Xy => 2,
+            Dimension::Xyz | Dimension::Xym => 3,
+            Dimension::Xyzm => 4,
+        }
+    }
+
+    /// 是否包含Z分量 Whether the Z ordinate is present
+    pub fn has_z(self) -> bool {
+        matches!(self, Dimension::Xyz | Dimension::Xyzm)
+    }
+
+    /// 是否包含M分量 Whether the M ordinate is present
+    pub fn has_m(self) -> bool {
+        matches!(self, Dimension::Xym | Dimension::Xyzm)
+    }
+
+    pub(crate) fn from_flags(has_z: bool, has_m: bool) -> Self {
+        match (has_z, has_m) {
+            (false, false) => Dimension::Xy,
+            (true, false) => Dimension::Xyz,
+            (false, true) => Dimension::Xym,
+            (true, true) => Dimension::Xyzm,
+        }
+    }
+}
+
+/// 坐标 Coordinate
+///
+/// `z` 与 `m` 是否有值由所属几何对象的 [`Dimension`] 决定。
+/// Whether `z` and `m` hold values is determined by the [`Dimension`] of the owning geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Coord {
+    /// X坐标或经度 X coordinate or longitude
+    pub x: f64,
+    /// Y坐标或纬度 Y coordinate or latitude
+    pub y: f64,
+    /// 高度 Altitude
+    pub z: Option<f64>,
+    /// 度量 Measure
+    pub m: Option<f64>,
+}
+
+impl Coord {
+    /// 创建二维坐标 Create a two-dimensional coordinate
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            z: None,
+            m: None,
+        }
+    }
+
+    /// 按维度由分量创建坐标 Create a coordinate from its ordinates in the given dimension
+    pub(crate) fn from_ordinates(dimension: Dimension, ordinates: &[f64]) -> Self {
+        let mut coord = Self::new(ordinates[0], ordinates[1]);
+        match dimension {
+            Dimension::Xy => {}
+            Dimension::Xyz => coord.z = Some(ordinates[2]),
+            Dimension::Xym => coord.m = Some(ordinates[2]),
+            Dimension::Xyzm => {
+                coord.z = Some(ordinates[2]);
+                coord.m = Some(ordinates[3]);
+            }
+        }
+        coord
+    }
+
+    /// 按维度依次返回各分量，缺失的分量为NaN
+    /// Returns the ordinates in the given dimension; missing ordinates are NaN
+    pub(crate) fn ordinates(&self, dimension: Dimension) -> impl Iterator<Item = f64> {
+        let z = dimension.has_z().then(|| self.z.unwrap_or(f64::NAN));
+        let m = dimension.has_m().then(|| self.m.unwrap_or(f64::NAN));
+        [Some(self.x), Some(self.y), z, m].into_iter().flatten()
+    }
+}
+
+/// 几何类型及其坐标 Geometry type with its coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeometryKind {
+    /// 点，`None` 表示空点 Point; `None` is an empty point
+    Point(Option<Coord>),
+    /// 线 Line string
+    LineString(Vec<Coord>),
+    /// 多边形，第一个环为外环，其余为内环 (洞)
+    /// Polygon; the first ring is the exterior, the others are interiors (holes)
+    Polygon(Vec<Vec<Coord>>),
+    /// 多点 Multi-point
+    MultiPoint(Vec<Coord>),
+    /// 多线 Multi-line string
+    MultiLineString(Vec<Vec<Coord>>),
+    /// 多多边形 Multi-polygon
+    MultiPolygon(Vec<Vec<Vec<Coord>>>),
+    /// 几何集合 Geometry collection
+    GeometryCollection(Vec<Geometry>),
+}
+
+impl GeometryKind {
+    /// WKT关键字 WKT keyword
+    pub(crate) fn keyword(&self) -> &'static str {
+        match self {
+            GeometryKind::Point(_) => "POINT",
+            GeometryKind::LineString(_) => "LINESTRING",
+            GeometryKind::Polygon(_) => "POLYGON",
+            GeometryKind::MultiPoint(_) => "MULTIPOINT",
+            GeometryKind::MultiLineString(_) => "MULTILINESTRING",
+            GeometryKind::MultiPolygon(_) => "MULTIPOLYGON",
+            GeometryKind::GeometryCollection(_) => "GEOMETRYCOLLECTION",
+        }
+    }
+
+    /// WKB类型代码 (不含维度) WKB type code without the dimension
+    pub(crate) fn code(&self) -> u32 {
+        match self {
+            GeometryKind::Point(_) => 1,
+            GeometryKind::LineString(_) => 2,
+            GeometryKind::Polygon(_) => 3,
+            GeometryKind::MultiPoint(_) => 4,
+            GeometryKind::MultiLineString(_) => 5,
+            GeometryKind::MultiPolygon(_) => 6,
+            GeometryKind::GeometryCollection(_) => 7,
+        }
+    }
+
+    /// 是否为空几何 Whether the geometry is empty
+    pub fn is_empty(&self) -> bool {
+        match self {
+            GeometryKind::Point(point) => point.is_none(),
+            GeometryKind::LineString(coords) | GeometryKind::MultiPoint(coords) => {
+                coords.is_empty()
+            }
+            GeometryKind::Polygon(lines) | GeometryKind::MultiLineString(lines) => lines.is_empty(),
+            GeometryKind::MultiPolygon(polygons) => polygons.is_empty(),
+            GeometryKind::GeometryCollection(geometries) => geometries.is_empty(),
+        }
+    }
+}
+
+/// 几何对象 Geometry
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::geometry::Geometry;
+/// use coordtransform::CoordSystem;
+///
+/// let mut geometry: Geometry = "SRID=4326;POINT Z (116.404 39.915 44.5)".parse().unwrap();
+/// geometry.convert_in_place(CoordSystem::Wgs84, CoordSystem::Epsg3857);
+///
+/// assert_eq!(geometry.srid, Some(3857));
+/// assert!(geometry.to_string().starts_with("SRID=3857;POINT Z ("));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geometry {
+    /// 空间参考标识，仅PostGIS扩展格式 (EWKT/EWKB) 携带
+    /// Spatial reference identifier, only carried by the PostGIS extended formats (EWKT/EWKB)
+    pub srid: Option<i32>,
+    /// 坐标维度 Coordinate dimension
+    pub dimension: Dimension,
+    /// 几何类型及其坐标 Geometry type with its coordinates
+    pub kind: GeometryKind,
+}
+
+impl Geometry {
+    /// 创建不带SRID的几何对象 Create a geometry without an SRID
+    pub fn new(dimension: Dimension, kind: GeometryKind) -> Self {
+        Self {
+            srid: None,
+            dimension,
+            kind,
+        }
+    }
+
+    /// 使用给定函数原地转换每个坐标的X、Y
+    /// Convert the X and Y of every coordinate in place with the given function
+    ///
+    /// # Parameters 参数
+    ///
+    /// * `f` - 接收X、Y，返回转换后坐标的函数 Function taking X and Y and returning the converted coordinates
+    pub fn transform_in_place<F>(&mut self, f: &F)
+    where
+        F: Fn(f64, f64) -> (f64, f64),
+    {
+        fn coords<F: Fn(f64, f64) -> (f64, f64)>(coords: &mut [Coord], f: &F) {
+            for coord in coords {
+                (coord.x, coord.y) = f(coord.x, coord.y);
+            }
+        }
+
+        match &mut self.kind {
+            GeometryKind::Point(point) => coords(point.as_mut_slice(), f),
+            GeometryKind::LineString(line) | GeometryKind::MultiPoint(line) => coords(line, f),
+            GeometryKind::Polygon(lines) | GeometryKind::MultiLineString(lines) => {
+                lines.iter_mut().for_each(|line| coords(line, f))
+            }
+            GeometryKind::MultiPolygon(polygons) => polygons
+                .iter_mut()
+                .flatten()
+                .for_each(|ring| coords(ring, f)),
+            GeometryKind::GeometryCollection(geometries) => geometries
+                .iter_mut()
+                .for_each(|geometry| geometry.transform_in_place(f)),
+        }
+    }
+
+    /// 在两个坐标系之间原地转换，并改写SRID
+    /// Convert between two coordinate systems in place and rewrite the SRID
+    ///
+    /// 已有SRID时改为目标坐标系的SRID ([`CoordSystem::srid`])；目标为GCJ02或BD09等没有官方SRID的经纬度坐标系时
+    /// 改为4326，因为这类数据在PostGIS中通常以4326存储。没有SRID的几何对象保持没有SRID。
+    ///
+    /// An existing SRID is replaced by the SRID of the target system ([`CoordSystem::srid`]); when the
+    /// target is a geographic system without an official SRID, such as GCJ02 or BD09, it becomes 4326
+    /// because such data is usually stored as 4326 in PostGIS. Geometries without an SRID stay without
+    /// one.
+    ///
+    /// # Parameters 参数
+    ///
+    /// * `from` - 源坐标系 Source coordinate system
+    /// * `to` - 目标坐标系 Target coordinate system
+    pub fn convert_in_place(&mut self, from: CoordSystem, to: CoordSystem) {
+        self.transform_in_place(&|x, y| crate::convert(from, to, x, y));
+        if self.srid.is_some() {
+            self.srid = to.srid().or(CoordSystem::Wgs84.srid());
+        }
+    }
+}
+
+/// WKT或WKB中几何集合的最大嵌套层数，防止恶意输入导致栈溢出
+/// Maximum nesting depth of geometry collections in WKT or WKB, guarding against stack overflows
+/// on crafted input
+pub const MAX_NESTING_DEPTH: usize = 64;
+
+/// WKT或WKB解析错误
+/// WKT or WKB parse error
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum GeometryError {
+    /// 输入提前结束 The input ended early
+    UnexpectedEnd,
+    /// 在偏移处遇到意外内容 Unexpected content at the given offset
+    Unexpected {
+        /// 字节偏移 Byte offset
+        offset: usize,
+        /// 期望的内容 What was expected
+        expected: &'static str,
+    },
+    /// 无法识别的几何类型 Unrecognised geometry type
+    UnknownType(String),
+    /// 坐标分量个数与维度不符 The number of ordinates does not match the dimension
+    DimensionMismatch {
+        /// 字节偏移 Byte offset
+        offset: usize,
+    },
+    /// 无效的WKB字节序标记 Invalid WKB byte order marker
+    InvalidByteOrder(u8),
+    /// 无效的十六进制字符串 Invalid hexadecimal string
+    InvalidHex,
+    /// 几何对象之后还有多余内容 Extra content after the geometry
+    TrailingData {
+        /// 字节偏移 Byte offset
+        offset: usize,
+    },
+    /// 几何集合嵌套超过 [`MAX_NESTING_DEPTH`] 层
+    /// Geometry collections are nested deeper than [`MAX_NESTING_DEPTH`]
+    TooDeeplyNested {
+        /// 字节偏移 Byte offset
+        offset: usize,
+    },
+}
+
+impl fmt::Display for GeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeometryError::UnexpectedEnd => f.write_str("unexpected end of input"),
+            GeometryError::Unexpected { offset, expected } => {
+                write!(f, "expected {} at offset {}", expected, offset)
+            }
+            GeometryError::UnknownType(name) => write!(f, "unknown geometry type `{}`", name),
+            GeometryError::DimensionMismatch { offset } => {
+                write!(f, "coordinate dimension mismatch at offset {}", offset)
+            }
+            GeometryError::InvalidByteOrder(byte) => {
+                write!(f, "invalid WKB byte order marker {}", byte)
+            }
+            GeometryError::InvalidHex => f.write_str("invalid hexadecimal string"),
+            GeometryError::TrailingData { offset } => {
+                write!(f, "unexpected data after the geometry at offset {}", offset)
+            }
+            GeometryError::TooDeeplyNested { offset } => write!(
+                f,
+                "geometry nested deeper than {} levels at offset {}",
+                MAX_NESTING_DEPTH, offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GeometryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_keeps_z_and_m() {
+        let mut geometry = Geometry::new(
+            Dimension::Xyzm,
+            GeometryKind::LineString(vec![Coord {
+                x: 116.404,
+                y: 39.915,
+                z: Some(10.0),
+                m: Some(3.0),
+            }]),
+        );
+        geometry.convert_in_place(CoordSystem::Wgs84, CoordSystem::Gcj02);
+
+        let GeometryKind::LineString(line) = &geometry.kind else {
+            panic!("geometry type changed");
+        };
+        let (x, y) = crate::wgs84_to_gcj02(116.404, 39.915);
+        assert_eq!((line[0].x, line[0].y), (x, y));
+        assert_eq!((line[0].z, line[0].m), (Some(10.0), Some(3.0)));
+        assert_eq!(geometry.srid, None);
+    }
+
+    #[test]
+    fn test_srid_rewrite() {
+        let mut geometry = Geometry {
+            srid: Some(4326),
+            ..Geometry::new(Dimension::Xy, GeometryKind::Point(None))
+        };
+        geometry.convert_in_place(CoordSystem::Wgs84, CoordSystem::Gcj02);
+        assert_eq!(geometry.srid, Some(4326));
+        geometry.convert_in_place(CoordSystem::Gcj02, CoordSystem::Epsg3857);
+        assert_eq!(geometry.srid, Some(3857));
+
+        // 投影坐标转换为GCJ02或BD09经纬度后不应仍标记为3857
+        // Projected coordinates converted to GCJ02 or BD09 degrees must not stay labelled 3857
+        for to in [CoordSystem::Gcj02, CoordSystem::Bd09] {
+            let mut geometry = Geometry {
+                srid: Some(3857),
+                ..Geometry::new(Dimension::Xy, GeometryKind::Point(None))
+            };
+            geometry.convert_in_place(CoordSystem::Epsg3857, to);
+            assert_eq!(geometry.srid, Some(4326));
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! GPX轨迹坐标转换 (需要 `gpx` 特性)
+//! GPX track conversion (requires the `gpx` feature)
+//!
+//! 支持GPX 1.0与1.1。航点 (`wpt`)、路线点 (`rtept`) 与轨迹点 (`trkpt`) 的 `lat`/`lon` 属性
+//! 被转换，文档其余部分 (高程、时间、扩展、注释、命名空间等) 原样输出；已有的 `bounds`
+//! 元素按转换后的点重新计算。由于 `bounds` 位于所有点之前，文档会先完整读入内存。
+//!
+//! Supports GPX 1.0 and 1.1. The `lat`/`lon` attributes of waypoints (`wpt`), route points
+//! (`rtept`) and track points (`trkpt`) are converted while the rest of the document (elevation,
+//! timestamps, extensions, comments, namespaces, ...) is written through unchanged; existing
+//! `bounds` elements are recomputed from the converted points. Because `bounds` precedes every
+//! point, the document is read into memory before it is written.
+//!
+//! ```rust
+//! use coordtransform::{gpx, CoordSystem};
+//!
+//! let input = r#"<gpx version="1.1" creator="phone"><trk><trkseg>
+//! <trkpt lat="39.915" lon="116.404"><ele>44.5</ele><time>2024-05-01T08:00:00Z</time></trkpt>
+//! </trkseg></trk></gpx>"#;
+//! let output = gpx::convert_str(input, CoordSystem::Wgs84, CoordSystem::Gcj02).unwrap();
+//!
+//! let (lon, lat) = coordtransform::wgs84_to_gcj02(116.404, 39.915);
+//! assert!(output.contains(&format!(r#"<trkpt lat="{}" lon="{}">"#, lat, lon)));
+//! assert!(output.contains("<ele>44.5</ele><time>2024-05-01T08:00:00Z</time>"));
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+use crate::{CoordSystem, convert as convert_coord};
+
+/// 携带坐标的GPX元素 GPX elements carrying a coordinate
+const POINT_ELEMENTS: [&str; 3] = ["wpt", "rtept", "trkpt"];
+
+/// GPX转换错误
+/// GPX conversion error
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum GpxError {
+    /// XML解析失败 XML parsing failed
+    Xml(quick_xml::Error),
+    /// 读写失败 Reading or writing failed
+    Io(io::Error),
+    /// 根元素不是 `gpx` The root element is not `gpx`
+    NotGpx,
+    /// 点缺少 `lat`/`lon` 属性、属性不是数字或转换结果不是有限数
+    /// A point lacks `lat`/`lon`, they are not numbers, or they convert to non-finite numbers
+    InvalidPoint {
+        /// 该元素之后的字节偏移 Byte offset just after the element
+        offset: u64,
+    },
+}
+
+impl fmt::Display for GpxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpxError::Xml(err) => write!(f, "invalid XML: {}", err),
+            GpxError::Io(err) => write!(f, "I/O error: {}", err),
+            GpxError::NotGpx => f.write_str("the root element is not `gpx`"),
+            GpxError::InvalidPoint { offset } => {
+                write!(f, "invalid point before offset {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GpxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GpxError::Xml(err) => Some(err),
+            GpxError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<quick_xml::Error> for GpxError {
+    fn from(err: quick_xml::Error) -> Self {
+        GpxError::Xml(err)
+    }
+}
+
+impl From<quick_xml::events::attributes::AttrError> for GpxError {
+    fn from(err: quick_xml::events::attributes::AttrError) -> Self {
+        GpxError::Xml(err.into())
+    }
+}
+
+impl From<io::Error> for GpxError {
+    fn from(err: io::Error) -> Self {
+        GpxError::Io(err)
+    }
+}
+
+/// 转换GPX文本中的所有点
+/// Convert every point of a GPX text
+///
+/// # Parameters 参数
+///
+/// * `input` - GPX文本 GPX text
+/// * `from` - 源坐标系 Source system
+/// * `to` - 目标坐标系 Target system
+///
+/// # Return Value 返回值
+///
+/// 转换后的GPX文本 The converted GPX text
+pub fn convert_str(input: &str, from: CoordSystem, to: CoordSystem) -> Result<String, GpxError> {
+    let mut output = Vec::with_capacity(input.len());
+    convert_into(input, &mut output, from, to)?;
+    // 输入为UTF-8，写出的事件均来自输入或为ASCII数字
+    // The input is UTF-8 and every written event comes from it or is ASCII digits
+    Ok(String::from_utf8(output).expect("GPX output is UTF-8"))
+}
+
+/// 从读取器读取GPX并将转换结果写入写入器
+/// Read GPX from a reader and write the converted document to a writer
+///
+/// # Parameters 参数
+///
+/// * `reader` - UTF-8编码的GPX输入 UTF-8 encoded GPX input
+/// * `writer` - GPX输出 GPX output
+/// * `from` - 源坐标系 Source system
+/// * `to` - 目标坐标系 Target system
+pub fn convert<R: Read, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<(), GpxError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    convert_into(&input, &mut writer, from, to)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn convert_into<W: Write>(
+    input: &str,
+    writer: W,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<(), GpxError> {
+    let mut reader = Reader::from_str(input);
+    let mut events = Vec::new();
+    let mut bounds = Vec::new();
+    let mut extent: Option<[f64; 4]> = None;
+    let mut seen_root = false;
+
+    loop {
+        let event = reader.read_event()?;
+        if let Event::Start(start) | Event::Empty(start) = &event
+            && !seen_root
+        {
+            if start.local_name().as_ref() != "gpx" {
+                return Err(GpxError::NotGpx);
+            }
+            seen_root = true;
+        }
+        let offset = reader.buffer_position();
+        let event = match event {
+            Event::Eof => break,
+            Event::Start(start) => Event::Start(convert_element(
+                start,
+                &mut bounds,
+                events.len(),
+                &mut extent,
+                (from, to),
+                offset,
+            )?),
+            Event::Empty(start) => Event::Empty(convert_element(
+                start,
+                &mut bounds,
+                events.len(),
+                &mut extent,
+                (from, to),
+                offset,
+            )?),
+            event => event,
+        };
+        events.push(event);
+    }
+    if !seen_root {
+        return Err(GpxError::NotGpx);
+    }
+
+    if let Some([min_lon, min_lat, max_lon, max_lat]) = extent {
+        for index in bounds {
+            if let Event::Start(start) | Event::Empty(start) = &mut events[index] {
+                *start = with_coordinates(
+                    start,
+                    &[
+                        ("minlat", min_lat),
+                        ("minlon", min_lon),
+                        ("maxlat", max_lat),
+                        ("maxlon", max_lon),
+                    ],
+                )?;
+            }
+        }
+    }
+
+    let mut writer = Writer::new(writer);
+    for event in events {
+        writer.write_event(event)?;
+    }
+    Ok(())
+}
+
+/// 转换点元素的坐标，并记录 `bounds` 元素的位置
+/// Convert the coordinate of a point element and record where `bounds` elements are
+fn convert_element<'a>(
+    start: BytesStart<'a>,
+    bounds: &mut Vec<usize>,
+    index: usize,
+    extent: &mut Option<[f64; 4]>,
+    (from, to): (CoordSystem, CoordSystem),
+    offset: u64,
+) -> Result<BytesStart<'a>, GpxError> {
+    let name = start.local_name();
+    if name.as_ref() == "bounds" {
+        bounds.push(index);
+        return Ok(start);
+    }
+    if !POINT_ELEMENTS.contains(&name.as_ref()) {
+        return Ok(start);
+    }
+
+    let (mut lat, mut lon) = (None, None);
+    for attr in start.attributes() {
+        let attr = attr?;
+        let value = attr.value.trim().parse::<f64>().ok();
+        match attr.key.local_name().as_ref() {
+            "lat" => lat = value,
+            "lon" => lon = value,
+            _ => {}
+        }
+    }
+    let (lon, lat) = match (lon, lat) {
+        (Some(lon), Some(lat)) => convert_coord(from, to, lon, lat),
+        _ => return Err(GpxError::InvalidPoint { offset }),
+    };
+    if !lon.is_finite() || !lat.is_finite() {
+        return Err(GpxError::InvalidPoint { offset });
+    }
+
+    let [min_lon, min_lat, max_lon, max_lat] = extent.get_or_insert([lon, lat, lon, lat]);
+    *min_lon = min_lon.min(lon);
+    *min_lat = min_lat.min(lat);
+    *max_lon = max_lon.max(lon);
+    *max_lat = max_lat.max(lat);
+
+    with_coordinates(&start, &[("lat", lat), ("lon", lon)])
+}
+
+/// 复制元素并替换给定属性的数值，其余属性保持原样和原顺序
+/// Copy an element replacing the values of the given attributes, keeping the others as they are
+/// and in their order
+fn with_coordinates<'a>(
+    start: &BytesStart<'a>,
+    values: &[(&str, f64)],
+) -> Result<BytesStart<'a>, GpxError> {
+    let name = start.name().as_ref().to_string();
+    let mut copy = BytesStart::new(name);
+    for attr in start.attributes() {
+        let attr = attr?;
+        let key = attr.key.as_ref();
+        let value = match values.iter().find(|(name, _)| *name == key) {
+            Some((_, value)) => Cow::Owned(value.to_string()),
+            // 原值可能来自单引号属性 The raw value may come from a single-quoted attribute
+            None if attr.value.contains('"') => Cow::Owned(attr.value.replace('"', "&quot;")),
+            None => attr.value,
+        };
+        copy.push_attribute(Attribute {
+            key: attr.key,
+            value,
+        });
+    }
+    Ok(copy.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GPX_11: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <metadata><name>walk</name><bounds minlat="39.9" minlon="116.3" maxlat="40.0" maxlon="116.5"/></metadata>
+  <wpt lat="39.915" lon="116.404"><name>A &amp; B</name></wpt>
+  <rte><rtept lon="116.5" lat="40.0"/></rte>
+  <trk><trkseg>
+    <trkpt lat="39.9" lon="116.3"><ele>50</ele><time>2024-05-01T08:00:00Z</time>
+      <extensions><hr>120</hr></extensions></trkpt>
+  </trkseg></trk>
+  <!-- end -->
+</gpx>"#;
+
+    #[test]
+    fn test_convert_gpx_11() {
+        let output = convert_str(GPX_11, CoordSystem::Wgs84, CoordSystem::Bd09).unwrap();
+
+        let (lon, lat) = crate::wgs84_to_bd09(116.404, 39.915);
+        assert!(output.contains(&format!(r#"<wpt lat="{}" lon="{}">"#, lat, lon)));
+        let (lon, lat) = crate::wgs84_to_bd09(116.5, 40.0);
+        assert!(output.contains(&format!(r#"<rtept lon="{}" lat="{}"/>"#, lon, lat)));
+        let (min_lon, min_lat) = crate::wgs84_to_bd09(116.3, 39.9);
+        assert!(output.contains(&format!(
+            r#"<bounds minlat="{}" minlon="{}" maxlat="{}" maxlon="{}"/>"#,
+            min_lat, min_lon, lat, lon
+        )));
+        assert!(output.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(output.contains("<name>A &amp; B</name>"));
+        assert!(output.contains("<extensions><hr>120</hr></extensions>"));
+        assert!(output.contains("<!-- end -->"));
+
+        let back = convert_str(&output, CoordSystem::Bd09, CoordSystem::Wgs84).unwrap();
+        assert_eq!(back.lines().count(), GPX_11.lines().count());
+    }
+
+    #[test]
+    fn test_convert_gpx_10() {
+        let input = r#"<gpx version="1.0"><bounds minlat="0" minlon="0" maxlat="0" maxlon="0"/><wpt lat='39.915' lon='116.404' sym='"flag"'/></gpx>"#;
+        let mut output = Vec::new();
+        convert(
+            input.as_bytes(),
+            &mut output,
+            CoordSystem::Gcj02,
+            CoordSystem::Wgs84,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let (lon, lat) = crate::gcj02_to_wgs84(116.404, 39.915);
+        assert!(output.contains(&format!(r#"minlat="{}" minlon="{}""#, lat, lon)));
+        assert!(output.contains(&format!(
+            r#"<wpt lat="{}" lon="{}" sym="&quot;flag&quot;"/>"#,
+            lat, lon
+        )));
+    }
+
+    #[test]
+    fn test_invalid_gpx() {
+        let (from, to) = (CoordSystem::Wgs84, CoordSystem::Gcj02);
+        assert!(matches!(
+            convert_str("<kml/>", from, to),
+            Err(GpxError::NotGpx)
+        ));
+        assert!(matches!(convert_str("", from, to), Err(GpxError::NotGpx)));
+        assert!(matches!(
+            convert_str(r#"<gpx><wpt lat="1"/></gpx>"#, from, to),
+            Err(GpxError::InvalidPoint { .. })
+        ));
+        assert!(matches!(
+            convert_str(r#"<gpx><wpt lat="x" lon="1"/></gpx>"#, from, to),
+            Err(GpxError::InvalidPoint { .. })
+        ));
+        assert!(matches!(
+            convert_str("<gpx><trk></gpx>", from, to),
+            Err(GpxError::Xml(_))
+        ));
+    }
+}
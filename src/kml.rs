@@ -0,0 +1,592 @@
+//! KML/KMZ坐标转换 (需要 `kml` 特性，KMZ另需 `kmz` 特性)
+//! KML/KMZ conversion (requires the `kml` feature, plus `kmz` for KMZ archives)
+//!
+//! 转换 `coordinates` 元素 (Point、LineString、LinearRing、Polygon、MultiGeometry及
+//! `gx:LatLonQuad` 中的坐标元组)、`gx:Track` 的 `gx:coord` 以及 `LatLonBox`/`LatLonAltBox` 的
+//! 四个边界；样式、扩展数据、注释及元素顺序原样输出，坐标元组中的高度与空白布局保持不变。
+//! 边界框取四个角转换后的外包矩形；CDATA中的坐标同样转换并仍以CDATA输出。
+//!
+//! Converts `coordinates` elements (the tuples of Point, LineString, LinearRing, Polygon,
+//! MultiGeometry and `gx:LatLonQuad`), the `gx:coord` elements of `gx:Track` and the four edges of
+//! `LatLonBox`/`LatLonAltBox`; styles, extended data, comments and element order are written
+//! through unchanged, as are altitudes and the whitespace layout of coordinate tuples. Boxes become
+//! the envelope of their four converted corners; coordinates inside CDATA are converted too and
+//! written back as CDATA.
+//!
+//! ```rust
+//! use coordtransform::{kml, CoordSystem};
+//!
+//! let input = r#"<kml xmlns="http://www.opengis.net/kml/2.2"><Placemark>
+//!   <styleUrl>#red</styleUrl>
+//!   <Point><coordinates>116.404,39.915,44.5</coordinates></Point>
+//! </Placemark></kml>"#;
+//! let output = kml::convert_str(input, CoordSystem::Wgs84, CoordSystem::Gcj02).unwrap();
+//!
+//! let (lon, lat) = coordtransform::wgs84_to_gcj02(116.404, 39.915);
+//! assert!(output.contains(&format!("<coordinates>{},{},44.5</coordinates>", lon, lat)));
+//! assert!(output.contains("<styleUrl>#red</styleUrl>"));
+//! ```
+
+use std::fmt;
+#[cfg(feature = "kmz")]
+use std::io::Seek;
+use std::io::{self, Read, Write};
+
+use quick_xml::events::{BytesCData, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use crate::{CoordSystem, convert as convert_coord};
+
+/// KML转换错误
+/// KML conversion error
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum KmlError {
+    /// XML解析失败 XML parsing failed
+    Xml(quick_xml::Error),
+    /// 读写失败 Reading or writing failed
+    Io(io::Error),
+    /// KMZ压缩包读写失败 Reading or writing the KMZ archive failed
+    #[cfg(feature = "kmz")]
+    Zip(zip::result::ZipError),
+    /// 根元素不是 `kml`，或KMZ中没有KML文件
+    /// The root element is not `kml`, or a KMZ archive holds no KML file
+    NotKml,
+    /// 坐标不是数字、缺少分量或转换结果不是有限数
+    /// A coordinate is not a number, lacks a component or converts to a non-finite number
+    InvalidCoordinate {
+        /// 该内容之后的字节偏移 Byte offset just after the content
+        offset: u64,
+    },
+}
+
+impl fmt::Display for KmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KmlError::Xml(err) => write!(f, "invalid XML: {}", err),
+            KmlError::Io(err) => write!(f, "I/O error: {}", err),
+            #[cfg(feature = "kmz")]
+            KmlError::Zip(err) => write!(f, "invalid KMZ archive: {}", err),
+            KmlError::NotKml => f.write_str("not a KML document"),
+            KmlError::InvalidCoordinate { offset } => {
+                write!(f, "invalid coordinate before offset {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for KmlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KmlError::Xml(err) => Some(err),
+            KmlError::Io(err) => Some(err),
+            #[cfg(feature = "kmz")]
+            KmlError::Zip(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<quick_xml::Error> for KmlError {
+    fn from(err: quick_xml::Error) -> Self {
+        KmlError::Xml(err)
+    }
+}
+
+impl From<io::Error> for KmlError {
+    fn from(err: io::Error) -> Self {
+        KmlError::Io(err)
+    }
+}
+
+#[cfg(feature = "kmz")]
+impl From<zip::result::ZipError> for KmlError {
+    fn from(err: zip::result::ZipError) -> Self {
+        KmlError::Zip(err)
+    }
+}
+
+/// 当前文本内容的含义 What the current text content holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Content {
+    /// 无需转换 Nothing to convert
+    Other,
+    /// `coordinates` 中以空白分隔的 "经度,纬度[,高度]" 元组
+    /// Whitespace-separated "lon,lat[,alt]" tuples of `coordinates`
+    Tuples,
+    /// `gx:coord` 中以空格分隔的 "经度 纬度 高度"
+    /// Space-separated "lon lat alt" of `gx:coord`
+    GxCoord,
+    /// 边界框的某条边，序号依次为北、南、东、西
+    /// One edge of a box, indexed north, south, east, west
+    Edge(usize),
+}
+
+/// 边界框的四条边 The four edges of a box
+const EDGES: [&str; 4] = ["north", "south", "east", "west"];
+
+/// 正在缓冲的边界框 A box being buffered
+#[derive(Default)]
+struct PendingBox {
+    events: Vec<Event<'static>>,
+    /// 各条边的文本事件序号及数值 Index of each edge's text event and its value
+    edges: [Option<(usize, f64)>; 4],
+}
+
+/// 转换KML文本中的所有坐标
+/// Convert every coordinate of a KML text
+///
+/// # Parameters 参数
+///
+/// * `input` - KML文本 KML text
+/// * `from` - 源坐标系 Source system
+/// * `to` - 目标坐标系 Target system
+///
+/// # Return Value 返回值
+///
+/// 转换后的KML文本 The converted KML text
+pub fn convert_str(input: &str, from: CoordSystem, to: CoordSystem) -> Result<String, KmlError> {
+    let mut output = Vec::with_capacity(input.len());
+    convert_into(input, &mut output, from, to)?;
+    // 输入为UTF-8，写出的事件均来自输入或为ASCII数字
+    // The input is UTF-8 and every written event comes from it or is ASCII digits
+    Ok(String::from_utf8(output).expect("KML output is UTF-8"))
+}
+
+/// 从读取器读取KML并将转换结果写入写入器
+/// Read KML from a reader and write the converted document to a writer
+///
+/// # Parameters 参数
+///
+/// * `reader` - UTF-8编码的KML输入 UTF-8 encoded KML input
+/// * `writer` - KML输出 KML output
+/// * `from` - 源坐标系 Source system
+/// * `to` - 目标坐标系 Target system
+pub fn convert<R: Read, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<(), KmlError> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    convert_into(&input, &mut writer, from, to)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// 转换KMZ压缩包中的所有KML文件，其余文件 (图标、叠加图片等) 原样复制
+/// Convert every KML file of a KMZ archive, copying the other files (icons, overlay images, ...)
+/// unchanged
+///
+/// # Parameters 参数
+///
+/// * `reader` - KMZ输入 KMZ input
+/// * `writer` - KMZ输出 KMZ output
+/// * `from` - 源坐标系 Source system
+/// * `to` - 目标坐标系 Target system
+///
+/// # Return Value 返回值
+///
+/// 写入完成的输出 The finished output
+#[cfg(feature = "kmz")]
+pub fn convert_kmz<R: Read + Seek, W: Write + Seek>(
+    reader: R,
+    writer: W,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<W, KmlError> {
+    use zip::CompressionMethod;
+    use zip::write::SimpleFileOptions;
+
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut output = zip::ZipWriter::new(writer);
+    let mut documents = 0;
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        let name = file.name()?.into_owned();
+        if file.is_dir() || !name.to_ascii_lowercase().ends_with(".kml") {
+            output.raw_copy_file(file)?;
+            continue;
+        }
+
+        let mut input = String::new();
+        file.read_to_string(&mut input)?;
+        let method = match file.compression() {
+            CompressionMethod::Stored => CompressionMethod::Stored,
+            _ => CompressionMethod::Deflated,
+        };
+        let mut options = SimpleFileOptions::default().compression_method(method);
+        if let Some(modified) = file.last_modified() {
+            options = options.last_modified_time(modified);
+        }
+        if let Some(mode) = file.unix_mode() {
+            options = options.unix_permissions(mode);
+        }
+        drop(file);
+
+        output.start_file(name, options)?;
+        convert_into(&input, &mut output, from, to)?;
+        documents += 1;
+    }
+    if documents == 0 {
+        return Err(KmlError::NotKml);
+    }
+    Ok(output.finish()?)
+}
+
+fn convert_into<W: Write>(
+    input: &str,
+    writer: W,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<(), KmlError> {
+    let mut reader = Reader::from_str(input);
+    let mut writer = Writer::new(writer);
+    let mut content = Content::Other;
+    let mut pending: Option<PendingBox> = None;
+    let mut seen_root = false;
+
+    loop {
+        let event = reader.read_event()?;
+        let offset = reader.buffer_position();
+        let event = match event {
+            Event::Eof => break,
+            Event::Start(start) => {
+                let name = start.local_name();
+                if !seen_root {
+                    if name.as_ref() != "kml" {
+                        return Err(KmlError::NotKml);
+                    }
+                    seen_root = true;
+                }
+                content = match name.as_ref() {
+                    "coordinates" => Content::Tuples,
+                    "coord" => Content::GxCoord,
+                    "LatLonBox" | "LatLonAltBox" => {
+                        pending = Some(PendingBox::default());
+                        Content::Other
+                    }
+                    edge if pending.is_some() => EDGES
+                        .iter()
+                        .position(|name| *name == edge)
+                        .map_or(Content::Other, Content::Edge),
+                    _ => Content::Other,
+                };
+                Event::Start(start)
+            }
+            Event::Empty(start) => {
+                if !seen_root {
+                    if start.local_name().as_ref() != "kml" {
+                        return Err(KmlError::NotKml);
+                    }
+                    seen_root = true;
+                }
+                Event::Empty(start)
+            }
+            Event::Text(text) => {
+                match convert_content(content, pending.as_mut(), &text, from, to, offset)? {
+                    Some(converted) => Event::Text(BytesText::from_escaped(converted)),
+                    None => Event::Text(text),
+                }
+            }
+            Event::CData(data) => {
+                match convert_content(content, pending.as_mut(), &data, from, to, offset)? {
+                    Some(converted) => Event::CData(BytesCData::new(converted)),
+                    None => Event::CData(data),
+                }
+            }
+            Event::End(end) => {
+                content = Content::Other;
+                if matches!(end.local_name().as_ref(), "LatLonBox" | "LatLonAltBox")
+                    && let Some(mut pending) = pending.take()
+                {
+                    convert_box(&mut pending, from, to)
+                        .ok_or(KmlError::InvalidCoordinate { offset })?;
+                    for event in pending.events {
+                        writer.write_event(event)?;
+                    }
+                }
+                Event::End(end)
+            }
+            event => event,
+        };
+
+        match pending.as_mut() {
+            Some(pending) => pending.events.push(event.into_owned()),
+            None => writer.write_event(event)?,
+        }
+    }
+    if !seen_root {
+        return Err(KmlError::NotKml);
+    }
+    Ok(())
+}
+
+/// 转换文本或CDATA内容，返回 `None` 时原样输出；边界框的边只记录数值，结束时统一替换
+/// Convert text or CDATA content, `None` meaning it is written through unchanged; box edges only
+/// record their value and are replaced once the box ends
+fn convert_content(
+    content: Content,
+    pending: Option<&mut PendingBox>,
+    text: &str,
+    from: CoordSystem,
+    to: CoordSystem,
+    offset: u64,
+) -> Result<Option<String>, KmlError> {
+    let converted = match content {
+        Content::Other => return Ok(None),
+        Content::Tuples => convert_tuples(text, from, to),
+        Content::GxCoord => convert_gx_coord(text, from, to),
+        Content::Edge(edge) => {
+            let value = text.trim().parse::<f64>().ok();
+            if let (Some(pending), Some(value)) = (pending, value) {
+                pending.edges[edge] = Some((pending.events.len(), value));
+            }
+            return Ok(None);
+        }
+    };
+    converted
+        .map(Some)
+        .ok_or(KmlError::InvalidCoordinate { offset })
+}
+
+/// 转换以空白分隔的坐标元组，保留空白与高度
+/// Convert whitespace-separated coordinate tuples, keeping the whitespace and altitudes
+fn convert_tuples(text: &str, from: CoordSystem, to: CoordSystem) -> Option<String> {
+    let mut output = String::with_capacity(text.len() + 16);
+    let mut rest = text;
+    while !rest.is_empty() {
+        let token_start = rest.len() - rest.trim_start().len();
+        output.push_str(&rest[..token_start]);
+        rest = &rest[token_start..];
+
+        let token_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (token, tail) = rest.split_at(token_end);
+        if !token.is_empty() {
+            let mut parts = token.splitn(3, ',');
+            let (lon, lat) = convert_pair(parts.next()?, parts.next()?, from, to)?;
+            output.push_str(&format!("{},{}", lon, lat));
+            if let Some(altitude) = parts.next() {
+                output.push(',');
+                output.push_str(altitude);
+            }
+        }
+        rest = tail;
+    }
+    Some(output)
+}
+
+/// 转换 `gx:coord` 的 "经度 纬度 高度"
+/// Convert the "lon lat alt" of a `gx:coord`
+fn convert_gx_coord(text: &str, from: CoordSystem, to: CoordSystem) -> Option<String> {
+    let mut parts = text.split_whitespace();
+    let (lon, lat) = convert_pair(parts.next()?, parts.next()?, from, to)?;
+    let mut output = format!("{} {}", lon, lat);
+    for part in parts {
+        output.push(' ');
+        output.push_str(part);
+    }
+    Some(output)
+}
+
+fn convert_pair(lon: &str, lat: &str, from: CoordSystem, to: CoordSystem) -> Option<(f64, f64)> {
+    let lon = lon.trim().parse::<f64>().ok()?;
+    let lat = lat.trim().parse::<f64>().ok()?;
+    let (lon, lat) = convert_coord(from, to, lon, lat);
+    (lon.is_finite() && lat.is_finite()).then_some((lon, lat))
+}
+
+/// 将边界框替换为四个角转换后的外包矩形
+/// Replace a box by the envelope of its four converted corners
+fn convert_box(pending: &mut PendingBox, from: CoordSystem, to: CoordSystem) -> Option<()> {
+    let [north, south, east, west] = pending.edges.map(|edge| edge.map(|(_, value)| value));
+    let (north, south, east, west) = (north?, south?, east?, west?);
+
+    let mut envelope = [
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+    ];
+    for (lon, lat) in [(west, south), (east, south), (west, north), (east, north)] {
+        let (lon, lat) = convert_coord(from, to, lon, lat);
+        if !lon.is_finite() || !lat.is_finite() {
+            return None;
+        }
+        envelope[0] = envelope[0].max(lat);
+        envelope[1] = envelope[1].min(lat);
+        envelope[2] = envelope[2].max(lon);
+        envelope[3] = envelope[3].min(lon);
+    }
+
+    for (edge, value) in pending.edges.iter().zip(envelope) {
+        if let Some((index, _)) = edge {
+            pending.events[*index] = Event::Text(BytesText::from_escaped(value.to_string()));
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+<Document>
+  <Style id="red"><LineStyle><color>ff0000ff</color></LineStyle></Style>
+  <Placemark>
+    <ExtendedData><Data name="lat"><value>1,2</value></Data></ExtendedData>
+    <MultiGeometry>
+      <LineString><coordinates>
+        116.3,39.9,10 116.4,39.95
+      </coordinates></LineString>
+      <Polygon><outerBoundaryIs><LinearRing><coordinates>116.3,39.9 116.4,39.9 116.4,40 116.3,39.9</coordinates></LinearRing></outerBoundaryIs></Polygon>
+    </MultiGeometry>
+  </Placemark>
+  <Placemark><gx:Track><when>2024-05-01T08:00:00Z</when><gx:coord>116.404 39.915 44.5</gx:coord></gx:Track></Placemark>
+  <GroundOverlay><Icon><href>files/map.png</href></Icon>
+    <LatLonBox><north>40</north><south>39.9</south><east>116.5</east><west>116.3</west><rotation>0</rotation></LatLonBox>
+  </GroundOverlay>
+</Document>
+</kml>"#;
+
+    #[test]
+    fn test_convert_kml() {
+        let output = convert_str(KML, CoordSystem::Wgs84, CoordSystem::Gcj02).unwrap();
+
+        let (lon, lat) = crate::wgs84_to_gcj02(116.3, 39.9);
+        let (lon2, lat2) = crate::wgs84_to_gcj02(116.4, 39.95);
+        assert!(output.contains(&format!(
+            "<coordinates>\n        {},{},10 {},{}\n      </coordinates>",
+            lon, lat, lon2, lat2
+        )));
+        let (lon, lat) = crate::wgs84_to_gcj02(116.404, 39.915);
+        assert!(output.contains(&format!("<gx:coord>{} {} 44.5</gx:coord>", lon, lat)));
+        assert!(output.contains("<value>1,2</value>"));
+        assert!(output.contains("<color>ff0000ff</color>"));
+
+        let corners = [(116.3, 39.9), (116.5, 39.9), (116.3, 40.0), (116.5, 40.0)]
+            .map(|(lon, lat)| crate::wgs84_to_gcj02(lon, lat));
+        let south = corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min);
+        let east = corners
+            .iter()
+            .map(|c| c.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert!(output.contains(&format!("<south>{}</south>", south)));
+        assert!(output.contains(&format!("<east>{}</east>", east)));
+        assert!(output.contains("<rotation>0</rotation></LatLonBox>"));
+        assert_eq!(output.lines().count(), KML.lines().count());
+    }
+
+    #[test]
+    fn test_cdata() {
+        let input = "<kml><Placemark><name><![CDATA[A & B]]></name>\
+            <Point><coordinates><![CDATA[116.404,39.915,44.5]]></coordinates></Point>\
+            <gx:Track><gx:coord><![CDATA[116.404 39.915 44.5]]></gx:coord></gx:Track>\
+            <LatLonBox><north><![CDATA[40]]></north><south>39.9</south>\
+            <east>116.5</east><west>116.3</west></LatLonBox></Placemark></kml>";
+        let output = convert_str(input, CoordSystem::Wgs84, CoordSystem::Gcj02).unwrap();
+
+        let (lon, lat) = crate::wgs84_to_gcj02(116.404, 39.915);
+        assert!(output.contains(&format!(
+            "<coordinates><![CDATA[{},{},44.5]]></coordinates>",
+            lon, lat
+        )));
+        assert!(output.contains(&format!(
+            "<gx:coord><![CDATA[{} {} 44.5]]></gx:coord>",
+            lon, lat
+        )));
+        assert!(output.contains("<name><![CDATA[A & B]]></name>"));
+        let north = [(116.3, 40.0), (116.5, 40.0)]
+            .map(|(lon, lat)| crate::wgs84_to_gcj02(lon, lat).1)
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert!(output.contains(&format!("<north>{}</north>", north)));
+
+        assert!(matches!(
+            convert_str(
+                "<kml><Point><coordinates><![CDATA[116.4]]></coordinates></Point></kml>",
+                CoordSystem::Wgs84,
+                CoordSystem::Gcj02
+            ),
+            Err(KmlError::InvalidCoordinate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_kml() {
+        let (from, to) = (CoordSystem::Wgs84, CoordSystem::Gcj02);
+        assert!(matches!(
+            convert_str("<gpx/>", from, to),
+            Err(KmlError::NotKml)
+        ));
+        assert_eq!(convert_str("<kml/>", from, to).unwrap(), "<kml/>");
+        assert!(matches!(
+            convert_str(
+                "<kml><Point><coordinates>116.4</coordinates></Point></kml>",
+                from,
+                to
+            ),
+            Err(KmlError::InvalidCoordinate { .. })
+        ));
+        assert!(matches!(
+            convert_str(
+                "<kml><LatLonBox><north>40</north></LatLonBox></kml>",
+                from,
+                to
+            ),
+            Err(KmlError::InvalidCoordinate { .. })
+        ));
+        assert!(matches!(
+            convert_str("<kml><Point></kml>", from, to),
+            Err(KmlError::Xml(_))
+        ));
+    }
+
+    #[cfg(feature = "kmz")]
+    #[test]
+    fn test_convert_kmz() {
+        use std::io::Cursor;
+        use zip::write::SimpleFileOptions;
+
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("doc.kml", SimpleFileOptions::default())
+            .unwrap();
+        archive.write_all(KML.as_bytes()).unwrap();
+        archive
+            .start_file("files/map.png", SimpleFileOptions::default())
+            .unwrap();
+        archive.write_all(b"\x89PNG").unwrap();
+        let input = archive.finish().unwrap().into_inner();
+
+        let output = convert_kmz(
+            Cursor::new(input),
+            Cursor::new(Vec::new()),
+            CoordSystem::Wgs84,
+            CoordSystem::Gcj02,
+        )
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(output.into_inner())).unwrap();
+        let mut document = String::new();
+        archive
+            .by_name("doc.kml")
+            .unwrap()
+            .read_to_string(&mut document)
+            .unwrap();
+        assert_eq!(
+            document,
+            convert_str(KML, CoordSystem::Wgs84, CoordSystem::Gcj02).unwrap()
+        );
+        let mut image = Vec::new();
+        archive
+            .by_name("files/map.png")
+            .unwrap()
+            .read_to_end(&mut image)
+            .unwrap();
+        assert_eq!(image, b"\x89PNG");
+    }
+}
@@ -32,6 +32,12 @@
 //! // gcj02火星坐标系 -> WGS84坐标系
 //! let (lon, lat) = gcj02_to_wgs84(116.404, 39.915);
 //!
+//! // gcj02火星坐标系 -> WGS84坐标系 (迭代求逆，高精度)
+//! let (lon, lat) = gcj02_to_wgs84_precise(116.404, 39.915);
+//!
+//! // bd09百度坐标系 -> gcj02火星坐标系 (迭代求逆，高精度)
+//! let (lon, lat) = bd09_to_gcj02_precise(116.404, 39.915);
+//!
 //! // bd09百度坐标系 -> WGS84坐标系
 //! let (lon, lat) = bd09_to_wgs84(116.404, 39.915);
 //!
@@ -43,15 +49,108 @@
 //!
 //! // EPSG:3857坐标系 -> WGS84坐标系
 //! let (lon, lat) = epsg3857_to_wgs84(12958752.0, 4825923.0);
+//!
+//! // 校验输入，拒绝NaN及超出范围的坐标 Validate input, rejecting NaN and out-of-range coordinates
+//! assert!(try_wgs84_to_gcj02(f64::NAN, 39.915).is_err());
+//!
+//! // 运行时选择坐标系 Choose the coordinate systems at runtime
+//! let from: CoordSystem = "bd09ll".parse().unwrap();
+//! let (lon, lat) = convert(from, CoordSystem::Wgs84, 116.404, 39.915);
+//! ```
+//!
+//! ## no_std
+//!
+//! 关闭默认的 `std` 特性并启用 `libm` 特性即可在裸机目标上使用本文件中的所有转换函数；
+//! 启用 `libm` 时结果在各平台上逐位一致。几何、WKT/WKB及各文件格式模块需要 `std`。
+//!
+//! Disable the default `std` feature and enable `libm` to use every conversion function of this
+//! file on bare-metal targets; with `libm` the results are bit-identical across platforms. The
+//! geometry, WKT/WKB and file format modules require `std`.
+//!
+//! ```toml
+//! coordtransform = { version = "0.3", default-features = false, features = ["libm"] }
 //! ```
 
-use std::f64::consts::PI;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("coordtransform requires the `std` feature or, for no_std builds, the `libm` feature");
+
+use core::f64::consts::PI;
+
+use math::{atan, atan2, cos, exp, ln, sin, sqrt, tan};
+
+pub mod batch;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod f32;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+#[cfg(feature = "std")]
+pub mod geometry;
+#[cfg(feature = "gpx")]
+pub mod gpx;
+#[cfg(feature = "kml")]
+pub mod kml;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod tile;
+#[cfg(feature = "serde")]
+pub mod with;
+#[cfg(feature = "std")]
+pub mod wkb;
+#[cfg(feature = "std")]
+pub mod wkt;
+
+mod bd09mc;
+mod boundary;
+mod checked;
+mod error;
+#[cfg(feature = "geo-types")]
+mod geo;
+mod math;
+mod point;
+mod precise;
+mod system;
+
+pub use bd09mc::{
+    bd09_to_bd09mc, bd09mc_to_bd09, bd09mc_to_gcj02, bd09mc_to_wgs84, gcj02_to_bd09mc,
+    wgs84_to_bd09mc,
+};
+pub use boundary::{
+    Provider, Region, RegionPolicy, ShiftPolicy, is_in_china, region_of, region_policy,
+    set_provider_policy, set_region_policy,
+};
+
+pub use precise::{
+    InverseOptions, InverseResult, bd09_to_epsg3857_precise, bd09_to_gcj02_iterative,
+    bd09_to_gcj02_precise, bd09_to_wgs84_precise, gcj02_to_epsg3857_precise,
+    gcj02_to_wgs84_iterative, gcj02_to_wgs84_precise,
+};
+pub use checked::{
+    try_bd09_to_epsg3857, try_bd09_to_epsg3857_precise, try_bd09_to_epsg3857_precise_with,
+    try_bd09_to_gcj02, try_bd09_to_gcj02_precise, try_bd09_to_gcj02_precise_with,
+    try_bd09_to_wgs84, try_bd09_to_wgs84_precise, try_bd09_to_wgs84_precise_with, try_convert,
+    try_epsg3857_to_bd09, try_epsg3857_to_gcj02, try_epsg3857_to_wgs84, try_gcj02_to_bd09,
+    try_gcj02_to_epsg3857, try_gcj02_to_wgs84, try_gcj02_to_wgs84_precise,
+    try_gcj02_to_wgs84_precise_with, try_wgs84_to_bd09, try_wgs84_to_epsg3857, try_wgs84_to_gcj02,
+};
+pub use error::TransformError;
+#[cfg(feature = "geo-types")]
+pub use geo::ChinaTransform;
+pub use point::{Bd09Point, Gcj02Point, TaggedCoord, WebMercatorPoint, Wgs84Point};
+pub use system::{CoordSystem, ParseCoordSystemError, convert, convert_with_policy};
 
 /// X_PI constant 常量
 ///
 const X_PI: f64 = PI * 3000.0 / 180.0;
 
 /// Offset constant 偏移量常量
+#[allow(clippy::excessive_precision)]
 const OFFSET: f64 = 0.00669342162296594323;
 
 /// 地球长半轴
@@ -63,6 +162,7 @@ const AXIS: f64 = 6378245.0;
 
 /// 地球半径 (米)
 /// Earth radius in meters
+#[allow(clippy::empty_line_after_doc_comments)]
 const EARTH_RADIUS: f64 = 6378137.0;
 
 /// 最大纬度 (度)
@@ -92,11 +192,11 @@ pub fn bd09_to_gcj02(lon: f64, lat: f64) -> (f64, f64) {
     let x = lon - 0.0065;
     let y = lat - 0.006;
 
-    let z = (x * x + y * y).sqrt() - 0.00002 * (y * X_PI).sin();
-    let theta = y.atan2(x) - 0.000003 * (x * X_PI).cos();
+    let z = sqrt(x * x + y * y) - 0.00002 * sin(y * X_PI);
+    let theta = atan2(y, x) - 0.000003 * cos(x * X_PI);
 
-    let g_lon = z * theta.cos();
-    let g_lat = z * theta.sin();
+    let g_lon = z * cos(theta);
+    let g_lat = z * sin(theta);
 
     (g_lon, g_lat)
 }
@@ -120,11 +220,11 @@ pub fn bd09_to_gcj02(lon: f64, lat: f64) -> (f64, f64) {
 /// let (lon, lat) = gcj02_to_bd09(116.404, 39.915);
 /// ```
 pub fn gcj02_to_bd09(lon: f64, lat: f64) -> (f64, f64) {
-    let z = (lon * lon + lat * lat).sqrt() + 0.00002 * (lat * X_PI).sin();
-    let theta = lat.atan2(lon) + 0.000003 * (lon * X_PI).cos();
+    let z = sqrt(lon * lon + lat * lat) + 0.00002 * sin(lat * X_PI);
+    let theta = atan2(lat, lon) + 0.000003 * cos(lon * X_PI);
 
-    let bd_lon = z * theta.cos() + 0.0065;
-    let bd_lat = z * theta.sin() + 0.006;
+    let bd_lon = z * cos(theta) + 0.0065;
+    let bd_lat = z * sin(theta) + 0.006;
 
     (bd_lon, bd_lat)
 }
@@ -179,9 +279,7 @@ pub fn gcj02_to_wgs84(lon: f64, lat: f64) -> (f64, f64) {
         return (lon, lat);
     }
 
-    let (mg_lon, mg_lat) = delta(lon, lat);
-
-    (lon * 2.0 - mg_lon, lat * 2.0 - mg_lat)
+    undelta(lon, lat)
 }
 
 /// 百度坐标系 -> WGS84坐标系
@@ -232,16 +330,23 @@ pub fn wgs84_to_bd09(lon: f64, lat: f64) -> (f64, f64) {
     gcj02_to_bd09(lon, lat)
 }
 
+/// Approximate inverse of the coordinate offset 坐标偏移量的近似逆变换
+fn undelta(lon: f64, lat: f64) -> (f64, f64) {
+    let (mg_lon, mg_lat) = delta(lon, lat);
+
+    (lon * 2.0 - mg_lon, lat * 2.0 - mg_lat)
+}
+
 /// Calculate coordinate offset 计算坐标偏移量
 fn delta(lon: f64, lat: f64) -> (f64, f64) {
     let (dlat, dlon) = transform(lon - 105.0, lat - 35.0);
     let radlat = lat / 180.0 * PI;
-    let magic = radlat.sin();
+    let magic = sin(radlat);
     let magic = 1.0 - OFFSET * magic * magic;
-    let sqrtmagic = magic.sqrt();
+    let sqrtmagic = sqrt(magic);
 
     let dlat = (dlat * 180.0) / ((AXIS * (1.0 - OFFSET)) / (magic * sqrtmagic) * PI);
-    let dlon = (dlon * 180.0) / (AXIS / sqrtmagic * radlat.cos() * PI);
+    let dlon = (dlon * 180.0) / (AXIS / sqrtmagic * cos(radlat) * PI);
 
     let mg_lat = lat + dlat;
     let mg_lon = lon + dlon;
@@ -252,18 +357,18 @@ fn delta(lon: f64, lat: f64) -> (f64, f64) {
 /// Coordinate transformation function 坐标变换函数
 fn transform(lon: f64, lat: f64) -> (f64, f64) {
     let lonlat = lon * lat;
-    let abs_x = lon.abs().sqrt();
+    let abs_x = sqrt(lon.abs());
     let lon_pi = lon * PI;
     let lat_pi = lat * PI;
-    let d = 20.0 * (6.0 * lon_pi).sin() + 20.0 * (2.0 * lon_pi).sin();
+    let d = 20.0 * sin(6.0 * lon_pi) + 20.0 * sin(2.0 * lon_pi);
 
     let mut x = d;
     let mut y = d;
 
-    x += 20.0 * lat_pi.sin() + 40.0 * (lat_pi / 3.0).sin();
-    y += 20.0 * lon_pi.sin() + 40.0 * (lon_pi / 3.0).sin();
-    x += 160.0 * (lat_pi / 12.0).sin() + 320.0 * (lat_pi / 30.0).sin();
-    y += 150.0 * (lon_pi / 12.0).sin() + 300.0 * (lon_pi / 30.0).sin();
+    x += 20.0 * sin(lat_pi) + 40.0 * sin(lat_pi / 3.0);
+    y += 20.0 * sin(lon_pi) + 40.0 * sin(lon_pi / 3.0);
+    x += 160.0 * sin(lat_pi / 12.0) + 320.0 * sin(lat_pi / 30.0);
+    y += 150.0 * sin(lon_pi / 12.0) + 300.0 * sin(lon_pi / 30.0);
 
     x *= 2.0 / 3.0;
     y *= 2.0 / 3.0;
@@ -293,13 +398,14 @@ fn transform(lon: f64, lat: f64) -> (f64, f64) {
 ///
 /// let (x, y) = wgs84_to_epsg3857(116.404, 39.915);
 /// ```
+#[allow(clippy::manual_clamp)]
 pub fn wgs84_to_epsg3857(lon: f64, lat: f64) -> (f64, f64) {
-    // 限制纬度范围
-    // Clamp latitude to valid range
+    // 限制纬度范围 (NaN映射为 -MAX_LATITUDE，`clamp` 会保留NaN)
+    // Clamp latitude to valid range (NaN becomes -MAX_LATITUDE, `clamp` would keep it)
     let lat = lat.max(-MAX_LATITUDE).min(MAX_LATITUDE);
     
     let x = lon * PI / 180.0 * EARTH_RADIUS;
-    let y = ((PI / 4.0 + lat * PI / 360.0).tan()).ln() * EARTH_RADIUS;
+    let y = ln(tan(PI / 4.0 + lat * PI / 360.0)) * EARTH_RADIUS;
     
     (x, y)
 }
@@ -325,7 +431,7 @@ pub fn wgs84_to_epsg3857(lon: f64, lat: f64) -> (f64, f64) {
 /// ```
 pub fn epsg3857_to_wgs84(x: f64, y: f64) -> (f64, f64) {
     let lon = x / EARTH_RADIUS * 180.0 / PI;
-    let lat = (2.0 * (y / EARTH_RADIUS).exp().atan() - PI / 2.0) * 180.0 / PI;
+    let lat = (2.0 * atan(exp(y / EARTH_RADIUS)) - PI / 2.0) * 180.0 / PI;
     
     (lon, lat)
 }
@@ -427,14 +533,24 @@ pub fn epsg3857_to_bd09(x: f64, y: f64) -> (f64, f64) {
 }
 
 /// Determine whether the coordinates are outside of China 判断坐标是否在中国境外
+///
+/// 按进程级默认策略不偏移的区域 (见 [`set_region_policy`]) 也视为境外。
+/// Regions whose default shift policy is [`ShiftPolicy::NoShift`] (see [`set_region_policy`])
+/// count as outside as well.
 fn is_out_of_china(lon: f64, lat: f64) -> bool {
-    !(lon > 72.004 && lon < 135.05 && lat > 3.86 && lat < 53.55)
+    !boundary::is_shifted(lon, lat)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// 覆盖中国范围的网格 A grid covering China
+    pub(crate) fn china_grid() -> impl Iterator<Item = (f64, f64)> {
+        (0..=60)
+            .flat_map(|i| (0..=40).map(move |j| (73.6 + i as f64 * 1.01, 18.2 + j as f64 * 0.88)))
+    }
+
     #[test]
     fn test_bd09_to_gcj02() {
         let (lon, lat) = bd09_to_gcj02(116.404, 39.915);
@@ -540,6 +656,16 @@ mod tests {
         assert!((lat - expected_lat).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_neighbouring_countries_not_shifted() {
+        // 首尔、河内、乌兰巴托不在中国境内，不应偏移
+        // Seoul, Hanoi and Ulaanbaatar are outside China and must not be shifted
+        for (lon, lat) in [(126.978, 37.566), (105.834, 21.028), (106.906, 47.886)] {
+            assert_eq!(wgs84_to_gcj02(lon, lat), (lon, lat));
+            assert_eq!(gcj02_to_wgs84(lon, lat), (lon, lat));
+        }
+    }
+
     #[test]
     fn test_epsg3857_edge_cases() {
         // 测试极限纬度
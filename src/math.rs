@@ -0,0 +1,82 @@
+//! 转换所需的浮点函数
+//! Floating-point functions used by the conversions
+//!
+//! 启用 `libm` 特性时始终使用 [libm](https://crates.io/crates/libm) 实现 (即使同时启用了 `std`)，
+//! 使 `no_std` 设备与服务器上的结果逐位一致；否则使用 `std` 的 `f64` 方法。
+//!
+//! With the `libm` feature the [libm](https://crates.io/crates/libm) implementations are always
+//! used, even alongside `std`, so results are bit-identical between `no_std` devices and servers;
+//! otherwise the `f64` methods of `std` are used.
+
+#[cfg(feature = "libm")]
+pub(crate) use libm::{atan, atan2, cos, exp, floor, log as ln, sin, sqrt, tan};
+
+#[cfg(not(feature = "libm"))]
+mod imp {
+    #[inline]
+    pub(crate) fn sin(x: f64) -> f64 {
+        x.sin()
+    }
+
+    #[inline]
+    pub(crate) fn cos(x: f64) -> f64 {
+        x.cos()
+    }
+
+    #[inline]
+    pub(crate) fn tan(x: f64) -> f64 {
+        x.tan()
+    }
+
+    #[inline]
+    pub(crate) fn atan(x: f64) -> f64 {
+        x.atan()
+    }
+
+    #[inline]
+    pub(crate) fn atan2(y: f64, x: f64) -> f64 {
+        y.atan2(x)
+    }
+
+    #[inline]
+    pub(crate) fn sqrt(x: f64) -> f64 {
+        x.sqrt()
+    }
+
+    #[inline]
+    pub(crate) fn exp(x: f64) -> f64 {
+        x.exp()
+    }
+
+    #[inline]
+    pub(crate) fn ln(x: f64) -> f64 {
+        x.ln()
+    }
+
+    #[inline]
+    pub(crate) fn floor(x: f64) -> f64 {
+        x.floor()
+    }
+}
+
+#[cfg(not(feature = "libm"))]
+pub(crate) use imp::{atan, atan2, cos, exp, floor, ln, sin, sqrt, tan};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_math_functions() {
+        let x = 0.7;
+        assert!((sin(x) - 0.644217687237691).abs() < 1e-15);
+        assert!((cos(x) - 0.7648421872844885).abs() < 1e-15);
+        assert!((tan(x) - 0.8422883804630794).abs() < 1e-15);
+        assert!((atan(x) - 0.6107259643892086).abs() < 1e-15);
+        assert!((atan2(x, -1.0) - 2.5308666892005847).abs() < 1e-15);
+        assert!((sqrt(x) - 0.8366600265340756).abs() < 1e-15);
+        assert!((exp(x) - 2.0137527074704766).abs() < 1e-15);
+        assert!((ln(x) + 0.35667494393873245).abs() < 1e-15);
+        assert_eq!((floor(x), floor(-x), floor(2.0)), (0.0, -1.0, 2.0));
+    }
+}
@@ -0,0 +1,260 @@
+//! 并行批量坐标转换 (需要 `rayon` 特性)
+//! Parallel batch coordinate conversion (requires the `rayon` feature)
+//!
+//! 与 [`batch`](crate::batch) 模块的接口相同，输入按 [`CHUNK_SIZE`] 个点分块后在rayon线程池中并行转换。
+//! 每个分块调用相同的逐点转换函数，结果与串行版本完全一致。另外提供返回 `Vec` 的 `_to_vec` 版本。
+//!
+//! Same interface as the [`batch`](crate::batch) module; the input is split into chunks of
+//! [`CHUNK_SIZE`] points that are converted on the rayon thread pool. Every chunk calls the same
+//! per-point functions, so results are identical to the serial path. `_to_vec` variants returning a
+//! `Vec` are provided as well.
+//!
+//! ```rust
+//! use coordtransform::{batch, parallel};
+//!
+//! let input = vec![(116.404, 39.915); 10_000];
+//! let output = parallel::wgs84_to_gcj02_to_vec(&input);
+//!
+//! let mut expected = vec![(0.0, 0.0); input.len()];
+//! batch::wgs84_to_gcj02(&input, &mut expected);
+//! assert_eq!(output, expected);
+//! ```
+
+use rayon::prelude::*;
+
+use crate::CoordSystem;
+
+/// 每个并行任务处理的点数 Number of points handled by each parallel task
+pub const CHUNK_SIZE: usize = 4096;
+
+/// 生成一组并行批量转换函数
+/// Generate a set of parallel batch conversion functions
+macro_rules! parallel_conversion {
+    ($name:ident, $in_place:ident, $to_vec:ident, $title:literal) => {
+        #[doc = concat!($title, "，并行批量转换到输出切片")]
+        #[doc = ""]
+        #[doc = concat!("Parallel version of [`batch::", stringify!($name), "`](crate::batch::", stringify!($name), ").")]
+        #[doc = ""]
+        #[doc = "# Panics"]
+        #[doc = ""]
+        #[doc = "输入与输出长度不同时 panic Panics if `input` and `output` differ in length"]
+        pub fn $name(input: &[(f64, f64)], output: &mut [(f64, f64)]) {
+            map_into(input, output, crate::$name);
+        }
+
+        #[doc = concat!($title, "，并行原地批量转换")]
+        #[doc = ""]
+        #[doc = concat!("Parallel version of [`batch::", stringify!($in_place), "`](crate::batch::", stringify!($in_place), ").")]
+        pub fn $in_place(points: &mut [(f64, f64)]) {
+            map_in_place(points, crate::$name);
+        }
+
+        #[doc = concat!($title, "，并行批量转换并返回新的 `Vec`")]
+        #[doc = ""]
+        #[doc = concat!("Parallel version of [`batch::", stringify!($name), "`](crate::batch::", stringify!($name), ") returning a new `Vec`.")]
+        pub fn $to_vec(input: &[(f64, f64)]) -> Vec<(f64, f64)> {
+            map_to_vec(input, crate::$name)
+        }
+    };
+}
+
+parallel_conversion!(
+    bd09_to_gcj02,
+    bd09_to_gcj02_in_place,
+    bd09_to_gcj02_to_vec,
+    "百度坐标系 -> 火星坐标系"
+);
+parallel_conversion!(
+    gcj02_to_bd09,
+    gcj02_to_bd09_in_place,
+    gcj02_to_bd09_to_vec,
+    "火星坐标系 -> 百度坐标系"
+);
+parallel_conversion!(
+    wgs84_to_gcj02,
+    wgs84_to_gcj02_in_place,
+    wgs84_to_gcj02_to_vec,
+    "WGS84坐标系 -> 火星坐标系"
+);
+parallel_conversion!(
+    gcj02_to_wgs84,
+    gcj02_to_wgs84_in_place,
+    gcj02_to_wgs84_to_vec,
+    "火星坐标系 -> WGS84坐标系"
+);
+parallel_conversion!(
+    bd09_to_wgs84,
+    bd09_to_wgs84_in_place,
+    bd09_to_wgs84_to_vec,
+    "百度坐标系 -> WGS84坐标系"
+);
+parallel_conversion!(
+    wgs84_to_bd09,
+    wgs84_to_bd09_in_place,
+    wgs84_to_bd09_to_vec,
+    "WGS84坐标系 -> 百度坐标系"
+);
+parallel_conversion!(
+    wgs84_to_epsg3857,
+    wgs84_to_epsg3857_in_place,
+    wgs84_to_epsg3857_to_vec,
+    "WGS84坐标系 -> EPSG:3857坐标系"
+);
+parallel_conversion!(
+    epsg3857_to_wgs84,
+    epsg3857_to_wgs84_in_place,
+    epsg3857_to_wgs84_to_vec,
+    "EPSG:3857坐标系 -> WGS84坐标系"
+);
+parallel_conversion!(
+    gcj02_to_epsg3857,
+    gcj02_to_epsg3857_in_place,
+    gcj02_to_epsg3857_to_vec,
+    "GCJ02坐标系 -> EPSG:3857坐标系"
+);
+parallel_conversion!(
+    epsg3857_to_gcj02,
+    epsg3857_to_gcj02_in_place,
+    epsg3857_to_gcj02_to_vec,
+    "EPSG:3857坐标系 -> GCJ02坐标系"
+);
+parallel_conversion!(
+    bd09_to_epsg3857,
+    bd09_to_epsg3857_in_place,
+    bd09_to_epsg3857_to_vec,
+    "BD09坐标系 -> EPSG:3857坐标系"
+);
+parallel_conversion!(
+    epsg3857_to_bd09,
+    epsg3857_to_bd09_in_place,
+    epsg3857_to_bd09_to_vec,
+    "EPSG:3857坐标系 -> BD09坐标系"
+);
+
+/// 任意两个坐标系之间的并行批量转换
+/// Parallel batch conversion between any two coordinate systems
+///
+/// # Panics
+///
+/// 输入与输出长度不同时 panic Panics if `input` and `output` differ in length
+pub fn convert(
+    from: CoordSystem,
+    to: CoordSystem,
+    input: &[(f64, f64)],
+    output: &mut [(f64, f64)],
+) {
+    map_into(input, output, |lon, lat| crate::convert(from, to, lon, lat));
+}
+
+/// 任意两个坐标系之间的并行原地批量转换
+/// Parallel in-place batch conversion between any two coordinate systems
+pub fn convert_in_place(from: CoordSystem, to: CoordSystem, points: &mut [(f64, f64)]) {
+    map_in_place(points, |lon, lat| crate::convert(from, to, lon, lat));
+}
+
+/// 任意两个坐标系之间的并行批量转换，返回新的 `Vec`
+/// Parallel batch conversion between any two coordinate systems returning a new `Vec`
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{parallel, CoordSystem};
+///
+/// let points = vec![(116.404, 39.915); 100];
+/// let converted = parallel::convert_to_vec(CoordSystem::Bd09, CoordSystem::Wgs84, &points);
+/// assert_eq!(converted.len(), points.len());
+/// ```
+pub fn convert_to_vec(from: CoordSystem, to: CoordSystem, input: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    map_to_vec(input, |lon, lat| crate::convert(from, to, lon, lat))
+}
+
+fn map_into<F>(input: &[(f64, f64)], output: &mut [(f64, f64)], f: F)
+where
+    F: Fn(f64, f64) -> (f64, f64) + Sync,
+{
+    assert_eq!(
+        input.len(),
+        output.len(),
+        "input and output slices must have the same length"
+    );
+    output
+        .par_chunks_mut(CHUNK_SIZE)
+        .zip(input.par_chunks(CHUNK_SIZE))
+        .for_each(|(out, chunk)| {
+            for (out, &(lon, lat)) in out.iter_mut().zip(chunk) {
+                *out = f(lon, lat);
+            }
+        });
+}
+
+fn map_in_place<F>(points: &mut [(f64, f64)], f: F)
+where
+    F: Fn(f64, f64) -> (f64, f64) + Sync,
+{
+    points.par_chunks_mut(CHUNK_SIZE).for_each(|chunk| {
+        for point in chunk.iter_mut() {
+            *point = f(point.0, point.1);
+        }
+    });
+}
+
+fn map_to_vec<F>(input: &[(f64, f64)], f: F) -> Vec<(f64, f64)>
+where
+    F: Fn(f64, f64) -> (f64, f64) + Sync,
+{
+    let mut output = vec![(0.0, 0.0); input.len()];
+    map_into(input, &mut output, f);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 跨越多个分块且最后一块不满的测试点
+    /// Test points spanning several chunks with a partial last chunk
+    fn points() -> Vec<(f64, f64)> {
+        (0..CHUNK_SIZE * 3 + 17)
+            .map(|i| {
+                (
+                    100.0 + (i % 300) as f64 * 0.1,
+                    20.0 + (i % 200) as f64 * 0.1,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parallel_matches_serial() {
+        let input = points();
+        let mut expected = vec![(0.0, 0.0); input.len()];
+        crate::batch::wgs84_to_bd09(&input, &mut expected);
+
+        let mut output = vec![(0.0, 0.0); input.len()];
+        wgs84_to_bd09(&input, &mut output);
+        assert_eq!(output, expected);
+        assert_eq!(wgs84_to_bd09_to_vec(&input), expected);
+
+        let mut points = input.clone();
+        wgs84_to_bd09_in_place(&mut points);
+        assert_eq!(points, expected);
+    }
+
+    #[test]
+    fn test_parallel_convert() {
+        let input = points();
+        let mut expected = input.clone();
+        crate::batch::convert_in_place(CoordSystem::Gcj02, CoordSystem::Epsg3857, &mut expected);
+        assert_eq!(
+            convert_to_vec(CoordSystem::Gcj02, CoordSystem::Epsg3857, &input),
+            expected
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_parallel_length_mismatch() {
+        let mut output = [(0.0, 0.0); 2];
+        gcj02_to_wgs84(&points(), &mut output);
+    }
+}
@@ -0,0 +1,276 @@
+//! 各坐标系的类型安全坐标点
+//! Type-safe points per coordinate system
+//!
+//! 每个坐标系都有自己的点类型，坐标系之间只能通过 [`From`]/[`Into`] 转换，
+//! 编译器因此会拒绝把BD09坐标当作GCJ02坐标使用。
+//!
+//! Each coordinate system has its own point type and points only move between systems through
+//! [`From`]/[`Into`], so the compiler rejects passing a BD09 point where a GCJ02 point is expected.
+//!
+//! ```rust
+//! use coordtransform::{Bd09Point, Gcj02Point, Wgs84Point};
+//!
+//! let wgs = Wgs84Point::new(116.404, 39.915);
+//! let gcj: Gcj02Point = wgs.into();
+//! let bd = Bd09Point::from(gcj);
+//! let (lon, lat) = bd.into();
+//! ```
+
+use crate::{
+    CoordSystem, bd09_to_epsg3857, bd09_to_gcj02, bd09_to_wgs84, epsg3857_to_bd09,
+    epsg3857_to_gcj02, epsg3857_to_wgs84, gcj02_to_bd09, gcj02_to_epsg3857, gcj02_to_wgs84,
+    wgs84_to_bd09, wgs84_to_epsg3857, wgs84_to_gcj02,
+};
+
+/// 定义经纬度坐标点类型
+/// Define a longitude/latitude point type
+macro_rules! geographic_point {
+    ($(#[$meta:meta])* $name:ident, $system:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Default)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct $name {
+            /// 经度 Longitude
+            pub lon: f64,
+            /// 纬度 Latitude
+            pub lat: f64,
+        }
+
+        impl $name {
+            /// 坐标点所属坐标系 Coordinate system of the point
+            pub const SYSTEM: CoordSystem = $system;
+
+            /// 创建坐标点 Create a point
+            pub const fn new(lon: f64, lat: f64) -> Self {
+                Self { lon, lat }
+            }
+        }
+
+        impl From<(f64, f64)> for $name {
+            fn from((lon, lat): (f64, f64)) -> Self {
+                Self { lon, lat }
+            }
+        }
+
+        impl From<$name> for (f64, f64) {
+            fn from(point: $name) -> Self {
+                (point.lon, point.lat)
+            }
+        }
+    };
+}
+
+/// 通过已有的转换函数实现坐标点之间的 [`From`]
+/// Implement [`From`] between points using an existing conversion function
+macro_rules! impl_point_conversion {
+    ($from:ident => $to:ident, $convert:ident) => {
+        impl From<$from> for $to {
+            fn from(point: $from) -> Self {
+                let (a, b): (f64, f64) = point.into();
+                $convert(a, b).into()
+            }
+        }
+    };
+}
+
+geographic_point!(
+    /// WGS84坐标系坐标点 WGS84 Coordinate System point
+    Wgs84Point,
+    CoordSystem::Wgs84
+);
+
+geographic_point!(
+    /// GCJ02火星坐标系坐标点 GCJ02 Mars Coordinate System point
+    Gcj02Point,
+    CoordSystem::Gcj02
+);
+
+geographic_point!(
+    /// BD09百度坐标系坐标点 BD09 Baidu Coordinate System point
+    Bd09Point,
+    CoordSystem::Bd09
+);
+
+/// EPSG:3857 Web墨卡托投影坐标点 (米)
+/// EPSG:3857 Web Mercator point in meters
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WebMercatorPoint {
+    /// X坐标 (米) X coordinate in meters
+    pub x: f64,
+    /// Y坐标 (米) Y coordinate in meters
+    pub y: f64,
+}
+
+impl WebMercatorPoint {
+    /// 坐标点所属坐标系 Coordinate system of the point
+    pub const SYSTEM: CoordSystem = CoordSystem::Epsg3857;
+
+    /// 创建坐标点 Create a point
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<(f64, f64)> for WebMercatorPoint {
+    fn from((x, y): (f64, f64)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<WebMercatorPoint> for (f64, f64) {
+    fn from(point: WebMercatorPoint) -> Self {
+        (point.x, point.y)
+    }
+}
+
+/// 带坐标系标记的坐标
+/// Coordinates tagged with their coordinate system
+///
+/// 用于坐标系只在运行时才知道的场景，例如持久化到JSON时把坐标系与数值一起保存。
+/// 坐标系为EPSG:3857时 `lon`、`lat` 分别为以米为单位的X、Y坐标。
+///
+/// Meant for cases where the system is only known at runtime, such as persisting the system next
+/// to the numbers in JSON. For EPSG:3857, `lon` and `lat` hold the X and Y coordinates in meters.
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{CoordSystem, TaggedCoord};
+///
+/// let bd = TaggedCoord::new(CoordSystem::Bd09, 116.404, 39.915);
+/// let wgs = bd.convert_to(CoordSystem::Wgs84);
+/// assert_eq!(wgs.system, CoordSystem::Wgs84);
+/// assert_eq!(wgs.coords(), coordtransform::bd09_to_wgs84(116.404, 39.915));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaggedCoord {
+    /// 坐标系 Coordinate system
+    pub system: CoordSystem,
+    /// 经度或X坐标 Longitude or X coordinate
+    pub lon: f64,
+    /// 纬度或Y坐标 Latitude or Y coordinate
+    pub lat: f64,
+}
+
+impl TaggedCoord {
+    /// 创建带坐标系标记的坐标 Create tagged coordinates
+    pub const fn new(system: CoordSystem, lon: f64, lat: f64) -> Self {
+        Self { system, lon, lat }
+    }
+
+    /// 返回 (经度, 纬度) 元组 Returns the (longitude, latitude) tuple
+    pub const fn coords(&self) -> (f64, f64) {
+        (self.lon, self.lat)
+    }
+
+    /// 转换到目标坐标系
+    /// Convert to the target coordinate system
+    ///
+    /// # Parameters 参数
+    ///
+    /// * `to` - 目标坐标系 Target coordinate system
+    pub fn convert_to(self, to: CoordSystem) -> Self {
+        let (lon, lat) = crate::convert(self.system, to, self.lon, self.lat);
+        Self::new(to, lon, lat)
+    }
+}
+
+/// 由类型安全坐标点生成带坐标系标记的坐标
+/// Build tagged coordinates from a type-safe point
+macro_rules! impl_tagged_from {
+    ($($point:ident),*) => {
+        $(
+            impl From<$point> for TaggedCoord {
+                fn from(point: $point) -> Self {
+                    let (lon, lat) = point.into();
+                    Self::new($point::SYSTEM, lon, lat)
+                }
+            }
+        )*
+    };
+}
+
+impl_tagged_from!(Wgs84Point, Gcj02Point, Bd09Point, WebMercatorPoint);
+
+impl_point_conversion!(Wgs84Point => Gcj02Point, wgs84_to_gcj02);
+impl_point_conversion!(Wgs84Point => Bd09Point, wgs84_to_bd09);
+impl_point_conversion!(Wgs84Point => WebMercatorPoint, wgs84_to_epsg3857);
+impl_point_conversion!(Gcj02Point => Wgs84Point, gcj02_to_wgs84);
+impl_point_conversion!(Gcj02Point => Bd09Point, gcj02_to_bd09);
+impl_point_conversion!(Gcj02Point => WebMercatorPoint, gcj02_to_epsg3857);
+impl_point_conversion!(Bd09Point => Wgs84Point, bd09_to_wgs84);
+impl_point_conversion!(Bd09Point => Gcj02Point, bd09_to_gcj02);
+impl_point_conversion!(Bd09Point => WebMercatorPoint, bd09_to_epsg3857);
+impl_point_conversion!(WebMercatorPoint => Wgs84Point, epsg3857_to_wgs84);
+impl_point_conversion!(WebMercatorPoint => Gcj02Point, epsg3857_to_gcj02);
+impl_point_conversion!(WebMercatorPoint => Bd09Point, epsg3857_to_bd09);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_conversions_match_functions() {
+        let wgs = Wgs84Point::new(116.404, 39.915);
+
+        let gcj = Gcj02Point::from(wgs);
+        assert_eq!(<(f64, f64)>::from(gcj), wgs84_to_gcj02(116.404, 39.915));
+
+        let bd = Bd09Point::from(wgs);
+        assert_eq!(<(f64, f64)>::from(bd), wgs84_to_bd09(116.404, 39.915));
+
+        let mercator = WebMercatorPoint::from(wgs);
+        assert_eq!((mercator.x, mercator.y), wgs84_to_epsg3857(116.404, 39.915));
+
+        let back: Wgs84Point = bd.into();
+        assert_eq!((back.lon, back.lat), bd09_to_wgs84(bd.lon, bd.lat));
+
+        let bd_from_mercator: Bd09Point = mercator.into();
+        assert_eq!(
+            (bd_from_mercator.lon, bd_from_mercator.lat),
+            epsg3857_to_bd09(mercator.x, mercator.y)
+        );
+    }
+
+    #[test]
+    fn test_point_tuple_conversions() {
+        let point = Gcj02Point::from((116.404, 39.915));
+        assert_eq!(point, Gcj02Point::new(116.404, 39.915));
+        let (lon, lat) = point.into();
+        assert_eq!((lon, lat), (116.404, 39.915));
+
+        assert_eq!(Wgs84Point::SYSTEM, CoordSystem::Wgs84);
+        assert_eq!(WebMercatorPoint::SYSTEM, CoordSystem::Epsg3857);
+    }
+
+    #[test]
+    fn test_tagged_coord() {
+        let tagged = TaggedCoord::from(Gcj02Point::new(116.404, 39.915));
+        assert_eq!(tagged.system, CoordSystem::Gcj02);
+
+        let mercator = tagged.convert_to(CoordSystem::Epsg3857);
+        assert_eq!(mercator.coords(), gcj02_to_epsg3857(116.404, 39.915));
+        assert_eq!(mercator.convert_to(CoordSystem::Epsg3857), mercator);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let tagged = TaggedCoord::new(CoordSystem::Bd09, 116.404, 39.915);
+        let json = serde_json::to_string(&tagged).unwrap();
+        assert_eq!(json, r#"{"system":"BD09","lon":116.404,"lat":39.915}"#);
+        assert_eq!(serde_json::from_str::<TaggedCoord>(&json).unwrap(), tagged);
+
+        let alias = r#"{"system":"epsg:4326","lon":1.0,"lat":2.0}"#;
+        let parsed: TaggedCoord = serde_json::from_str(alias).unwrap();
+        assert_eq!(parsed.system, CoordSystem::Wgs84);
+        assert!(serde_json::from_str::<TaggedCoord>(r#"{"system":"utm","lon":1,"lat":2}"#).is_err());
+
+        let point = WebMercatorPoint::new(1.5, 2.5);
+        let json = serde_json::to_string(&point).unwrap();
+        assert_eq!(json, r#"{"x":1.5,"y":2.5}"#);
+        assert_eq!(serde_json::from_str::<WebMercatorPoint>(&json).unwrap(), point);
+    }
+}
@@ -0,0 +1,437 @@
+//! 高精度逆变换
+//! High-precision inverse transformations
+//!
+//! [`gcj02_to_wgs84`](crate::gcj02_to_wgs84) 仅用一次 `delta` 计算近似求逆，残差可达米级；
+//! [`bd09_to_gcj02`](crate::bd09_to_gcj02) 也只是 [`gcj02_to_bd09`](crate::gcj02_to_bd09) 的闭式近似逆。
+//! 本模块通过不动点迭代精确求解正向变换的逆，使往返转换误差小于亚毫米级。
+//!
+//! [`gcj02_to_wgs84`](crate::gcj02_to_wgs84) approximates the inverse with a single `delta`
+//! evaluation, which leaves meter-level residuals, and [`bd09_to_gcj02`](crate::bd09_to_gcj02) is
+//! only a closed-form approximation of the inverse of [`gcj02_to_bd09`](crate::gcj02_to_bd09).
+//! This module solves the inverse of the forward transformations by fixed-point iteration, so round
+//! trips are accurate to well below a millimeter.
+
+use crate::{bd09_to_gcj02, delta, gcj02_to_bd09, is_out_of_china, wgs84_to_epsg3857};
+
+/// 迭代求逆的参数
+/// Options for the iterative inverse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseOptions {
+    /// 收敛阈值 (度)，正向变换结果与输入之差小于该值时停止迭代
+    /// Convergence tolerance in degrees; iteration stops once the forward transformation
+    /// reproduces the input within this value
+    pub tolerance: f64,
+    /// 最大迭代次数 Maximum number of iterations
+    pub max_iterations: u32,
+}
+
+impl InverseOptions {
+    /// 默认收敛阈值 (度)，约 0.1 微米
+    /// Default tolerance in degrees, about 0.1 micrometers
+    pub const DEFAULT_TOLERANCE: f64 = 1e-12;
+
+    /// 默认最大迭代次数
+    /// Default maximum number of iterations
+    pub const DEFAULT_MAX_ITERATIONS: u32 = 10;
+
+    /// 创建迭代参数
+    /// Create iteration options
+    pub const fn new(tolerance: f64, max_iterations: u32) -> Self {
+        Self {
+            tolerance,
+            max_iterations,
+        }
+    }
+}
+
+impl Default for InverseOptions {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TOLERANCE, Self::DEFAULT_MAX_ITERATIONS)
+    }
+}
+
+/// 迭代求逆的结果
+/// Result of an iterative inverse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseResult {
+    /// 经度 Longitude
+    pub lon: f64,
+    /// 纬度 Latitude
+    pub lat: f64,
+    /// 达到的残差 (度)，即正向变换结果与输入的最大分量差
+    /// Achieved residual in degrees: the largest component difference between the forward
+    /// transformation of the result and the input
+    pub residual: f64,
+    /// 实际迭代次数 Number of iterations performed
+    pub iterations: u32,
+    /// 残差是否达到收敛阈值 Whether the residual reached the tolerance
+    pub converged: bool,
+}
+
+impl InverseResult {
+    /// 返回 (经度, 纬度) 元组 Returns the (longitude, latitude) tuple
+    pub fn coords(&self) -> (f64, f64) {
+        (self.lon, self.lat)
+    }
+}
+
+/// gcj02火星坐标系 -> WGS84坐标系 (高精度)
+/// GCJ02 Coordinate System -> WGS84 Coordinate System (high precision)
+///
+/// 使用默认参数迭代求解 [`wgs84_to_gcj02`](crate::wgs84_to_gcj02) 的逆。
+/// Iteratively inverts [`wgs84_to_gcj02`](crate::wgs84_to_gcj02) using the default options.
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的 (经度, 纬度) 元组 Returns a tuple of (longitude, latitude) after conversion
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{gcj02_to_wgs84_precise, wgs84_to_gcj02};
+///
+/// let (lon, lat) = wgs84_to_gcj02(116.404, 39.915);
+/// let (lon, lat) = gcj02_to_wgs84_precise(lon, lat);
+/// assert!((lon - 116.404).abs() < 1e-9);
+/// assert!((lat - 39.915).abs() < 1e-9);
+/// ```
+pub fn gcj02_to_wgs84_precise(lon: f64, lat: f64) -> (f64, f64) {
+    gcj02_to_wgs84_iterative(lon, lat, InverseOptions::default()).coords()
+}
+
+/// gcj02火星坐标系 -> WGS84坐标系 (可配置的迭代求逆)
+/// GCJ02 Coordinate System -> WGS84 Coordinate System (configurable iterative inverse)
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+/// * `options` - 收敛阈值与最大迭代次数 Tolerance and maximum iteration count
+///
+/// # Return Value 返回值
+///
+/// 返回包含坐标、残差和迭代次数的 [`InverseResult`]
+/// Returns an [`InverseResult`] with the coordinates, residual and iteration count
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{gcj02_to_wgs84_iterative, InverseOptions};
+///
+/// let result = gcj02_to_wgs84_iterative(116.404, 39.915, InverseOptions::new(1e-10, 5));
+/// assert!(result.converged);
+/// assert!(result.residual <= 1e-10);
+/// ```
+pub fn gcj02_to_wgs84_iterative(lon: f64, lat: f64, options: InverseOptions) -> InverseResult {
+    if is_out_of_china(lon, lat) {
+        return InverseResult {
+            lon,
+            lat,
+            residual: 0.0,
+            iterations: 0,
+            converged: true,
+        };
+    }
+
+    let (mg_lon, mg_lat) = delta(lon, lat);
+    let guess = (lon * 2.0 - mg_lon, lat * 2.0 - mg_lat);
+
+    solve(lon, lat, guess, delta, options)
+}
+
+/// 百度坐标系 -> 火星坐标系 (高精度)
+/// Baidu Coordinate System -> Mars Coordinate System (high precision)
+///
+/// 使用默认参数迭代求解 [`gcj02_to_bd09`](crate::gcj02_to_bd09) 的逆。
+/// Iteratively inverts [`gcj02_to_bd09`](crate::gcj02_to_bd09) using the default options.
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的 (经度, 纬度) 元组 Returns a tuple of (longitude, latitude) after conversion
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{bd09_to_gcj02_precise, gcj02_to_bd09};
+///
+/// let (lon, lat) = gcj02_to_bd09(116.404, 39.915);
+/// let (lon, lat) = bd09_to_gcj02_precise(lon, lat);
+/// assert!((lon - 116.404).abs() < 1e-9);
+/// assert!((lat - 39.915).abs() < 1e-9);
+/// ```
+pub fn bd09_to_gcj02_precise(lon: f64, lat: f64) -> (f64, f64) {
+    bd09_to_gcj02_iterative(lon, lat, InverseOptions::default()).coords()
+}
+
+/// 百度坐标系 -> 火星坐标系 (可配置的迭代求逆)
+/// Baidu Coordinate System -> Mars Coordinate System (configurable iterative inverse)
+///
+/// 以 [`bd09_to_gcj02`](crate::bd09_to_gcj02) 的结果为初值迭代。
+/// Iteration starts from the result of [`bd09_to_gcj02`](crate::bd09_to_gcj02).
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+/// * `options` - 收敛阈值与最大迭代次数 Tolerance and maximum iteration count
+///
+/// # Return Value 返回值
+///
+/// 返回包含坐标、残差和迭代次数的 [`InverseResult`]
+/// Returns an [`InverseResult`] with the coordinates, residual and iteration count
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{bd09_to_gcj02_iterative, InverseOptions};
+///
+/// let result = bd09_to_gcj02_iterative(116.404, 39.915, InverseOptions::default());
+/// assert!(result.converged);
+/// ```
+pub fn bd09_to_gcj02_iterative(lon: f64, lat: f64, options: InverseOptions) -> InverseResult {
+    let guess = bd09_to_gcj02(lon, lat);
+    solve(lon, lat, guess, gcj02_to_bd09, options)
+}
+
+/// 百度坐标系 -> WGS84坐标系 (高精度)
+/// Baidu Coordinate System -> WGS84 Coordinate System (high precision)
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的 (经度, 纬度) 元组 Returns a tuple of (longitude, latitude) after conversion
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{bd09_to_wgs84_precise, wgs84_to_bd09};
+///
+/// let (lon, lat) = wgs84_to_bd09(116.404, 39.915);
+/// let (lon, lat) = bd09_to_wgs84_precise(lon, lat);
+/// assert!((lon - 116.404).abs() < 1e-9);
+/// assert!((lat - 39.915).abs() < 1e-9);
+/// ```
+pub fn bd09_to_wgs84_precise(lon: f64, lat: f64) -> (f64, f64) {
+    let (lon, lat) = bd09_to_gcj02_precise(lon, lat);
+    gcj02_to_wgs84_precise(lon, lat)
+}
+
+/// BD09坐标系 -> EPSG:3857坐标系 (高精度)
+/// BD09 Coordinate System -> EPSG:3857 Coordinate System (high precision)
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude (度 degrees)
+/// * `lat` - 纬度 Latitude (度 degrees)
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的 (X, Y) 元组 (米) Returns a tuple of (X, Y) in meters after conversion
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::bd09_to_epsg3857_precise;
+///
+/// let (x, y) = bd09_to_epsg3857_precise(116.404, 39.915);
+/// ```
+pub fn bd09_to_epsg3857_precise(lon: f64, lat: f64) -> (f64, f64) {
+    let (wgs_lon, wgs_lat) = bd09_to_wgs84_precise(lon, lat);
+    wgs84_to_epsg3857(wgs_lon, wgs_lat)
+}
+
+/// GCJ02坐标系 -> EPSG:3857坐标系 (高精度)
+/// GCJ02 Coordinate System -> EPSG:3857 Coordinate System (high precision)
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude (度 degrees)
+/// * `lat` - 纬度 Latitude (度 degrees)
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的 (X, Y) 元组 (米) Returns a tuple of (X, Y) in meters after conversion
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::gcj02_to_epsg3857_precise;
+///
+/// let (x, y) = gcj02_to_epsg3857_precise(116.404, 39.915);
+/// ```
+pub fn gcj02_to_epsg3857_precise(lon: f64, lat: f64) -> (f64, f64) {
+    let (wgs_lon, wgs_lat) = gcj02_to_wgs84_precise(lon, lat);
+    wgs84_to_epsg3857(wgs_lon, wgs_lat)
+}
+
+/// 不动点迭代求解 `forward(x) = target`
+/// Solve `forward(x) = target` by fixed-point iteration
+///
+/// 正向变换均为恒等映射加上一个缓慢变化的小偏移，因此 `x -= forward(x) - target` 收敛很快。
+/// The forward transformations are the identity plus a small, slowly varying offset, so
+/// `x -= forward(x) - target` converges quickly.
+fn solve<F>(
+    lon: f64,
+    lat: f64,
+    guess: (f64, f64),
+    forward: F,
+    options: InverseOptions,
+) -> InverseResult
+where
+    F: Fn(f64, f64) -> (f64, f64),
+{
+    let (mut x, mut y) = guess;
+    let mut iterations = 0;
+
+    loop {
+        let (f_lon, f_lat) = forward(x, y);
+        let d_lon = f_lon - lon;
+        let d_lat = f_lat - lat;
+        let residual = d_lon.abs().max(d_lat.abs());
+
+        let converged = residual <= options.tolerance;
+        if converged || iterations >= options.max_iterations {
+            return InverseResult {
+                lon: x,
+                lat: y,
+                residual,
+                iterations,
+                converged,
+            };
+        }
+
+        x -= d_lon;
+        y -= d_lat;
+        iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        epsg3857_to_bd09, epsg3857_to_gcj02, gcj02_to_wgs84, wgs84_to_bd09, wgs84_to_gcj02,
+    };
+
+    const POINTS: [(f64, f64); 4] = [
+        (116.404, 39.915),
+        (121.4737, 31.2304),
+        (87.6168, 43.8256),
+        (108.9398, 18.2528),
+    ];
+
+    #[test]
+    fn test_gcj02_to_wgs84_precise_round_trip() {
+        for &(lon, lat) in POINTS.iter() {
+            let (g_lon, g_lat) = wgs84_to_gcj02(lon, lat);
+            let (w_lon, w_lat) = gcj02_to_wgs84_precise(g_lon, g_lat);
+            // 1e-9 度约 0.1 毫米 1e-9 degrees is about 0.1 millimeters
+            assert!((w_lon - lon).abs() < 1e-9);
+            assert!((w_lat - lat).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_gcj02_to_wgs84_precise_beats_approximation() {
+        let (g_lon, g_lat) = wgs84_to_gcj02(116.404, 39.915);
+        let (a_lon, a_lat) = gcj02_to_wgs84(g_lon, g_lat);
+        let (p_lon, p_lat) = gcj02_to_wgs84_precise(g_lon, g_lat);
+        let approx_error = (a_lon - 116.404).abs().max((a_lat - 39.915).abs());
+        let precise_error = (p_lon - 116.404).abs().max((p_lat - 39.915).abs());
+        assert!(precise_error < approx_error);
+    }
+
+    #[test]
+    fn test_gcj02_to_wgs84_iterative_reports_residual() {
+        let result = gcj02_to_wgs84_iterative(116.404, 39.915, InverseOptions::default());
+        assert!(result.converged);
+        assert!(result.residual <= InverseOptions::DEFAULT_TOLERANCE);
+        assert!(result.iterations > 0);
+
+        let (f_lon, f_lat) = wgs84_to_gcj02(result.lon, result.lat);
+        assert!((f_lon - 116.404).abs() <= result.residual + 1e-15);
+        assert!((f_lat - 39.915).abs() <= result.residual + 1e-15);
+    }
+
+    #[test]
+    fn test_gcj02_to_wgs84_iterative_not_converged() {
+        let result = gcj02_to_wgs84_iterative(116.404, 39.915, InverseOptions::new(1e-15, 0));
+        assert!(!result.converged);
+        assert_eq!(result.iterations, 0);
+        assert_eq!(result.coords(), gcj02_to_wgs84(116.404, 39.915));
+    }
+
+    #[test]
+    fn test_gcj02_to_wgs84_iterative_out_of_china() {
+        let result = gcj02_to_wgs84_iterative(0.0, 0.0, InverseOptions::default());
+        assert_eq!(result.coords(), (0.0, 0.0));
+        assert!(result.converged);
+        assert_eq!(result.iterations, 0);
+    }
+
+    #[test]
+    fn test_bd09_to_gcj02_precise_round_trip() {
+        for &(lon, lat) in POINTS.iter() {
+            let (b_lon, b_lat) = gcj02_to_bd09(lon, lat);
+            let (g_lon, g_lat) = bd09_to_gcj02_precise(b_lon, b_lat);
+            assert!((g_lon - lon).abs() < 1e-9);
+            assert!((g_lat - lat).abs() < 1e-9);
+
+            // 再次正向转换应复现输入 Re-encoding reproduces the input
+            let (r_lon, r_lat) = gcj02_to_bd09(g_lon, g_lat);
+            assert!((r_lon - b_lon).abs() < 1e-11);
+            assert!((r_lat - b_lat).abs() < 1e-11);
+        }
+    }
+
+    #[test]
+    fn test_bd09_to_gcj02_iterative_reports_residual() {
+        let result = bd09_to_gcj02_iterative(116.404, 39.915, InverseOptions::default());
+        assert!(result.converged);
+        assert!(result.residual <= InverseOptions::DEFAULT_TOLERANCE);
+
+        let result = bd09_to_gcj02_iterative(116.404, 39.915, InverseOptions::new(0.0, 0));
+        assert!(!result.converged);
+        assert_eq!(result.coords(), bd09_to_gcj02(116.404, 39.915));
+    }
+
+    #[test]
+    fn test_bd09_to_wgs84_precise_round_trip() {
+        for &(lon, lat) in POINTS.iter() {
+            let (b_lon, b_lat) = wgs84_to_bd09(lon, lat);
+            let (w_lon, w_lat) = bd09_to_wgs84_precise(b_lon, b_lat);
+            assert!((w_lon - lon).abs() < 1e-9);
+            assert!((w_lat - lat).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_bd09_to_epsg3857_precise_round_trip() {
+        let (x, y) = bd09_to_epsg3857_precise(116.404, 39.915);
+        let (lon, lat) = epsg3857_to_bd09(x, y);
+        assert!((lon - 116.404).abs() < 1e-9);
+        assert!((lat - 39.915).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gcj02_to_epsg3857_precise_round_trip() {
+        let (x, y) = gcj02_to_epsg3857_precise(116.404, 39.915);
+        let (lon, lat) = epsg3857_to_gcj02(x, y);
+        assert!((lon - 116.404).abs() < 1e-9);
+        assert!((lat - 39.915).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,316 @@
+//! SIMD加速的批量坐标转换 (需要 `simd` 特性)
+//! SIMD-accelerated batch coordinate conversion (requires the `simd` feature)
+//!
+//! 与 [`batch`](crate::batch) 模块的接口相同，但 `transform`、`delta` 及BD09旋转按4个点一组
+//! 同时计算。基于 [`wide`] 实现，在不支持SIMD的目标上自动退化为标量运算，
+//! 不足一组的剩余点使用标量函数计算。
+//!
+//! Same interface as the [`batch`](crate::batch) module, but `transform`, `delta` and the BD09
+//! rotation are evaluated on lanes of four points at once. Built on [`wide`], which falls back to
+//! scalar code on targets without SIMD; trailing points that do not fill a lane group use the scalar
+//! functions.
+//!
+//! 在x86_64上默认只启用SSE2，使用 `RUSTFLAGS="-C target-cpu=native"` 启用AVX2可获得最大加速。
+//! On x86_64 only SSE2 is enabled by default; build with `RUSTFLAGS="-C target-cpu=native"` to use
+//! AVX2 for the largest speedup.
+//!
+//! # 精度 Accuracy
+//!
+//! SIMD三角函数与标准库的实现不同，结果与标量函数的差异不超过 [`TOLERANCE`] 度 (约0.01毫米)。
+//! The SIMD trigonometric functions differ from the standard library ones; results stay within
+//! [`TOLERANCE`] degrees (about 0.01 millimeters) of the scalar functions.
+//!
+//! ```rust
+//! use coordtransform::simd;
+//!
+//! let input = vec![(116.404, 39.915); 10];
+//! let mut output = vec![(0.0, 0.0); 10];
+//! simd::wgs84_to_gcj02(&input, &mut output);
+//!
+//! let (lon, lat) = coordtransform::wgs84_to_gcj02(116.404, 39.915);
+//! assert!((output[9].0 - lon).abs() < simd::TOLERANCE);
+//! assert!((output[9].1 - lat).abs() < simd::TOLERANCE);
+//! ```
+
+use std::f64::consts::PI;
+
+use wide::f64x4;
+
+use crate::boundary::is_shifted;
+use crate::{AXIS, OFFSET, X_PI};
+
+/// 每组同时计算的点数 Number of points evaluated together
+pub const LANES: usize = 4;
+
+/// 与标量函数结果的最大差异 (度)
+/// Maximum difference from the scalar functions, in degrees
+pub const TOLERANCE: f64 = 1e-10;
+
+/// 生成一对SIMD批量转换函数
+/// Generate a pair of SIMD batch conversion functions
+macro_rules! simd_conversion {
+    ($name:ident, $in_place:ident, $lanes:ident, $title:literal) => {
+        #[doc = concat!($title, "，SIMD批量转换到输出切片")]
+        #[doc = ""]
+        #[doc = concat!("SIMD batch version of [`", stringify!($name), "`](crate::", stringify!($name), ") writing into an output slice.")]
+        #[doc = ""]
+        #[doc = "# Panics"]
+        #[doc = ""]
+        #[doc = "输入与输出长度不同时 panic Panics if `input` and `output` differ in length"]
+        pub fn $name(input: &[(f64, f64)], output: &mut [(f64, f64)]) {
+            assert_eq!(
+                input.len(),
+                output.len(),
+                "input and output slices must have the same length"
+            );
+            let mut chunks = input.chunks_exact(LANES);
+            let mut out_chunks = output.chunks_exact_mut(LANES);
+            for (chunk, out) in (&mut chunks).zip(&mut out_chunks) {
+                let (lon, lat) = load(chunk);
+                let (lon, lat) = $lanes(lon, lat);
+                store(out, lon, lat);
+            }
+            for (out, &(lon, lat)) in out_chunks.into_remainder().iter_mut().zip(chunks.remainder()) {
+                *out = crate::$name(lon, lat);
+            }
+        }
+
+        #[doc = concat!($title, "，SIMD原地批量转换")]
+        #[doc = ""]
+        #[doc = concat!("SIMD batch version of [`", stringify!($name), "`](crate::", stringify!($name), ") converting in place.")]
+        pub fn $in_place(points: &mut [(f64, f64)]) {
+            let mut chunks = points.chunks_exact_mut(LANES);
+            for chunk in &mut chunks {
+                let (lon, lat) = load(chunk);
+                let (lon, lat) = $lanes(lon, lat);
+                store(chunk, lon, lat);
+            }
+            for point in chunks.into_remainder() {
+                *point = crate::$name(point.0, point.1);
+            }
+        }
+    };
+}
+
+simd_conversion!(
+    bd09_to_gcj02,
+    bd09_to_gcj02_in_place,
+    bd09_to_gcj02_x4,
+    "百度坐标系 -> 火星坐标系"
+);
+simd_conversion!(
+    gcj02_to_bd09,
+    gcj02_to_bd09_in_place,
+    gcj02_to_bd09_x4,
+    "火星坐标系 -> 百度坐标系"
+);
+simd_conversion!(
+    wgs84_to_gcj02,
+    wgs84_to_gcj02_in_place,
+    wgs84_to_gcj02_x4,
+    "WGS84坐标系 -> 火星坐标系"
+);
+simd_conversion!(
+    gcj02_to_wgs84,
+    gcj02_to_wgs84_in_place,
+    gcj02_to_wgs84_x4,
+    "火星坐标系 -> WGS84坐标系"
+);
+simd_conversion!(
+    bd09_to_wgs84,
+    bd09_to_wgs84_in_place,
+    bd09_to_wgs84_x4,
+    "百度坐标系 -> WGS84坐标系"
+);
+simd_conversion!(
+    wgs84_to_bd09,
+    wgs84_to_bd09_in_place,
+    wgs84_to_bd09_x4,
+    "WGS84坐标系 -> 百度坐标系"
+);
+
+fn load(points: &[(f64, f64)]) -> (f64x4, f64x4) {
+    let lon = f64x4::new([points[0].0, points[1].0, points[2].0, points[3].0]);
+    let lat = f64x4::new([points[0].1, points[1].1, points[2].1, points[3].1]);
+    (lon, lat)
+}
+
+fn store(points: &mut [(f64, f64)], lon: f64x4, lat: f64x4) {
+    let (lon, lat) = (lon.to_array(), lat.to_array());
+    for (i, point) in points.iter_mut().enumerate() {
+        *point = (lon[i], lat[i]);
+    }
+}
+
+/// 按区域策略保留不需要偏移的点 Keep the points that are not shifted under the region policies
+fn select_shifted(lon: f64x4, lat: f64x4, shifted: (f64x4, f64x4)) -> (f64x4, f64x4) {
+    let (lon, lat) = (lon.to_array(), lat.to_array());
+    let (mut s_lon, mut s_lat) = (shifted.0.to_array(), shifted.1.to_array());
+    for i in 0..LANES {
+        if !is_shifted(lon[i], lat[i]) {
+            s_lon[i] = lon[i];
+            s_lat[i] = lat[i];
+        }
+    }
+    (f64x4::new(s_lon), f64x4::new(s_lat))
+}
+
+fn bd09_to_gcj02_x4(lon: f64x4, lat: f64x4) -> (f64x4, f64x4) {
+    let x = lon - 0.0065;
+    let y = lat - 0.006;
+
+    let z = (x * x + y * y).sqrt() - 0.00002 * (y * X_PI).sin();
+    let theta = y.atan2(x) - 0.000003 * (x * X_PI).cos();
+    let (sin, cos) = theta.sin_cos();
+
+    (z * cos, z * sin)
+}
+
+fn gcj02_to_bd09_x4(lon: f64x4, lat: f64x4) -> (f64x4, f64x4) {
+    let z = (lon * lon + lat * lat).sqrt() + 0.00002 * (lat * X_PI).sin();
+    let theta = lat.atan2(lon) + 0.000003 * (lon * X_PI).cos();
+    let (sin, cos) = theta.sin_cos();
+
+    (z * cos + 0.0065, z * sin + 0.006)
+}
+
+fn wgs84_to_gcj02_x4(lon: f64x4, lat: f64x4) -> (f64x4, f64x4) {
+    select_shifted(lon, lat, delta_x4(lon, lat))
+}
+
+fn gcj02_to_wgs84_x4(lon: f64x4, lat: f64x4) -> (f64x4, f64x4) {
+    let (mg_lon, mg_lat) = delta_x4(lon, lat);
+    select_shifted(lon, lat, (lon * 2.0 - mg_lon, lat * 2.0 - mg_lat))
+}
+
+fn bd09_to_wgs84_x4(lon: f64x4, lat: f64x4) -> (f64x4, f64x4) {
+    let (lon, lat) = bd09_to_gcj02_x4(lon, lat);
+    gcj02_to_wgs84_x4(lon, lat)
+}
+
+fn wgs84_to_bd09_x4(lon: f64x4, lat: f64x4) -> (f64x4, f64x4) {
+    let (lon, lat) = wgs84_to_gcj02_x4(lon, lat);
+    gcj02_to_bd09_x4(lon, lat)
+}
+
+/// `delta` 的SIMD版本 SIMD version of `delta`
+fn delta_x4(lon: f64x4, lat: f64x4) -> (f64x4, f64x4) {
+    let (dlat, dlon) = transform_x4(lon - 105.0, lat - 35.0);
+    let radlat = lat / 180.0 * PI;
+    let (sin, cos) = radlat.sin_cos();
+    let magic = 1.0 - OFFSET * sin * sin;
+    let sqrtmagic = magic.sqrt();
+
+    let dlat = (dlat * 180.0) / ((AXIS * (1.0 - OFFSET)) / (magic * sqrtmagic) * PI);
+    let dlon = (dlon * 180.0) / (AXIS / sqrtmagic * cos * PI);
+
+    (lon + dlon, lat + dlat)
+}
+
+/// `transform` 的SIMD版本 SIMD version of `transform`
+///
+/// 用三倍角公式 `sin(3a) = 3sin(a) - 4sin³(a)` 由 `sin(2a)`、`sin(a/3)` 推出 `sin(6a)`、`sin(a)`，
+/// 正弦计算从10次减少到7次。
+/// `sin(6a)` and `sin(a)` are derived from `sin(2a)` and `sin(a/3)` with the triple-angle identity
+/// `sin(3a) = 3sin(a) - 4sin³(a)`, cutting the sine evaluations from ten to seven.
+fn transform_x4(lon: f64x4, lat: f64x4) -> (f64x4, f64x4) {
+    let lonlat = lon * lat;
+    let abs_x = lon.abs().sqrt();
+    let lon_pi = lon * PI;
+    let lat_pi = lat * PI;
+
+    let sin_2lon = (2.0 * lon_pi).sin();
+    let sin_lon_3 = (lon_pi / 3.0).sin();
+    let sin_lat_3 = (lat_pi / 3.0).sin();
+    let d = 20.0 * triple_angle(sin_2lon) + 20.0 * sin_2lon;
+
+    let mut x = d;
+    let mut y = d;
+
+    x += 20.0 * triple_angle(sin_lat_3) + 40.0 * sin_lat_3;
+    y += 20.0 * triple_angle(sin_lon_3) + 40.0 * sin_lon_3;
+    x += 160.0 * (lat_pi / 12.0).sin() + 320.0 * (lat_pi / 30.0).sin();
+    y += 150.0 * (lon_pi / 12.0).sin() + 300.0 * (lon_pi / 30.0).sin();
+
+    x *= 2.0 / 3.0;
+    y *= 2.0 / 3.0;
+
+    x += -100.0 + 2.0 * lon + 3.0 * lat + 0.2 * lat * lat + 0.1 * lonlat + 0.2 * abs_x;
+    y += 300.0 + lon + 2.0 * lat + 0.1 * lon * lon + 0.1 * lonlat + 0.1 * abs_x;
+
+    (x, y)
+}
+
+/// 由 `sin(a)` 计算 `sin(3a)` Compute `sin(3a)` from `sin(a)`
+fn triple_angle(sin: f64x4) -> f64x4 {
+    sin * (3.0 - 4.0 * sin * sin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 覆盖中国及周边的测试点，长度不是 `LANES` 的整数倍以覆盖剩余点
+    /// Test points over China and its surroundings; the length is not a multiple of `LANES` so the
+    /// remainder path is covered
+    fn points() -> Vec<(f64, f64)> {
+        let mut points = Vec::new();
+        for i in 0..61 {
+            for j in 0..47 {
+                points.push((70.0 + i as f64, 5.0 + j as f64));
+            }
+        }
+        points.push((0.0, 0.0));
+        points
+    }
+
+    fn assert_close(actual: &[(f64, f64)], expected: impl Fn(f64, f64) -> (f64, f64)) {
+        for (&(lon, lat), &(a_lon, a_lat)) in points().iter().zip(actual) {
+            let (e_lon, e_lat) = expected(lon, lat);
+            assert!((a_lon - e_lon).abs() < TOLERANCE, "{} {}", lon, lat);
+            assert!((a_lat - e_lat).abs() < TOLERANCE, "{} {}", lon, lat);
+        }
+    }
+
+    #[test]
+    fn test_simd_matches_scalar() {
+        let input = points();
+        let mut output = vec![(0.0, 0.0); input.len()];
+
+        wgs84_to_gcj02(&input, &mut output);
+        assert_close(&output, crate::wgs84_to_gcj02);
+        gcj02_to_wgs84(&input, &mut output);
+        assert_close(&output, crate::gcj02_to_wgs84);
+        bd09_to_gcj02(&input, &mut output);
+        assert_close(&output, crate::bd09_to_gcj02);
+        gcj02_to_bd09(&input, &mut output);
+        assert_close(&output, crate::gcj02_to_bd09);
+        bd09_to_wgs84(&input, &mut output);
+        assert_close(&output, crate::bd09_to_wgs84);
+        wgs84_to_bd09(&input, &mut output);
+        assert_close(&output, crate::wgs84_to_bd09);
+    }
+
+    #[test]
+    fn test_simd_in_place() {
+        let mut points = points();
+        wgs84_to_bd09_in_place(&mut points);
+        assert_close(&points, crate::wgs84_to_bd09);
+    }
+
+    #[test]
+    fn test_simd_out_of_china_unchanged() {
+        let input = [
+            (0.0, 0.0),
+            (126.978, 37.566),
+            (116.404, 39.915),
+            (-74.006, 40.713),
+        ];
+        let mut output = [(1.0, 1.0); 4];
+        wgs84_to_gcj02(&input, &mut output);
+        assert_eq!(output[0], input[0]);
+        assert_eq!(output[1], input[1]);
+        assert_ne!(output[2], input[2]);
+        assert_eq!(output[3], input[3]);
+    }
+}
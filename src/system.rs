@@ -0,0 +1,405 @@
+//! 坐标系枚举与通用转换入口
+//! Coordinate system enum and generic conversion entry point
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{
+    RegionPolicy, bd09_to_epsg3857, bd09_to_gcj02, bd09_to_wgs84, delta, epsg3857_to_bd09,
+    epsg3857_to_gcj02, epsg3857_to_wgs84, gcj02_to_bd09, gcj02_to_epsg3857, gcj02_to_wgs84,
+    undelta, wgs84_to_bd09, wgs84_to_epsg3857, wgs84_to_gcj02,
+};
+
+/// 坐标系 Coordinate system
+///
+/// 可通过 [`FromStr`] 从常见名称解析，例如 `"wgs84"`、`"EPSG:4326"`、`"gcj02"`、`"bd09ll"`、`"EPSG:3857"`，
+/// 不区分大小写。
+/// Can be parsed with [`FromStr`] from common names such as `"wgs84"`, `"EPSG:4326"`, `"gcj02"`,
+/// `"bd09ll"` and `"EPSG:3857"`, case-insensitively.
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::CoordSystem;
+///
+/// let system: CoordSystem = "EPSG:4326".parse().unwrap();
+/// assert_eq!(system, CoordSystem::Wgs84);
+/// assert_eq!(CoordSystem::Bd09.to_string(), "BD09");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CoordSystem {
+    /// WGS84坐标系 (EPSG:4326) WGS84 Coordinate System (EPSG:4326)
+    Wgs84,
+    /// GCJ02火星坐标系 GCJ02 Mars Coordinate System
+    Gcj02,
+    /// BD09百度坐标系 BD09 Baidu Coordinate System
+    Bd09,
+    /// EPSG:3857 Web墨卡托投影坐标系 (米) EPSG:3857 Web Mercator projection (meters)
+    Epsg3857,
+}
+
+impl CoordSystem {
+    /// 全部坐标系 All coordinate systems
+    pub const ALL: [CoordSystem; 4] = [
+        CoordSystem::Wgs84,
+        CoordSystem::Gcj02,
+        CoordSystem::Bd09,
+        CoordSystem::Epsg3857,
+    ];
+
+    /// 是否为以米为单位的投影坐标系
+    /// Whether this is a projected coordinate system measured in meters
+    pub fn is_projected(self) -> bool {
+        matches!(self, CoordSystem::Epsg3857)
+    }
+
+    /// 对应的EPSG空间参考标识 (SRID)，GCJ02与BD09没有官方SRID，返回 `None`
+    /// The matching EPSG spatial reference identifier (SRID); GCJ02 and BD09 have no official SRID
+    /// and return `None`
+    pub fn srid(self) -> Option<i32> {
+        match self {
+            CoordSystem::Wgs84 => Some(4326),
+            CoordSystem::Epsg3857 => Some(3857),
+            CoordSystem::Gcj02 | CoordSystem::Bd09 => None,
+        }
+    }
+}
+
+impl fmt::Display for CoordSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CoordSystem::Wgs84 => "WGS84",
+            CoordSystem::Gcj02 => "GCJ02",
+            CoordSystem::Bd09 => "BD09",
+            CoordSystem::Epsg3857 => "EPSG:3857",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for CoordSystem {
+    type Err = ParseCoordSystemError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        let is_any =
+            |aliases: &[&str]| aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name));
+        let system = if is_any(&["wgs84", "wgs-84", "wgs_84", "epsg:4326", "4326", "gps"]) {
+            CoordSystem::Wgs84
+        } else if is_any(&["gcj02", "gcj-02", "gcj_02", "gcj", "mars"]) {
+            CoordSystem::Gcj02
+        } else if is_any(&["bd09", "bd-09", "bd_09", "bd09ll", "baidu"]) {
+            CoordSystem::Bd09
+        } else if is_any(&[
+            "epsg3857",
+            "epsg:3857",
+            "3857",
+            "epsg:900913",
+            "900913",
+            "webmercator",
+            "web-mercator",
+            "web_mercator",
+            "pseudo-mercator",
+        ]) {
+            CoordSystem::Epsg3857
+        } else {
+            return Err(ParseCoordSystemError);
+        };
+        Ok(system)
+    }
+}
+
+/// 无法识别的坐标系名称
+/// Unrecognised coordinate system name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseCoordSystemError;
+
+impl fmt::Display for ParseCoordSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown coordinate system")
+    }
+}
+
+impl core::error::Error for ParseCoordSystemError {}
+
+/// 序列化为 [`Display`](fmt::Display) 名称，例如 `"EPSG:3857"`
+/// Serialized as its [`Display`](fmt::Display) name, e.g. `"EPSG:3857"`
+#[cfg(feature = "serde")]
+impl serde::Serialize for CoordSystem {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// 反序列化时接受 [`FromStr`] 支持的所有名称
+/// Deserialization accepts every name understood by [`FromStr`]
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CoordSystem {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(|_| {
+            serde::de::Error::custom(format_args!("unknown coordinate system `{}`", name))
+        })
+    }
+}
+
+/// 任意两个坐标系之间的转换
+/// Conversion between any two coordinate systems
+///
+/// 根据源坐标系和目标坐标系选择对应的转换函数，源与目标相同时原样返回。
+/// Routes to the matching conversion function; coordinates are returned unchanged when both
+/// systems are the same.
+///
+/// # Parameters 参数
+///
+/// * `from` - 源坐标系 Source coordinate system
+/// * `to` - 目标坐标系 Target coordinate system
+/// * `lon` - 经度或X坐标 Longitude or X coordinate
+/// * `lat` - 纬度或Y坐标 Latitude or Y coordinate
+///
+/// # Return Value 返回值
+///
+/// 返回转换后的 (经度, 纬度) 或 (X, Y) 元组
+/// Returns a tuple of (longitude, latitude) or (X, Y) after conversion
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{convert, wgs84_to_bd09, CoordSystem};
+///
+/// let (lon, lat) = convert(CoordSystem::Wgs84, CoordSystem::Bd09, 116.404, 39.915);
+/// assert_eq!((lon, lat), wgs84_to_bd09(116.404, 39.915));
+/// ```
+pub fn convert(from: CoordSystem, to: CoordSystem, lon: f64, lat: f64) -> (f64, f64) {
+    use CoordSystem::*;
+
+    match (from, to) {
+        (Wgs84, Wgs84) | (Gcj02, Gcj02) | (Bd09, Bd09) | (Epsg3857, Epsg3857) => (lon, lat),
+        (Wgs84, Gcj02) => wgs84_to_gcj02(lon, lat),
+        (Wgs84, Bd09) => wgs84_to_bd09(lon, lat),
+        (Wgs84, Epsg3857) => wgs84_to_epsg3857(lon, lat),
+        (Gcj02, Wgs84) => gcj02_to_wgs84(lon, lat),
+        (Gcj02, Bd09) => gcj02_to_bd09(lon, lat),
+        (Gcj02, Epsg3857) => gcj02_to_epsg3857(lon, lat),
+        (Bd09, Wgs84) => bd09_to_wgs84(lon, lat),
+        (Bd09, Gcj02) => bd09_to_gcj02(lon, lat),
+        (Bd09, Epsg3857) => bd09_to_epsg3857(lon, lat),
+        (Epsg3857, Wgs84) => epsg3857_to_wgs84(lon, lat),
+        (Epsg3857, Gcj02) => epsg3857_to_gcj02(lon, lat),
+        (Epsg3857, Bd09) => epsg3857_to_bd09(lon, lat),
+    }
+}
+
+/// 按给定区域策略在任意两个坐标系之间转换
+/// Conversion between any two coordinate systems under the given region policy
+///
+/// 与 [`convert`] 相同，但香港、澳门、台湾是否偏移由 `policy` 决定，而不是进程级默认策略，
+/// 因此不受其他调用方 [`set_region_policy`](crate::set_region_policy) 的影响。
+/// Same as [`convert`], but whether Hong Kong, Macau and Taiwan are shifted is decided by `policy`
+/// rather than the process-wide default, so other callers of
+/// [`set_region_policy`](crate::set_region_policy) do not affect it.
+///
+/// # Parameters 参数
+///
+/// * `policy` - 区域偏移策略 Region shift policy
+/// * `from` - 源坐标系 Source coordinate system
+/// * `to` - 目标坐标系 Target coordinate system
+/// * `lon` - 经度或X坐标 Longitude or X coordinate
+/// * `lat` - 纬度或Y坐标 Latitude or Y coordinate
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::{convert_with_policy, CoordSystem, Provider, RegionPolicy};
+///
+/// // 台北在苹果地图上不偏移 Taipei is not shifted on Apple Maps
+/// let policy = RegionPolicy::from(Provider::Apple);
+/// let (lon, lat) = convert_with_policy(&policy, CoordSystem::Wgs84, CoordSystem::Gcj02, 121.5654, 25.0330);
+/// assert_eq!((lon, lat), (121.5654, 25.0330));
+/// ```
+pub fn convert_with_policy(
+    policy: &RegionPolicy,
+    from: CoordSystem,
+    to: CoordSystem,
+    lon: f64,
+    lat: f64,
+) -> (f64, f64) {
+    use CoordSystem::*;
+
+    // 只有WGS84与GCJ02之间的偏移受区域策略影响，其余步骤与 `convert` 相同
+    // Only the offset between WGS84 and GCJ02 depends on the policy; every other step is as in
+    // `convert`
+    let to_gcj02 = |(lon, lat): (f64, f64)| {
+        if policy.is_shifted(lon, lat) {
+            delta(lon, lat)
+        } else {
+            (lon, lat)
+        }
+    };
+    let to_wgs84 = |(lon, lat): (f64, f64)| {
+        if policy.is_shifted(lon, lat) {
+            undelta(lon, lat)
+        } else {
+            (lon, lat)
+        }
+    };
+    let bd09_to_gcj02 = |(lon, lat): (f64, f64)| bd09_to_gcj02(lon, lat);
+    let gcj02_to_bd09 = |(lon, lat): (f64, f64)| gcj02_to_bd09(lon, lat);
+    let epsg3857_to_wgs84 = |(x, y): (f64, f64)| epsg3857_to_wgs84(x, y);
+    let wgs84_to_epsg3857 = |(lon, lat): (f64, f64)| wgs84_to_epsg3857(lon, lat);
+
+    let point = (lon, lat);
+    match (from, to) {
+        (Wgs84, Gcj02) => to_gcj02(point),
+        (Wgs84, Bd09) => gcj02_to_bd09(to_gcj02(point)),
+        (Gcj02, Wgs84) => to_wgs84(point),
+        (Gcj02, Epsg3857) => wgs84_to_epsg3857(to_wgs84(point)),
+        (Bd09, Wgs84) => to_wgs84(bd09_to_gcj02(point)),
+        (Bd09, Epsg3857) => wgs84_to_epsg3857(to_wgs84(bd09_to_gcj02(point))),
+        (Epsg3857, Gcj02) => to_gcj02(epsg3857_to_wgs84(point)),
+        (Epsg3857, Bd09) => gcj02_to_bd09(to_gcj02(epsg3857_to_wgs84(point))),
+        _ => convert(from, to, lon, lat),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aliases() {
+        let cases = [
+            ("wgs84", CoordSystem::Wgs84),
+            ("EPSG:4326", CoordSystem::Wgs84),
+            (" WGS-84 ", CoordSystem::Wgs84),
+            ("gcj02", CoordSystem::Gcj02),
+            ("GCJ-02", CoordSystem::Gcj02),
+            ("bd09ll", CoordSystem::Bd09),
+            ("BD09", CoordSystem::Bd09),
+            ("EPSG:3857", CoordSystem::Epsg3857),
+            ("epsg:900913", CoordSystem::Epsg3857),
+        ];
+        for (name, system) in cases {
+            assert_eq!(name.parse::<CoordSystem>(), Ok(system), "{}", name);
+        }
+        assert_eq!("utm".parse::<CoordSystem>(), Err(ParseCoordSystemError));
+    }
+
+    #[test]
+    fn test_srid() {
+        assert_eq!(CoordSystem::Wgs84.srid(), Some(4326));
+        assert_eq!(CoordSystem::Epsg3857.srid(), Some(3857));
+        assert_eq!(CoordSystem::Gcj02.srid(), None);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for system in CoordSystem::ALL {
+            assert_eq!(system.to_string().parse::<CoordSystem>(), Ok(system));
+        }
+    }
+
+    #[test]
+    fn test_convert_routes_to_pairwise_functions() {
+        let (lon, lat) = (116.404, 39.915);
+        let (x, y) = wgs84_to_epsg3857(lon, lat);
+        use CoordSystem::*;
+
+        assert_eq!(convert(Wgs84, Gcj02, lon, lat), wgs84_to_gcj02(lon, lat));
+        assert_eq!(convert(Wgs84, Bd09, lon, lat), wgs84_to_bd09(lon, lat));
+        assert_eq!(convert(Wgs84, Epsg3857, lon, lat), (x, y));
+        assert_eq!(convert(Gcj02, Wgs84, lon, lat), gcj02_to_wgs84(lon, lat));
+        assert_eq!(convert(Gcj02, Bd09, lon, lat), gcj02_to_bd09(lon, lat));
+        assert_eq!(
+            convert(Gcj02, Epsg3857, lon, lat),
+            gcj02_to_epsg3857(lon, lat)
+        );
+        assert_eq!(convert(Bd09, Wgs84, lon, lat), bd09_to_wgs84(lon, lat));
+        assert_eq!(convert(Bd09, Gcj02, lon, lat), bd09_to_gcj02(lon, lat));
+        assert_eq!(
+            convert(Bd09, Epsg3857, lon, lat),
+            bd09_to_epsg3857(lon, lat)
+        );
+        assert_eq!(convert(Epsg3857, Wgs84, x, y), epsg3857_to_wgs84(x, y));
+        assert_eq!(convert(Epsg3857, Gcj02, x, y), epsg3857_to_gcj02(x, y));
+        assert_eq!(convert(Epsg3857, Bd09, x, y), epsg3857_to_bd09(x, y));
+
+        for system in CoordSystem::ALL {
+            assert_eq!(convert(system, system, lon, lat), (lon, lat));
+        }
+    }
+
+    #[test]
+    fn test_convert_with_policy() {
+        use crate::{Provider, Region, ShiftPolicy};
+
+        // 北京、香港、澳门、台北 Beijing, Hong Kong, Macau and Taipei
+        let points = [
+            (116.404, 39.915),
+            (114.158, 22.285),
+            (113.543, 22.187),
+            (121.5654, 25.033),
+        ];
+        // 默认策略下与 `convert` 一致 Matches `convert` under the default policy
+        for (from, to) in CoordSystem::ALL
+            .into_iter()
+            .flat_map(|from| CoordSystem::ALL.into_iter().map(move |to| (from, to)))
+        {
+            for (lon, lat) in points {
+                let (lon, lat) = if from.is_projected() {
+                    wgs84_to_epsg3857(lon, lat)
+                } else {
+                    (lon, lat)
+                };
+                assert_eq!(
+                    convert_with_policy(&RegionPolicy::SHIFT_ALL, from, to, lon, lat),
+                    convert(from, to, lon, lat),
+                    "{} -> {}",
+                    from,
+                    to
+                );
+            }
+        }
+
+        // 逐次指定的策略不影响默认策略 A per-call policy leaves the default untouched
+        let google = RegionPolicy::from(Provider::Google);
+        let (lon, lat) = points[1];
+        use CoordSystem::*;
+        assert_eq!(
+            convert_with_policy(&google, Wgs84, Gcj02, lon, lat),
+            (lon, lat)
+        );
+        assert_eq!(
+            convert_with_policy(&google, Gcj02, Wgs84, lon, lat),
+            (lon, lat)
+        );
+        assert_eq!(
+            convert_with_policy(&google, Wgs84, Bd09, lon, lat),
+            gcj02_to_bd09(lon, lat)
+        );
+        let (x, y) = wgs84_to_epsg3857(lon, lat);
+        assert_eq!(
+            convert_with_policy(&google, Epsg3857, Gcj02, x, y),
+            epsg3857_to_wgs84(x, y)
+        );
+        assert_ne!(convert(Wgs84, Gcj02, lon, lat), (lon, lat));
+        // 大陆仍然偏移 The mainland is still shifted
+        let (lon, lat) = points[0];
+        assert_eq!(
+            convert_with_policy(&google, Wgs84, Gcj02, lon, lat),
+            wgs84_to_gcj02(lon, lat)
+        );
+
+        let policy = RegionPolicy::SHIFT_ALL.with(Region::Taiwan, ShiftPolicy::NoShift);
+        let (lon, lat) = points[3];
+        assert_eq!(
+            convert_with_policy(&policy, Wgs84, Gcj02, lon, lat),
+            (lon, lat)
+        );
+        assert_ne!(
+            convert_with_policy(&policy, Wgs84, Gcj02, 114.158, 22.285),
+            (114.158, 22.285)
+        );
+    }
+}
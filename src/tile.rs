@@ -0,0 +1,874 @@
+//! 地图瓦片与像素坐标
+//! Map tile and pixel coordinates
+//!
+//! 百度瓦片以百度墨卡托坐标 (BD09MC) 的原点 (赤道与本初子午线交点) 为瓦片原点，x向东、y向北
+//! 递增，在西半球和南半球为负数。级别 `z` 下每像素对应 2^(18 - z) 米，瓦片边长256像素。
+//!
+//! Baidu tiles have their origin at the BD09MC origin (where the equator meets the prime
+//! meridian), with x increasing eastward and y northward, so they are negative in the western
+//! and southern hemispheres. At zoom `z` one pixel covers 2^(18 - z) meters and a tile is 256
+//! pixels wide.
+//!
+//! 瓦片内的像素偏移 ([`TilePixel`]) 以瓦片图片左上角为原点，x向右、y向下，取值范围为 `[0, 256)`，
+//! 可直接用于绘制。级别不能超过 [`MAX_ZOOM`]。
+//! Pixel offsets within a tile ([`TilePixel`]) start at the top-left corner of the tile image,
+//! with x to the right and y downward in `[0, 256)`, so they can be used for drawing directly.
+//! Zoom levels may not exceed [`MAX_ZOOM`].
+//!
+//! XYZ瓦片 (OpenStreetMap、Google等) 基于EPSG:3857，原点在西北角，y向南递增。高德、腾讯瓦片同为
+//! XYZ瓦片，但投影的是GCJ02经纬度，`gcj02_*` 函数用于将WGS84坐标映射到这类瓦片的正确像素上。
+//! 腾讯瓦片地址的行号为TMS行号，见 [`xyz_to_tms`]。
+//!
+//! XYZ tiles (OpenStreetMap, Google and others) are based on EPSG:3857 with the origin in the
+//! north-west and y increasing southward. Amap and Tencent tiles are XYZ tiles as well but project
+//! GCJ02 longitudes and latitudes; the `gcj02_*` functions map WGS84 points onto the right pixels
+//! of such tiles. Tencent tile URLs use TMS rows, see [`xyz_to_tms`].
+//!
+//! ```rust
+//! use coordtransform::tile;
+//!
+//! // 天安门所在的18级百度瓦片 The level-18 Baidu tile containing Tiananmen
+//! let pixel = tile::bd09_to_baidu_tile(116.404, 39.915, 18);
+//! assert_eq!((pixel.tile.x, pixel.tile.y), (50617, 18851));
+//!
+//! let (lon, lat) = tile::baidu_tile_to_bd09(pixel);
+//! assert!((lon - 116.404).abs() < 1e-6 && (lat - 39.915).abs() < 1e-6);
+//!
+//! // 覆盖一个范围的瓦片 Tiles covering a bounding box
+//! let tiles = tile::baidu_tiles_bd09([116.3, 39.85, 116.5, 40.0], 14);
+//! assert!(tiles.contains(tile::bd09_to_baidu_tile(116.404, 39.915, 14).tile));
+//!
+//! // GPS坐标在高德瓦片上的位置 A GPS position on an Amap tile
+//! let pixel = tile::wgs84_to_gcj02_xyz_tile(116.404, 39.915, 16);
+//! println!("{}/{}/{} @ ({:.1}, {:.1})", pixel.tile.z, pixel.tile.x, pixel.tile.y, pixel.x, pixel.y);
+//! ```
+
+use core::f64::consts::PI;
+
+use crate::math::floor;
+use crate::{
+    EARTH_RADIUS, bd09_to_bd09mc, bd09mc_to_bd09, epsg3857_to_wgs84, gcj02_to_wgs84_precise,
+    wgs84_to_epsg3857, wgs84_to_gcj02,
+};
+
+/// 瓦片边长 (像素)
+/// Tile size in pixels
+pub const TILE_SIZE: f64 = 256.0;
+
+/// 支持的最大级别，已远超实际瓦片服务 (约22级)；全局像素坐标为 2^(z+8) 量级，
+/// 限制级别使其远小于 `f64` 可精确表示的 2^53，从而保持瓦片内偏移的亚像素精度
+/// Highest supported zoom level, well beyond real tile services (about 22). Global pixel
+/// coordinates are of order 2^(z+8), and the limit keeps them far below the 2^53 that `f64`
+/// represents exactly, so offsets within a tile keep sub-pixel precision
+pub const MAX_ZOOM: u8 = 30;
+
+/// 百度瓦片每像素为1米的级别
+/// Zoom level at which a Baidu pixel covers one meter
+const BAIDU_BASE_ZOOM: i32 = 18;
+
+/// 瓦片编号
+/// Tile index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tile {
+    /// 列号 Column
+    pub x: i64,
+    /// 行号 Row
+    pub y: i64,
+    /// 级别 Zoom level
+    pub z: u8,
+}
+
+impl Tile {
+    /// 创建瓦片编号
+    /// Create a tile index
+    pub const fn new(x: i64, y: i64, z: u8) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// 瓦片内的像素位置，以瓦片图片左上角为原点，x向右、y向下
+/// Pixel position within a tile, from the top-left corner of the tile image with x to the right
+/// and y downward
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TilePixel {
+    /// 所在瓦片 Containing tile
+    pub tile: Tile,
+    /// 瓦片内x偏移 (像素) x offset in pixels
+    pub x: f64,
+    /// 瓦片内y偏移 (像素) y offset in pixels
+    pub y: f64,
+}
+
+/// 同一级别下的矩形瓦片范围，包含两端
+/// Rectangular range of tiles at one zoom level, inclusive at both ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileRange {
+    /// 级别 Zoom level
+    pub z: u8,
+    /// 最小列号 Minimum column
+    pub min_x: i64,
+    /// 最小行号 Minimum row
+    pub min_y: i64,
+    /// 最大列号 Maximum column
+    pub max_x: i64,
+    /// 最大行号 Maximum row
+    pub max_y: i64,
+}
+
+impl TileRange {
+    /// 范围内的瓦片数，超出 `u64` 时为 `u64::MAX`
+    /// Number of tiles in the range, saturating at `u64::MAX`
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        span(self.min_x, self.max_x).saturating_mul(span(self.min_y, self.max_y))
+    }
+
+    /// 范围是否为空
+    /// Whether the range is empty
+    pub fn is_empty(&self) -> bool {
+        self.min_x > self.max_x || self.min_y > self.max_y
+    }
+
+    /// 是否包含瓦片
+    /// Whether the range contains a tile
+    pub fn contains(&self, tile: Tile) -> bool {
+        tile.z == self.z
+            && (self.min_x..=self.max_x).contains(&tile.x)
+            && (self.min_y..=self.max_y).contains(&tile.y)
+    }
+
+    /// 按行号、再按列号递增的顺序遍历瓦片
+    /// Iterate over the tiles by increasing row, then column
+    pub fn iter(&self) -> TileIter {
+        TileIter {
+            range: *self,
+            next: (!self.is_empty()).then_some((self.min_x, self.min_y)),
+        }
+    }
+}
+
+impl IntoIterator for TileRange {
+    type Item = Tile;
+    type IntoIter = TileIter;
+
+    fn into_iter(self) -> TileIter {
+        self.iter()
+    }
+}
+
+/// [`TileRange`] 的迭代器
+/// Iterator over a [`TileRange`]
+#[derive(Debug, Clone)]
+pub struct TileIter {
+    range: TileRange,
+    /// 下一个瓦片的 (列号, 行号)，遍历结束时为 `None`
+    /// (column, row) of the next tile, `None` once exhausted
+    next: Option<(i64, i64)>,
+}
+
+impl Iterator for TileIter {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Tile> {
+        let (x, y) = self.next?;
+        let range = &self.range;
+        self.next = if x < range.max_x {
+            Some((x + 1, y))
+        } else if y < range.max_y {
+            Some((range.min_x, y + 1))
+        } else {
+            None
+        };
+        Some(Tile::new(x, y, range.z))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let Some((x, y)) = self.next else {
+            return (0, Some(0));
+        };
+        let range = &self.range;
+        let remaining = range
+            .max_y
+            .abs_diff(y)
+            .checked_mul(span(range.min_x, range.max_x))
+            .and_then(|full_rows| full_rows.checked_add(span(x, range.max_x)))
+            .and_then(|remaining| usize::try_from(remaining).ok());
+        match remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl core::iter::FusedIterator for TileIter {}
+
+/// `min..=max` 中的整数个数，超出 `u64` 时为 `u64::MAX`
+/// Number of integers in `min..=max`, saturating at `u64::MAX`
+fn span(min: i64, max: i64) -> u64 {
+    max.abs_diff(min).saturating_add(1)
+}
+
+/// 检查级别不超过 [`MAX_ZOOM`] Check that the zoom level does not exceed [`MAX_ZOOM`]
+fn check_zoom(z: u8) {
+    assert!(
+        z <= MAX_ZOOM,
+        "zoom level {} exceeds the maximum of {}",
+        z,
+        MAX_ZOOM
+    );
+}
+
+/// 精确的2的整数次幂 Exact integer power of two
+fn pow2(exp: i32) -> f64 {
+    f64::from_bits(((exp + 1023) as u64) << 52)
+}
+
+/// 百度瓦片在级别 `z` 下的分辨率 (米/像素)，即 2^(18 - z)
+/// Resolution of Baidu tiles at zoom `z` in meters per pixel, i.e. 2^(18 - z)
+///
+/// # Panics
+///
+/// `z` 超过 [`MAX_ZOOM`] 时 panic，本模块中所有接受级别的百度瓦片函数同样如此
+/// Panics if `z` exceeds [`MAX_ZOOM`], as do all Baidu tile functions in this module taking a zoom
+/// level
+pub fn baidu_resolution(z: u8) -> f64 {
+    check_zoom(z);
+    pow2(BAIDU_BASE_ZOOM - i32::from(z))
+}
+
+/// 百度墨卡托坐标 -> 级别 `z` 下的百度全局像素坐标 (y向北)
+/// Baidu Mercator -> global Baidu pixel coordinates at zoom `z` (y northward)
+pub fn bd09mc_to_baidu_pixel(x: f64, y: f64, z: u8) -> (f64, f64) {
+    let resolution = baidu_resolution(z);
+    (x / resolution, y / resolution)
+}
+
+/// 级别 `z` 下的百度全局像素坐标 (y向北) -> 百度墨卡托坐标
+/// Global Baidu pixel coordinates at zoom `z` (y northward) -> Baidu Mercator
+pub fn baidu_pixel_to_bd09mc(px: f64, py: f64, z: u8) -> (f64, f64) {
+    let resolution = baidu_resolution(z);
+    (px * resolution, py * resolution)
+}
+
+/// 百度墨卡托坐标 -> 百度瓦片及瓦片内像素偏移
+/// Baidu Mercator -> Baidu tile and pixel offset within it
+///
+/// 输入不做校验：NaN得到瓦片 (0, 0) 及NaN偏移，无穷大得到 `i64` 极值编号。
+/// The input is not validated: NaN yields tile (0, 0) with NaN offsets, and infinities yield
+/// `i64` extreme indices.
+///
+/// # Parameters 参数
+///
+/// * `x` - X坐标 X coordinate (米 meters)
+/// * `y` - Y坐标 Y coordinate (米 meters)
+/// * `z` - 级别 Zoom level
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::tile::{bd09mc_to_baidu_tile, Tile};
+///
+/// let pixel = bd09mc_to_baidu_tile(12958175.0, 4825923.77, 18);
+/// assert_eq!(pixel.tile, Tile::new(50617, 18851, 18));
+/// assert!((pixel.x - 223.0).abs() < 1e-6 && (pixel.y - 188.23).abs() < 1e-6);
+/// ```
+pub fn bd09mc_to_baidu_tile(x: f64, y: f64, z: u8) -> TilePixel {
+    let (px, py) = bd09mc_to_baidu_pixel(x, y, z);
+    let tile_x = floor(px / TILE_SIZE);
+    // 向上取整减一，使瓦片南边界上的点属于南侧瓦片，y偏移取值为 [0, 256)
+    // Ceiling minus one, so that points on a tile's south edge belong to the row below and the y
+    // offset stays in [0, 256)
+    let tile_y = -floor(-py / TILE_SIZE) - 1.0;
+    TilePixel {
+        tile: Tile::new(tile_x as i64, tile_y as i64, z),
+        x: px - tile_x * TILE_SIZE,
+        // 瓦片行号向北递增而图片像素向下递增 Rows grow northward but image pixels downward
+        y: (tile_y + 1.0) * TILE_SIZE - py,
+    }
+}
+
+/// 百度瓦片及瓦片内像素偏移 -> 百度墨卡托坐标
+/// Baidu tile and pixel offset within it -> Baidu Mercator
+pub fn baidu_tile_to_bd09mc(pixel: TilePixel) -> (f64, f64) {
+    let px = pixel.tile.x as f64 * TILE_SIZE + pixel.x;
+    let py = (pixel.tile.y as f64 + 1.0) * TILE_SIZE - pixel.y;
+    baidu_pixel_to_bd09mc(px, py, pixel.tile.z)
+}
+
+/// 百度坐标 -> 百度瓦片及瓦片内像素偏移
+/// BD09 -> Baidu tile and pixel offset within it
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+/// * `z` - 级别 Zoom level
+pub fn bd09_to_baidu_tile(lon: f64, lat: f64, z: u8) -> TilePixel {
+    let (x, y) = bd09_to_bd09mc(lon, lat);
+    bd09mc_to_baidu_tile(x, y, z)
+}
+
+/// 百度瓦片及瓦片内像素偏移 -> 百度坐标
+/// Baidu tile and pixel offset within it -> BD09
+pub fn baidu_tile_to_bd09(pixel: TilePixel) -> (f64, f64) {
+    let (x, y) = baidu_tile_to_bd09mc(pixel);
+    bd09mc_to_bd09(x, y)
+}
+
+/// 百度瓦片的范围 `[min_x, min_y, max_x, max_y]` (百度墨卡托米)
+/// Extent `[min_x, min_y, max_x, max_y]` of a Baidu tile in Baidu Mercator meters
+pub fn baidu_tile_bounds_bd09mc(tile: Tile) -> [f64; 4] {
+    let size = TILE_SIZE * baidu_resolution(tile.z);
+    let (x, y) = (tile.x as f64 * size, tile.y as f64 * size);
+    [x, y, x + size, y + size]
+}
+
+/// 覆盖百度墨卡托范围 `[min_x, min_y, max_x, max_y]` 的百度瓦片，包含与范围边界相接的瓦片
+/// Baidu tiles covering the Baidu Mercator box `[min_x, min_y, max_x, max_y]`, including tiles
+/// touching its edges
+pub fn baidu_tiles_bd09mc(bbox: [f64; 4], z: u8) -> TileRange {
+    let [x1, y1, x2, y2] = bbox;
+    let min = bd09mc_to_baidu_tile(x1.min(x2), y1.min(y2), z).tile;
+    let max = bd09mc_to_baidu_tile(x1.max(x2), y1.max(y2), z).tile;
+    TileRange {
+        z,
+        min_x: min.x,
+        min_y: min.y,
+        max_x: max.x,
+        max_y: max.y,
+    }
+}
+
+/// 覆盖百度坐标范围 `[west, south, east, north]` 的百度瓦片
+/// Baidu tiles covering the BD09 box `[west, south, east, north]`
+///
+/// 百度墨卡托的x只取决于经度、y只取决于纬度，因此范围的两个角点即可确定瓦片范围。
+/// BD09MC x depends only on the longitude and y only on the latitude, so the two corners of the
+/// box determine the tile range.
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::tile::baidu_tiles_bd09;
+///
+/// for tile in baidu_tiles_bd09([116.3, 39.85, 116.5, 40.0], 12) {
+///     println!("{}/{}/{}", tile.z, tile.x, tile.y);
+/// }
+/// ```
+pub fn baidu_tiles_bd09(bbox: [f64; 4], z: u8) -> TileRange {
+    let (x1, y1) = bd09_to_bd09mc(bbox[0], bbox[1]);
+    let (x2, y2) = bd09_to_bd09mc(bbox[2], bbox[3]);
+    baidu_tiles_bd09mc([x1, y1, x2, y2], z)
+}
+
+/// Web墨卡托世界范围的一半 (米)
+/// Half the extent of the Web Mercator world in meters
+const HALF_WORLD: f64 = PI * EARTH_RADIUS;
+
+/// 沿范围每条边采样的段数，用于非线性偏移后的覆盖范围
+/// Segments sampled along each edge of a box when covering it after the non-linear offset
+const EDGE_SAMPLES: u32 = 16;
+
+/// XYZ瓦片在级别 `z` 下的分辨率 (米/像素)
+/// Resolution of XYZ tiles at zoom `z` in meters per pixel
+///
+/// # Panics
+///
+/// `z` 超过 [`MAX_ZOOM`] 时 panic，本模块中所有接受级别的XYZ瓦片函数同样如此
+/// Panics if `z` exceeds [`MAX_ZOOM`], as do all XYZ tile functions in this module taking a zoom
+/// level
+pub fn xyz_resolution(z: u8) -> f64 {
+    check_zoom(z);
+    2.0 * HALF_WORLD / TILE_SIZE * pow2(-i32::from(z))
+}
+
+/// EPSG:3857坐标 -> 级别 `z` 下的XYZ全局像素坐标 (原点在西北角，y向南)
+/// EPSG:3857 -> global XYZ pixel coordinates at zoom `z` (origin in the north-west, y southward)
+pub fn epsg3857_to_xyz_pixel(x: f64, y: f64, z: u8) -> (f64, f64) {
+    let resolution = xyz_resolution(z);
+    ((x + HALF_WORLD) / resolution, (HALF_WORLD - y) / resolution)
+}
+
+/// 级别 `z` 下的XYZ全局像素坐标 (原点在西北角，y向南) -> EPSG:3857坐标
+/// Global XYZ pixel coordinates at zoom `z` (origin in the north-west, y southward) -> EPSG:3857
+pub fn xyz_pixel_to_epsg3857(px: f64, py: f64, z: u8) -> (f64, f64) {
+    let resolution = xyz_resolution(z);
+    (px * resolution - HALF_WORLD, HALF_WORLD - py * resolution)
+}
+
+/// EPSG:3857坐标 -> XYZ瓦片及瓦片内像素偏移
+/// EPSG:3857 -> XYZ tile and pixel offset within it
+///
+/// 世界范围之外的点归入边缘瓦片，此时偏移可能超出 `0..256`。输入不做校验：NaN得到瓦片 (0, 0)
+/// 及NaN偏移。
+/// Points outside the world fall into the edge tiles, with offsets possibly outside `0..256`.
+/// The input is not validated: NaN yields tile (0, 0) with NaN offsets.
+///
+/// # Parameters 参数
+///
+/// * `x` - X坐标 X coordinate (米 meters)
+/// * `y` - Y坐标 Y coordinate (米 meters)
+/// * `z` - 级别 Zoom level
+pub fn epsg3857_to_xyz_tile(x: f64, y: f64, z: u8) -> TilePixel {
+    let (px, py) = epsg3857_to_xyz_pixel(x, y, z);
+    let last = pow2(i32::from(z)) - 1.0;
+    let tile_x = floor(px / TILE_SIZE).clamp(0.0, last);
+    let tile_y = floor(py / TILE_SIZE).clamp(0.0, last);
+    TilePixel {
+        tile: Tile::new(tile_x as i64, tile_y as i64, z),
+        x: px - tile_x * TILE_SIZE,
+        y: py - tile_y * TILE_SIZE,
+    }
+}
+
+/// XYZ瓦片及瓦片内像素偏移 -> EPSG:3857坐标
+/// XYZ tile and pixel offset within it -> EPSG:3857
+pub fn xyz_tile_to_epsg3857(pixel: TilePixel) -> (f64, f64) {
+    let px = pixel.tile.x as f64 * TILE_SIZE + pixel.x;
+    let py = pixel.tile.y as f64 * TILE_SIZE + pixel.y;
+    xyz_pixel_to_epsg3857(px, py, pixel.tile.z)
+}
+
+/// WGS84坐标 -> XYZ瓦片及瓦片内像素偏移，用于OpenStreetMap等国际瓦片
+/// WGS84 -> XYZ tile and pixel offset within it, for international tiles such as OpenStreetMap
+///
+/// # Parameters 参数
+///
+/// * `lon` - 经度 Longitude
+/// * `lat` - 纬度 Latitude
+/// * `z` - 级别 Zoom level
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::tile::{wgs84_to_xyz_tile, Tile};
+///
+/// let pixel = wgs84_to_xyz_tile(116.404, 39.915, 10);
+/// assert_eq!(pixel.tile, Tile::new(843, 387, 10));
+/// ```
+pub fn wgs84_to_xyz_tile(lon: f64, lat: f64, z: u8) -> TilePixel {
+    let (x, y) = wgs84_to_epsg3857(lon, lat);
+    epsg3857_to_xyz_tile(x, y, z)
+}
+
+/// XYZ瓦片及瓦片内像素偏移 -> WGS84坐标
+/// XYZ tile and pixel offset within it -> WGS84
+pub fn xyz_tile_to_wgs84(pixel: TilePixel) -> (f64, f64) {
+    let (x, y) = xyz_tile_to_epsg3857(pixel);
+    epsg3857_to_wgs84(x, y)
+}
+
+/// GCJ02坐标 -> 高德、腾讯等GCJ02 XYZ瓦片及瓦片内像素偏移
+/// GCJ02 -> tile and pixel offset on GCJ02 XYZ tiles such as Amap and Tencent
+///
+/// 这类瓦片对GCJ02经纬度直接做Web墨卡托投影，因此与 [`wgs84_to_xyz_tile`] 计算相同，
+/// 只是输入为GCJ02坐标。
+/// These tiles apply the Web Mercator projection directly to GCJ02 longitudes and latitudes, so
+/// the math is that of [`wgs84_to_xyz_tile`] with GCJ02 input.
+pub fn gcj02_to_xyz_tile(lon: f64, lat: f64, z: u8) -> TilePixel {
+    wgs84_to_xyz_tile(lon, lat, z)
+}
+
+/// GCJ02 XYZ瓦片及瓦片内像素偏移 -> GCJ02坐标
+/// Tile and pixel offset on GCJ02 XYZ tiles -> GCJ02
+pub fn xyz_tile_to_gcj02(pixel: TilePixel) -> (f64, f64) {
+    xyz_tile_to_wgs84(pixel)
+}
+
+/// WGS84坐标 -> 高德、腾讯等GCJ02 XYZ瓦片及瓦片内像素偏移
+/// WGS84 -> tile and pixel offset on GCJ02 XYZ tiles such as Amap and Tencent
+///
+/// 先转换为GCJ02坐标，使GPS位置落在国内瓦片的正确像素上。
+/// The point is converted to GCJ02 first so that a GPS position lands on the right pixel of a
+/// Chinese provider tile.
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::tile::{gcj02_xyz_tile_to_wgs84, wgs84_to_gcj02_xyz_tile, wgs84_to_xyz_tile};
+///
+/// let pixel = wgs84_to_gcj02_xyz_tile(116.404, 39.915, 18);
+/// // 与国际瓦片上的位置相差数百像素 Hundreds of pixels away from the international tile position
+/// assert_ne!(pixel.tile, wgs84_to_xyz_tile(116.404, 39.915, 18).tile);
+///
+/// let (lon, lat) = gcj02_xyz_tile_to_wgs84(pixel);
+/// assert!((lon - 116.404).abs() < 1e-6 && (lat - 39.915).abs() < 1e-6);
+/// ```
+pub fn wgs84_to_gcj02_xyz_tile(lon: f64, lat: f64, z: u8) -> TilePixel {
+    let (lon, lat) = wgs84_to_gcj02(lon, lat);
+    gcj02_to_xyz_tile(lon, lat, z)
+}
+
+/// 高德、腾讯等GCJ02 XYZ瓦片及瓦片内像素偏移 -> WGS84坐标，使用高精度逆变换
+/// Tile and pixel offset on GCJ02 XYZ tiles such as Amap and Tencent -> WGS84, using the precise
+/// inverse
+pub fn gcj02_xyz_tile_to_wgs84(pixel: TilePixel) -> (f64, f64) {
+    let (lon, lat) = xyz_tile_to_gcj02(pixel);
+    gcj02_to_wgs84_precise(lon, lat)
+}
+
+/// XYZ瓦片的范围 `[min_x, min_y, max_x, max_y]` (EPSG:3857米)
+/// Extent `[min_x, min_y, max_x, max_y]` of an XYZ tile in EPSG:3857 meters
+pub fn xyz_tile_bounds_epsg3857(tile: Tile) -> [f64; 4] {
+    let (min_x, max_y) =
+        xyz_pixel_to_epsg3857(tile.x as f64 * TILE_SIZE, tile.y as f64 * TILE_SIZE, tile.z);
+    let (max_x, min_y) = xyz_pixel_to_epsg3857(
+        (tile.x + 1) as f64 * TILE_SIZE,
+        (tile.y + 1) as f64 * TILE_SIZE,
+        tile.z,
+    );
+    [min_x, min_y, max_x, max_y]
+}
+
+/// XYZ瓦片的经纬度范围 `[west, south, east, north]`
+/// Longitude/latitude extent `[west, south, east, north]` of an XYZ tile
+///
+/// 对于高德、腾讯等GCJ02瓦片，结果为GCJ02坐标。
+/// For GCJ02 tiles such as Amap and Tencent the result is in GCJ02.
+pub fn xyz_tile_bounds_wgs84(tile: Tile) -> [f64; 4] {
+    let [min_x, min_y, max_x, max_y] = xyz_tile_bounds_epsg3857(tile);
+    let (west, south) = epsg3857_to_wgs84(min_x, min_y);
+    let (east, north) = epsg3857_to_wgs84(max_x, max_y);
+    [west, south, east, north]
+}
+
+/// 覆盖EPSG:3857范围 `[min_x, min_y, max_x, max_y]` 的XYZ瓦片，包含与范围边界相接的瓦片
+/// XYZ tiles covering the EPSG:3857 box `[min_x, min_y, max_x, max_y]`, including tiles touching
+/// its edges
+///
+/// 范围被限制在世界范围内。
+/// The box is clamped to the world.
+pub fn xyz_tiles_epsg3857(bbox: [f64; 4], z: u8) -> TileRange {
+    let [x1, y1, x2, y2] = bbox;
+    // XYZ行号向南递增 XYZ rows grow southward
+    let min = epsg3857_to_xyz_tile(x1.min(x2), y1.max(y2), z).tile;
+    let max = epsg3857_to_xyz_tile(x1.max(x2), y1.min(y2), z).tile;
+    TileRange {
+        z,
+        min_x: min.x,
+        min_y: min.y,
+        max_x: max.x,
+        max_y: max.y,
+    }
+}
+
+/// 覆盖WGS84范围 `[west, south, east, north]` 的XYZ瓦片
+/// XYZ tiles covering the WGS84 box `[west, south, east, north]`
+///
+/// 对于高德、腾讯等GCJ02瓦片，传入GCJ02范围即可。
+/// For GCJ02 tiles such as Amap and Tencent, pass a GCJ02 box.
+///
+/// # Example 示例
+///
+/// ```rust
+/// use coordtransform::tile::xyz_tiles_wgs84;
+///
+/// let tiles = xyz_tiles_wgs84([-180.0, -85.0, 180.0, 85.0], 2);
+/// assert_eq!(tiles.len(), 16);
+/// ```
+pub fn xyz_tiles_wgs84(bbox: [f64; 4], z: u8) -> TileRange {
+    let (x1, y1) = wgs84_to_epsg3857(bbox[0], bbox[1]);
+    let (x2, y2) = wgs84_to_epsg3857(bbox[2], bbox[3]);
+    xyz_tiles_epsg3857([x1, y1, x2, y2], z)
+}
+
+/// 覆盖WGS84范围 `[west, south, east, north]` 的高德、腾讯等GCJ02 XYZ瓦片
+/// GCJ02 XYZ tiles such as Amap and Tencent covering the WGS84 box `[west, south, east, north]`
+///
+/// GCJ02偏移是非线性的，因此沿范围各边采样转换后取外包范围。
+/// The GCJ02 offset is non-linear, so points sampled along the edges of the box are converted and
+/// their envelope is used.
+pub fn gcj02_xyz_tiles_wgs84(bbox: [f64; 4], z: u8) -> TileRange {
+    let [west, south, east, north] = bbox;
+    let mut envelope = [
+        f64::INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NEG_INFINITY,
+    ];
+    for i in 0..=EDGE_SAMPLES {
+        let t = f64::from(i) / f64::from(EDGE_SAMPLES);
+        let lon = west + (east - west) * t;
+        let lat = south + (north - south) * t;
+        for (lon, lat) in [(lon, south), (lon, north), (west, lat), (east, lat)] {
+            let (lon, lat) = wgs84_to_gcj02(lon, lat);
+            let (x, y) = wgs84_to_epsg3857(lon, lat);
+            envelope = [
+                envelope[0].min(x),
+                envelope[1].min(y),
+                envelope[2].max(x),
+                envelope[3].max(y),
+            ];
+        }
+    }
+    xyz_tiles_epsg3857(envelope, z)
+}
+
+/// XYZ瓦片 -> TMS瓦片 (行号自南向北)，如腾讯瓦片地址使用的行号
+/// XYZ tile -> TMS tile (rows numbered from the south), as used in Tencent tile URLs
+///
+/// # Panics
+///
+/// 级别超过 [`MAX_ZOOM`] 时 panic Panics if the zoom level exceeds [`MAX_ZOOM`]
+pub fn xyz_to_tms(tile: Tile) -> Tile {
+    check_zoom(tile.z);
+    let rows = 1_i64 << tile.z;
+    Tile::new(tile.x, rows - 1 - tile.y, tile.z)
+}
+
+/// TMS瓦片 (行号自南向北) -> XYZ瓦片
+/// TMS tile (rows numbered from the south) -> XYZ tile
+///
+/// # Panics
+///
+/// 级别超过 [`MAX_ZOOM`] 时 panic Panics if the zoom level exceeds [`MAX_ZOOM`]
+pub fn tms_to_xyz(tile: Tile) -> Tile {
+    xyz_to_tms(tile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_baidu_tile() {
+        // 天安门 BD09MC (12958175.0, 4825923.77)
+        let pixel = bd09_to_baidu_tile(116.404, 39.915, 18);
+        assert_eq!(pixel.tile, Tile::new(50617, 18851, 18));
+        assert!((pixel.x - 223.0).abs() < 0.01 && (pixel.y - 188.23).abs() < 0.01);
+
+        // 每升一级像素坐标加倍 Pixel coordinates double with every level
+        let (px, py) = bd09mc_to_baidu_pixel(12958175.0, 4825923.77, 19);
+        assert_eq!((px, py), (12958175.0 * 2.0, 4825923.77 * 2.0));
+        assert_eq!(
+            bd09_to_baidu_tile(116.404, 39.915, 3).tile,
+            Tile::new(1, 0, 3)
+        );
+
+        // 西半球与南半球为负 Negative in the western and southern hemispheres
+        let pixel = bd09_to_baidu_tile(-74.006, -33.45, 10);
+        assert!(pixel.tile.x < 0 && pixel.tile.y < 0);
+        assert!((0.0..TILE_SIZE).contains(&pixel.x) && (0.0..TILE_SIZE).contains(&pixel.y));
+
+        // NaN落在瓦片 (0, 0) NaN falls into tile (0, 0)
+        let pixel = bd09_to_baidu_tile(f64::NAN, f64::NAN, 18);
+        assert_eq!(pixel.tile, Tile::new(0, 0, 18));
+        assert!(pixel.x.is_nan() && pixel.y.is_nan());
+        let pixel = bd09mc_to_baidu_tile(f64::INFINITY, f64::NEG_INFINITY, 18);
+        assert_eq!(pixel.tile, Tile::new(i64::MAX, i64::MIN, 18));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for z in [3, 10, 18, 19] {
+            let (x, y) = (12958175.0, -4825923.77);
+            let (back_x, back_y) = baidu_tile_to_bd09mc(bd09mc_to_baidu_tile(x, y, z));
+            assert!((back_x - x).abs() < 1e-6 && (back_y - y).abs() < 1e-6);
+        }
+        // 瓦片左上角 The top-left corner of a tile
+        let tile = Tile::new(50617, 18851, 18);
+        let [min_x, _, _, max_y] = baidu_tile_bounds_bd09mc(tile);
+        let corner = bd09mc_to_baidu_tile(min_x, max_y, 18);
+        assert_eq!((corner.tile, corner.x, corner.y), (tile, 0.0, 0.0));
+        // 南边界上的点属于南侧瓦片 Points on the south edge belong to the row below
+        let [_, min_y, _, _] = baidu_tile_bounds_bd09mc(tile);
+        let edge = bd09mc_to_baidu_tile(min_x, min_y, 18);
+        assert_eq!(
+            (edge.tile, edge.y),
+            (Tile::new(tile.x, tile.y - 1, 18), 0.0)
+        );
+        let (x, y) = baidu_tile_to_bd09mc(TilePixel {
+            tile,
+            x: 0.0,
+            y: 0.0,
+        });
+        assert_eq!((x, y), (min_x, max_y));
+    }
+
+    #[test]
+    fn test_tile_range() {
+        let range = baidu_tiles_bd09([116.3, 39.85, 116.5, 40.0], 14);
+        let tiles: Vec<Tile> = range.iter().collect();
+        assert_eq!(tiles.len() as u64, range.len());
+        assert_eq!(range.iter().size_hint(), (tiles.len(), Some(tiles.len())));
+        for corner in [
+            (116.3, 39.85),
+            (116.5, 40.0),
+            (116.3, 40.0),
+            (116.404, 39.915),
+        ] {
+            assert!(range.contains(bd09_to_baidu_tile(corner.0, corner.1, 14).tile));
+        }
+        assert_eq!(tiles[0], Tile::new(range.min_x, range.min_y, 14));
+        assert_eq!(tiles[1].y, range.min_y);
+
+        // 角点顺序无关 Corner order does not matter
+        assert_eq!(baidu_tiles_bd09([116.5, 40.0, 116.3, 39.85], 14), range);
+
+        let single = baidu_tiles_bd09mc([1.0, 1.0, 2.0, 2.0], 18);
+        assert_eq!(single.iter().collect::<Vec<_>>(), [Tile::new(0, 0, 18)]);
+
+        let empty = TileRange {
+            z: 1,
+            min_x: 1,
+            min_y: 0,
+            max_x: 0,
+            max_y: 0,
+        };
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.iter().next(), None);
+
+        // 极端范围不溢出 Extreme ranges do not overflow
+        let huge = TileRange {
+            z: MAX_ZOOM,
+            min_x: i64::MIN,
+            min_y: i64::MAX - 1,
+            max_x: i64::MAX,
+            max_y: i64::MAX,
+        };
+        assert_eq!(huge.len(), u64::MAX);
+        assert_eq!(huge.iter().size_hint(), (usize::MAX, None));
+        let corner = TileRange {
+            min_x: i64::MAX - 1,
+            ..huge
+        };
+        assert_eq!(corner.len(), 4);
+        assert_eq!(corner.iter().size_hint(), (4, Some(4)));
+        assert_eq!(
+            corner.iter().last(),
+            Some(Tile::new(i64::MAX, i64::MAX, MAX_ZOOM))
+        );
+        assert_eq!(corner.iter().count(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum")]
+    fn test_baidu_zoom_limit() {
+        bd09_to_baidu_tile(116.404, 39.915, MAX_ZOOM + 1);
+    }
+
+    #[test]
+    fn test_xyz_tile() {
+        let pixel = wgs84_to_xyz_tile(0.0, 0.0, 0);
+        assert_eq!(pixel.tile, Tile::new(0, 0, 0));
+        assert!((pixel.x - 128.0).abs() < 1e-9 && (pixel.y - 128.0).abs() < 1e-9);
+
+        let pixel = wgs84_to_xyz_tile(116.404, 39.915, 10);
+        assert_eq!(pixel.tile, Tile::new(843, 387, 10));
+        assert!((pixel.x - 26.806044).abs() < 1e-5 && (pixel.y - 251.002837).abs() < 1e-5);
+
+        for z in [0, 10, 18, 22] {
+            let pixel = wgs84_to_xyz_tile(-74.006, -33.45, z);
+            assert!((0.0..TILE_SIZE).contains(&pixel.x) && (0.0..TILE_SIZE).contains(&pixel.y));
+            let (lon, lat) = xyz_tile_to_wgs84(pixel);
+            assert!((lon + 74.006).abs() < 1e-9 && (lat + 33.45).abs() < 1e-9);
+        }
+
+        // 世界边缘归入边缘瓦片 The edges of the world fall into the edge tiles
+        assert_eq!(wgs84_to_xyz_tile(180.0, -90.0, 3).tile, Tile::new(7, 7, 3));
+        assert_eq!(wgs84_to_xyz_tile(-180.0, 90.0, 3).tile, Tile::new(0, 0, 3));
+
+        // NaN落在瓦片 (0, 0) NaN falls into tile (0, 0)
+        let pixel = epsg3857_to_xyz_tile(f64::NAN, f64::NAN, 3);
+        assert_eq!(pixel.tile, Tile::new(0, 0, 3));
+        assert!(pixel.x.is_nan() && pixel.y.is_nan());
+    }
+
+    #[test]
+    fn test_xyz_bounds() {
+        let [min_x, min_y, max_x, max_y] = xyz_tile_bounds_epsg3857(Tile::new(0, 0, 0));
+        assert_eq!(
+            (min_x, min_y, max_x, max_y),
+            (-HALF_WORLD, -HALF_WORLD, HALF_WORLD, HALF_WORLD)
+        );
+
+        let [west, south, east, north] = xyz_tile_bounds_wgs84(Tile::new(1, 0, 1));
+        assert!(west.abs() < 1e-9 && south.abs() < 1e-9 && (east - 180.0).abs() < 1e-9);
+        assert!((north - crate::MAX_LATITUDE).abs() < 1e-9);
+
+        let tile = wgs84_to_xyz_tile(116.404, 39.915, 15).tile;
+        let [west, south, east, north] = xyz_tile_bounds_wgs84(tile);
+        assert!(west <= 116.404 && 116.404 < east && south < 39.915 && 39.915 <= north);
+    }
+
+    #[test]
+    fn test_xyz_tile_range() {
+        assert_eq!(xyz_tiles_wgs84([-180.0, -90.0, 180.0, 90.0], 1).len(), 4);
+
+        let bbox = [116.3, 39.85, 116.5, 40.0];
+        let range = xyz_tiles_wgs84(bbox, 12);
+        assert!(range.min_x <= range.max_x && range.min_y <= range.max_y);
+        assert_eq!(
+            range.iter().next(),
+            Some(wgs84_to_xyz_tile(116.3, 40.0, 12).tile)
+        );
+        assert_eq!(
+            Tile::new(range.max_x, range.max_y, 12),
+            wgs84_to_xyz_tile(116.5, 39.85, 12).tile
+        );
+
+        let range = gcj02_xyz_tiles_wgs84(bbox, 16);
+        for (lon, lat) in [
+            (116.3, 39.85),
+            (116.5, 40.0),
+            (116.4, 39.85),
+            (116.404, 39.915),
+        ] {
+            assert!(range.contains(wgs84_to_gcj02_xyz_tile(lon, lat, 16).tile));
+        }
+    }
+
+    #[test]
+    fn test_gcj02_xyz_tile() {
+        let (lon, lat) = wgs84_to_gcj02(116.404, 39.915);
+        let pixel = wgs84_to_gcj02_xyz_tile(116.404, 39.915, 18);
+        assert_eq!(pixel, gcj02_to_xyz_tile(lon, lat, 18));
+        assert_ne!(pixel.tile, wgs84_to_xyz_tile(116.404, 39.915, 18).tile);
+
+        let (back_lon, back_lat) = xyz_tile_to_gcj02(pixel);
+        assert!((back_lon - lon).abs() < 1e-9 && (back_lat - lat).abs() < 1e-9);
+        let (back_lon, back_lat) = gcj02_xyz_tile_to_wgs84(pixel);
+        assert!((back_lon - 116.404).abs() < 1e-7 && (back_lat - 39.915).abs() < 1e-7);
+
+        // 中国境外不偏移 No offset outside China
+        assert_eq!(
+            wgs84_to_gcj02_xyz_tile(-74.006, 40.7128, 14),
+            wgs84_to_xyz_tile(-74.006, 40.7128, 14)
+        );
+    }
+
+    #[test]
+    fn test_tms() {
+        let tile = Tile::new(843, 387, 10);
+        assert_eq!(xyz_to_tms(tile), Tile::new(843, 636, 10));
+        assert_eq!(tms_to_xyz(xyz_to_tms(tile)), tile);
+        assert_eq!(xyz_to_tms(Tile::new(0, 0, 0)), Tile::new(0, 0, 0));
+        assert_eq!(
+            xyz_to_tms(Tile::new(0, 0, MAX_ZOOM)),
+            Tile::new(0, (1 << MAX_ZOOM) - 1, MAX_ZOOM)
+        );
+    }
+
+    #[test]
+    fn test_xyz_max_zoom() {
+        let range = xyz_tiles_wgs84([-180.0, -90.0, 180.0, 90.0], MAX_ZOOM);
+        let last = (1 << MAX_ZOOM) - 1;
+        assert_eq!((range.min_x, range.max_x, range.max_y), (0, last, last));
+        assert_eq!(range.len(), 1 << (2 * MAX_ZOOM));
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum")]
+    fn test_xyz_zoom_limit() {
+        xyz_tiles_wgs84([-180.0, -85.0, 180.0, 85.0], 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum")]
+    fn test_tms_zoom_limit() {
+        xyz_to_tms(Tile::new(0, 0, MAX_ZOOM + 1));
+    }
+}
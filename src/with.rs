@@ -0,0 +1,201 @@
+//! 序列化时自动转换坐标系的serde适配器 (需要 `serde` 特性)
+//! serde adapters converting between coordinate systems on the fly (requires the `serde` feature)
+//!
+//! 每个子模块以转换函数命名，可用于 `#[serde(with = "...")]`。反序列化时将外部数据按该函数转换，
+//! 序列化时使用反向转换写回，因此外部格式始终保持源坐标系。字段可以是 `(f64, f64)` 元组或任意坐标点类型，
+//! 外部格式为 `[经度, 纬度]` 数组。
+//!
+//! 往返精度取决于较不精确的方向：`wgs84_to_gcj02`、`wgs84_to_bd09`、`gcj02_to_bd09`、
+//! `epsg3857_to_gcj02` 与 `epsg3857_to_bd09` 反序列化使用正向变换、序列化使用高精度反算，
+//! 往返误差约为1e-9度；`gcj02_to_wgs84`、`bd09_to_wgs84`、`bd09_to_gcj02`、`gcj02_to_epsg3857`
+//! 与 `bd09_to_epsg3857` 反序列化本身就是近似反算，往返误差可达约5e-5度 (EPSG:3857为数米)，
+//! 其中 `bd09_to_gcj02` 约为2e-6度。
+//!
+//! Each submodule is named after a conversion function and is meant for `#[serde(with = "...")]`.
+//! Deserializing maps the external value through that function and serializing applies the reverse
+//! conversion, so the external format always stays in the source system. The field may be a
+//! `(f64, f64)` tuple or any point type; the external format is a `[lon, lat]` array.
+//!
+//! Round trips are as accurate as the less accurate direction. `wgs84_to_gcj02`, `wgs84_to_bd09`,
+//! `gcj02_to_bd09`, `epsg3857_to_gcj02` and `epsg3857_to_bd09` deserialize through a forward
+//! transformation and serialize through a precise inverse, so they round-trip to about 1e-9
+//! degrees. `gcj02_to_wgs84`, `bd09_to_wgs84`, `bd09_to_gcj02`, `gcj02_to_epsg3857` and
+//! `bd09_to_epsg3857` deserialize through an approximate inverse themselves, so their round trips
+//! are only accurate to about 5e-5 degrees (a few meters for EPSG:3857), or about 2e-6 degrees
+//! for `bd09_to_gcj02`.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Poi {
+//!     name: String,
+//!     /// 数据源提供BD09坐标，程序内使用WGS84
+//!     /// The source supplies BD09, the program works in WGS84
+//!     #[serde(with = "coordtransform::with::bd09_to_wgs84")]
+//!     position: coordtransform::Wgs84Point,
+//! }
+//!
+//! let poi: Poi = serde_json::from_str(r#"{"name":"天安门","position":[116.404,39.915]}"#).unwrap();
+//! let (lon, lat) = coordtransform::bd09_to_wgs84(116.404, 39.915);
+//! assert_eq!((poi.position.lon, poi.position.lat), (lon, lat));
+//! ```
+
+/// 生成serde适配器子模块
+/// Generate a serde adapter submodule
+macro_rules! serde_adapter {
+    ($name:ident, $inverse:ident, $title:literal) => {
+        #[doc = concat!($title, "，反序列化时调用 [`", stringify!($name), "`](crate::", stringify!($name), ")")]
+        #[doc = ""]
+        #[doc = concat!("Deserializes through [`", stringify!($name), "`](crate::", stringify!($name), ") and serializes through [`", stringify!($inverse), "`](crate::", stringify!($inverse), ").")]
+        pub mod $name {
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            /// 反向转换后序列化 Serialize after the reverse conversion
+            pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+                T: Copy + Into<(f64, f64)>,
+            {
+                let (lon, lat) = (*value).into();
+                crate::$inverse(lon, lat).serialize(serializer)
+            }
+
+            /// 反序列化后转换 Convert after deserializing
+            pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+            where
+                D: Deserializer<'de>,
+                T: From<(f64, f64)>,
+            {
+                let (lon, lat) = <(f64, f64)>::deserialize(deserializer)?;
+                Ok(crate::$name(lon, lat).into())
+            }
+        }
+    };
+}
+
+serde_adapter!(bd09_to_gcj02, gcj02_to_bd09, "百度坐标系 -> 火星坐标系");
+serde_adapter!(
+    gcj02_to_bd09,
+    bd09_to_gcj02_precise,
+    "火星坐标系 -> 百度坐标系"
+);
+serde_adapter!(
+    wgs84_to_gcj02,
+    gcj02_to_wgs84_precise,
+    "WGS84坐标系 -> 火星坐标系"
+);
+serde_adapter!(gcj02_to_wgs84, wgs84_to_gcj02, "火星坐标系 -> WGS84坐标系");
+serde_adapter!(bd09_to_wgs84, wgs84_to_bd09, "百度坐标系 -> WGS84坐标系");
+serde_adapter!(
+    wgs84_to_bd09,
+    bd09_to_wgs84_precise,
+    "WGS84坐标系 -> 百度坐标系"
+);
+serde_adapter!(
+    wgs84_to_epsg3857,
+    epsg3857_to_wgs84,
+    "WGS84坐标系 -> EPSG:3857坐标系"
+);
+serde_adapter!(
+    epsg3857_to_wgs84,
+    wgs84_to_epsg3857,
+    "EPSG:3857坐标系 -> WGS84坐标系"
+);
+serde_adapter!(
+    gcj02_to_epsg3857,
+    epsg3857_to_gcj02,
+    "GCJ02坐标系 -> EPSG:3857坐标系"
+);
+serde_adapter!(
+    epsg3857_to_gcj02,
+    gcj02_to_epsg3857_precise,
+    "EPSG:3857坐标系 -> GCJ02坐标系"
+);
+serde_adapter!(
+    bd09_to_epsg3857,
+    epsg3857_to_bd09,
+    "BD09坐标系 -> EPSG:3857坐标系"
+);
+serde_adapter!(
+    epsg3857_to_bd09,
+    bd09_to_epsg3857_precise,
+    "EPSG:3857坐标系 -> BD09坐标系"
+);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "super::gcj02_to_wgs84")]
+        position: (f64, f64),
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Shifted {
+        #[serde(with = "super::wgs84_to_gcj02")]
+        gcj02: (f64, f64),
+        #[serde(with = "super::gcj02_to_bd09")]
+        bd09: (f64, f64),
+        #[serde(with = "super::wgs84_to_bd09")]
+        wgs84_bd09: (f64, f64),
+        #[serde(with = "super::epsg3857_to_gcj02")]
+        mercator_gcj02: (f64, f64),
+        #[serde(with = "super::epsg3857_to_bd09")]
+        mercator_bd09: (f64, f64),
+    }
+
+    fn assert_position(value: &serde_json::Value, expected: (f64, f64), tolerance: f64) {
+        let lon = value[0].as_f64().unwrap();
+        let lat = value[1].as_f64().unwrap();
+        assert!(
+            (lon - expected.0).abs() < tolerance,
+            "{} != {}",
+            lon,
+            expected.0
+        );
+        assert!(
+            (lat - expected.1).abs() < tolerance,
+            "{} != {}",
+            lat,
+            expected.1
+        );
+    }
+
+    #[test]
+    fn test_adapter_round_trip() {
+        let record: Record = serde_json::from_str(r#"{"position":[116.404,39.915]}"#).unwrap();
+        assert_eq!(record.position, crate::gcj02_to_wgs84(116.404, 39.915));
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_position(&json["position"], (116.404, 39.915), 1e-5);
+    }
+
+    #[test]
+    fn test_adapter_precise_round_trip() {
+        let input = r#"{
+            "gcj02": [116.404, 39.915],
+            "bd09": [121.4737, 31.2304],
+            "wgs84_bd09": [87.6168, 43.8256],
+            "mercator_gcj02": [12958034.0, 4853597.0],
+            "mercator_bd09": [12127000.0, 2070000.0]
+        }"#;
+        let record: Shifted = serde_json::from_str(input).unwrap();
+        assert_eq!(record.gcj02, crate::wgs84_to_gcj02(116.404, 39.915));
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_position(&json["gcj02"], (116.404, 39.915), 1e-9);
+        assert_position(&json["bd09"], (121.4737, 31.2304), 1e-9);
+        assert_position(&json["wgs84_bd09"], (87.6168, 43.8256), 1e-9);
+        assert_position(&json["mercator_gcj02"], (12958034.0, 4853597.0), 1e-3);
+        assert_position(&json["mercator_bd09"], (12127000.0, 2070000.0), 1e-3);
+    }
+
+    #[test]
+    fn test_adapter_rejects_malformed_input() {
+        assert!(serde_json::from_str::<Record>(r#"{"position":[116.404]}"#).is_err());
+        assert!(serde_json::from_str::<Record>(r#"{"position":"116,39"}"#).is_err());
+    }
+}
@@ -0,0 +1,599 @@
+//! WKB与EWKB读写
+//! WKB and EWKB reading and writing
+//!
+//! 读取时同时接受ISO WKB (维度编码在类型代码的千位) 与PostGIS EWKB (维度与SRID编码在类型代码的高位标志)，
+//! 支持大端与小端字节序。空点按ISO约定编码为全部NaN的坐标。
+//!
+//! Reading accepts both ISO WKB (dimension encoded in the thousands of the type code) and PostGIS
+//! EWKB (dimension and SRID encoded as high flag bits of the type code), in either byte order. An
+//! empty point is encoded as a coordinate of NaNs, following the ISO convention.
+//!
+//! ```rust
+//! use coordtransform::{wkb, CoordSystem};
+//!
+//! // PostGIS导出的EWKB十六进制 EWKB hex as exported by PostGIS: SRID=4326;POINT(116.404 39.915)
+//! let hex = "0101000020E610000060E5D022DB195D4085EB51B81EF54340";
+//! let output = wkb::convert_hex(hex, CoordSystem::Wgs84, CoordSystem::Gcj02).unwrap();
+//!
+//! let geometry = wkb::read_hex(&output).unwrap();
+//! assert_eq!(geometry.srid, Some(4326));
+//! assert_eq!(output.len(), hex.len());
+//! ```
+
+use crate::CoordSystem;
+use crate::geometry::{Coord, Dimension, Geometry, GeometryError, GeometryKind, MAX_NESTING_DEPTH};
+
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// 字节序 Byte order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ByteOrder {
+    /// 大端 (XDR) Big endian (XDR)
+    BigEndian,
+    /// 小端 (NDR) Little endian (NDR)
+    #[default]
+    LittleEndian,
+}
+
+/// WKB格式 WKB flavour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WkbFormat {
+    /// ISO WKB，不包含SRID ISO WKB, without the SRID
+    Iso,
+    /// PostGIS扩展WKB，顶层几何对象带有SRID PostGIS extended WKB with the SRID on the top-level geometry
+    #[default]
+    Extended,
+}
+
+/// WKB写出选项 WKB writing options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct WkbOptions {
+    /// 字节序 Byte order
+    pub byte_order: ByteOrder,
+    /// WKB格式 WKB flavour
+    pub format: WkbFormat,
+}
+
+/// 解析WKB或EWKB
+/// Parse WKB or EWKB
+///
+/// # Parameters 参数
+///
+/// * `bytes` - WKB字节 WKB bytes
+///
+/// # Return Value 返回值
+///
+/// 返回解析得到的几何对象 Returns the parsed geometry
+pub fn read(bytes: &[u8]) -> Result<Geometry, GeometryError> {
+    read_with_options(bytes).map(|(geometry, _)| geometry)
+}
+
+/// 解析WKB或EWKB，同时返回输入使用的字节序与格式
+/// Parse WKB or EWKB, also returning the byte order and flavour of the input
+///
+/// 维度与SRID都未使用EWKB标志时，格式报告为 [`WkbFormat::Iso`]。
+/// The flavour is reported as [`WkbFormat::Iso`] when no EWKB flag is used for the dimension or
+/// the SRID.
+pub fn read_with_options(bytes: &[u8]) -> Result<(Geometry, WkbOptions), GeometryError> {
+    let mut reader = Reader {
+        bytes,
+        pos: 0,
+        byte_order: ByteOrder::LittleEndian,
+        extended: false,
+    };
+    let geometry = reader.geometry(0)?;
+    if reader.pos < bytes.len() {
+        return Err(GeometryError::TrailingData { offset: reader.pos });
+    }
+
+    let format = if reader.extended {
+        WkbFormat::Extended
+    } else {
+        WkbFormat::Iso
+    };
+    let byte_order = match bytes[0] {
+        0 => ByteOrder::BigEndian,
+        _ => ByteOrder::LittleEndian,
+    };
+    Ok((geometry, WkbOptions { byte_order, format }))
+}
+
+/// 写出WKB Write WKB
+///
+/// # Parameters 参数
+///
+/// * `geometry` - 几何对象 Geometry
+/// * `options` - 字节序与格式 Byte order and flavour
+pub fn write(geometry: &Geometry, options: WkbOptions) -> Vec<u8> {
+    let mut writer = Writer {
+        bytes: Vec::new(),
+        options,
+    };
+    let srid = match options.format {
+        WkbFormat::Extended => geometry.srid,
+        WkbFormat::Iso => None,
+    };
+    writer.geometry(geometry, srid);
+    writer.bytes
+}
+
+/// 解析十六进制WKB (PostGIS默认输出格式)
+/// Parse hex-encoded WKB, the default PostGIS output
+pub fn read_hex(hex: &str) -> Result<Geometry, GeometryError> {
+    read(&decode_hex(hex)?)
+}
+
+/// 写出大写十六进制WKB Write WKB as upper-case hex
+pub fn write_hex(geometry: &Geometry, options: WkbOptions) -> String {
+    encode_hex(&write(geometry, options))
+}
+
+/// 转换WKB中的所有坐标，保持输入的字节序与格式，并按 [`Geometry::convert_in_place`] 改写SRID
+/// Convert every coordinate of a WKB, keeping the byte order and flavour of the input and
+/// rewriting the SRID as [`Geometry::convert_in_place`] does
+///
+/// # Parameters 参数
+///
+/// * `bytes` - WKB字节 WKB bytes
+/// * `from` - 源坐标系 Source coordinate system
+/// * `to` - 目标坐标系 Target coordinate system
+pub fn convert_bytes(
+    bytes: &[u8],
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<Vec<u8>, GeometryError> {
+    let (mut geometry, options) = read_with_options(bytes)?;
+    geometry.convert_in_place(from, to);
+    Ok(write(&geometry, options))
+}
+
+/// 转换十六进制WKB中的所有坐标，规则同 [`convert_bytes`]
+/// Convert every coordinate of a hex-encoded WKB, following the same rules as [`convert_bytes`]
+pub fn convert_hex(hex: &str, from: CoordSystem, to: CoordSystem) -> Result<String, GeometryError> {
+    convert_bytes(&decode_hex(hex)?, from, to).map(|bytes| encode_hex(&bytes))
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, GeometryError> {
+    let hex = hex.trim().as_bytes();
+    if !hex.len().is_multiple_of(2) {
+        return Err(GeometryError::InvalidHex);
+    }
+    hex.chunks_exact(2)
+        .map(|pair| {
+            let digit = |c: u8| (c as char).to_digit(16).ok_or(GeometryError::InvalidHex);
+            Ok((digit(pair[0])? * 16 + digit(pair[1])?) as u8)
+        })
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    byte_order: ByteOrder,
+    /// 是否遇到EWKB标志 Whether an EWKB flag was seen
+    extended: bool,
+}
+
+impl Reader<'_> {
+    /// 读取几何对象，`depth` 为所在几何集合或多部件几何对象的嵌套层数
+    /// Read a geometry nested `depth` collections or multi-part geometries deep
+    fn geometry(&mut self, depth: usize) -> Result<Geometry, GeometryError> {
+        if depth > MAX_NESTING_DEPTH {
+            return Err(GeometryError::TooDeeplyNested { offset: self.pos });
+        }
+        self.byte_order = match self.take::<1>()?[0] {
+            0 => ByteOrder::BigEndian,
+            1 => ByteOrder::LittleEndian,
+            byte => return Err(GeometryError::InvalidByteOrder(byte)),
+        };
+
+        let code = self.u32()?;
+        let flags = code & (EWKB_Z | EWKB_M | EWKB_SRID);
+        self.extended |= flags != 0;
+        let srid = if flags & EWKB_SRID != 0 {
+            Some(self.u32()? as i32)
+        } else {
+            None
+        };
+
+        let iso = code & 0x0FFF_FFFF;
+        let dimension = Dimension::from_flags(
+            flags & EWKB_Z != 0 || matches!(iso / 1000, 1 | 3),
+            flags & EWKB_M != 0 || matches!(iso / 1000, 2 | 3),
+        );
+        let kind = match iso % 1000 {
+            1 => {
+                let coord = self.coord(dimension)?;
+                let empty = coord.x.is_nan() && coord.y.is_nan();
+                GeometryKind::Point((!empty).then_some(coord))
+            }
+            2 => GeometryKind::LineString(self.coords(dimension)?),
+            3 => GeometryKind::Polygon(self.list(|r| r.coords(dimension))?),
+            4 => GeometryKind::MultiPoint(self.members(
+                depth,
+                |kind| match kind {
+                    GeometryKind::Point(point) => point,
+                    _ => None,
+                },
+                "non-empty point",
+            )?),
+            5 => GeometryKind::MultiLineString(self.members(
+                depth,
+                |kind| match kind {
+                    GeometryKind::LineString(line) => Some(line),
+                    _ => None,
+                },
+                "line string",
+            )?),
+            6 => GeometryKind::MultiPolygon(self.members(
+                depth,
+                |kind| match kind {
+                    GeometryKind::Polygon(rings) => Some(rings),
+                    _ => None,
+                },
+                "polygon",
+            )?),
+            7 => GeometryKind::GeometryCollection(self.list(|r| r.geometry(depth + 1))?),
+            _ => return Err(GeometryError::UnknownType(code.to_string())),
+        };
+
+        Ok(Geometry {
+            srid,
+            dimension,
+            kind,
+        })
+    }
+
+    /// 读取指定类型的成员几何对象 Read member geometries of the given type
+    fn members<T>(
+        &mut self,
+        depth: usize,
+        unwrap: impl Fn(GeometryKind) -> Option<T>,
+        expected: &'static str,
+    ) -> Result<Vec<T>, GeometryError> {
+        self.list(|r| {
+            let offset = r.pos;
+            unwrap(r.geometry(depth + 1)?.kind)
+                .ok_or(GeometryError::Unexpected { offset, expected })
+        })
+    }
+
+    /// 读取带元素个数的列表 Read a count-prefixed list
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, GeometryError>,
+    ) -> Result<Vec<T>, GeometryError> {
+        let count = self.u32()? as usize;
+        // 每个元素至少占一个字节，避免按伪造的个数预分配
+        // Every item takes at least one byte; avoids preallocating for a forged count
+        let mut items = Vec::with_capacity(count.min(self.bytes.len() - self.pos));
+        for _ in 0..count {
+            // 成员几何对象各自带有字节序 Member geometries carry their own byte order
+            let byte_order = self.byte_order;
+            items.push(item(self)?);
+            self.byte_order = byte_order;
+        }
+        Ok(items)
+    }
+
+    fn coords(&mut self, dimension: Dimension) -> Result<Vec<Coord>, GeometryError> {
+        self.list(|r| r.coord(dimension))
+    }
+
+    fn coord(&mut self, dimension: Dimension) -> Result<Coord, GeometryError> {
+        let mut ordinates = [0.0; 4];
+        for ordinate in &mut ordinates[..dimension.size()] {
+            *ordinate = self.f64()?;
+        }
+        Ok(Coord::from_ordinates(
+            dimension,
+            &ordinates[..dimension.size()],
+        ))
+    }
+
+    fn u32(&mut self) -> Result<u32, GeometryError> {
+        let bytes = self.take::<4>()?;
+        Ok(match self.byte_order {
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64, GeometryError> {
+        let bytes = self.take::<8>()?;
+        Ok(match self.byte_order {
+            ByteOrder::BigEndian => f64::from_be_bytes(bytes),
+            ByteOrder::LittleEndian => f64::from_le_bytes(bytes),
+        })
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], GeometryError> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or(GeometryError::UnexpectedEnd)?;
+        self.pos += N;
+        Ok(bytes.try_into().expect("slice has length N"))
+    }
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+    options: WkbOptions,
+}
+
+impl Writer {
+    fn geometry(&mut self, geometry: &Geometry, srid: Option<i32>) {
+        let dimension = geometry.dimension;
+        let kind = &geometry.kind;
+        self.bytes.push(match self.options.byte_order {
+            ByteOrder::BigEndian => 0,
+            ByteOrder::LittleEndian => 1,
+        });
+
+        let code = match self.options.format {
+            WkbFormat::Iso => {
+                kind.code()
+                    + match dimension {
+                        Dimension::Xy => 0,
+                        Dimension::Xyz => 1000,
+                        Dimension::Xym => 2000,
+                        Dimension::Xyzm => 3000,
+                    }
+            }
+            WkbFormat::Extended => {
+                let mut code = kind.code();
+                if dimension.has_z() {
+                    code |= EWKB_Z;
+                }
+                if dimension.has_m() {
+                    code |= EWKB_M;
+                }
+                if srid.is_some() {
+                    code |= EWKB_SRID;
+                }
+                code
+            }
+        };
+        self.u32(code);
+        if let Some(srid) = srid {
+            self.u32(srid as u32);
+        }
+
+        let member = |kind| Geometry::new(dimension, kind);
+        match kind {
+            GeometryKind::Point(point) => {
+                let empty = Coord {
+                    x: f64::NAN,
+                    y: f64::NAN,
+                    z: Some(f64::NAN),
+                    m: Some(f64::NAN),
+                };
+                self.coord(point.as_ref().unwrap_or(&empty), dimension);
+            }
+            GeometryKind::LineString(line) => self.coords(line, dimension),
+            GeometryKind::Polygon(rings) => {
+                self.u32(rings.len() as u32);
+                for ring in rings {
+                    self.coords(ring, dimension);
+                }
+            }
+            GeometryKind::MultiPoint(points) => {
+                self.u32(points.len() as u32);
+                for point in points {
+                    self.geometry(&member(GeometryKind::Point(Some(*point))), None);
+                }
+            }
+            GeometryKind::MultiLineString(lines) => {
+                self.u32(lines.len() as u32);
+                for line in lines {
+                    self.geometry(&member(GeometryKind::LineString(line.clone())), None);
+                }
+            }
+            GeometryKind::MultiPolygon(polygons) => {
+                self.u32(polygons.len() as u32);
+                for polygon in polygons {
+                    self.geometry(&member(GeometryKind::Polygon(polygon.clone())), None);
+                }
+            }
+            GeometryKind::GeometryCollection(geometries) => {
+                self.u32(geometries.len() as u32);
+                for geometry in geometries {
+                    self.geometry(geometry, None);
+                }
+            }
+        }
+    }
+
+    fn coords(&mut self, coords: &[Coord], dimension: Dimension) {
+        self.u32(coords.len() as u32);
+        for coord in coords {
+            self.coord(coord, dimension);
+        }
+    }
+
+    fn coord(&mut self, coord: &Coord, dimension: Dimension) {
+        for value in coord.ordinates(dimension) {
+            self.bytes
+                .extend_from_slice(&match self.options.byte_order {
+                    ByteOrder::BigEndian => value.to_be_bytes(),
+                    ByteOrder::LittleEndian => value.to_le_bytes(),
+                });
+        }
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes
+            .extend_from_slice(&match self.options.byte_order {
+                ByteOrder::BigEndian => value.to_be_bytes(),
+                ByteOrder::LittleEndian => value.to_le_bytes(),
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wkt;
+
+    const ALL_OPTIONS: [WkbOptions; 4] = [
+        WkbOptions {
+            byte_order: ByteOrder::LittleEndian,
+            format: WkbFormat::Iso,
+        },
+        WkbOptions {
+            byte_order: ByteOrder::BigEndian,
+            format: WkbFormat::Iso,
+        },
+        WkbOptions {
+            byte_order: ByteOrder::LittleEndian,
+            format: WkbFormat::Extended,
+        },
+        WkbOptions {
+            byte_order: ByteOrder::BigEndian,
+            format: WkbFormat::Extended,
+        },
+    ];
+
+    #[test]
+    fn test_round_trip() {
+        let cases = [
+            "POINT (1 2)",
+            "POINT ZM (1 2 3 4)",
+            "POINT EMPTY",
+            "LINESTRING M (1 2 3, 4 5 6)",
+            "POLYGON Z ((0 0 1, 10 0 2, 10 10 3, 0 0 1))",
+            "MULTIPOINT ((1 2), (3 4))",
+            "MULTILINESTRING ((1 2, 3 4), (5 6, 7 8))",
+            "MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5)))",
+            "GEOMETRYCOLLECTION (POINT Z (1 2 3), MULTIPOINT Z ((4 5 6)))",
+        ];
+        for case in cases {
+            let geometry = wkt::read(case).unwrap();
+            for options in ALL_OPTIONS {
+                let bytes = write(&geometry, options);
+                let (parsed, parsed_options) = read_with_options(&bytes).unwrap();
+                assert_eq!(parsed, geometry, "{}", case);
+                assert_eq!(parsed_options.byte_order, options.byte_order, "{}", case);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_detection() {
+        // 三维几何对象在两种格式中的编码不同，因此可以识别格式
+        // 3D geometries are encoded differently by the two flavours, so the format is detected
+        let geometry = wkt::read("POINT Z (1 2 3)").unwrap();
+        for options in ALL_OPTIONS {
+            let (_, parsed) = read_with_options(&write(&geometry, options)).unwrap();
+            assert_eq!(parsed, options);
+        }
+
+        // 二维且无SRID时两种格式的编码相同，报告为ISO
+        // Both flavours encode 2D geometries without an SRID identically, reported as ISO
+        let geometry = wkt::read("POINT (1 2)").unwrap();
+        for options in ALL_OPTIONS {
+            let (_, parsed) = read_with_options(&write(&geometry, options)).unwrap();
+            assert_eq!(parsed.format, WkbFormat::Iso);
+            assert_eq!(parsed.byte_order, options.byte_order);
+        }
+    }
+
+    #[test]
+    fn test_postgis_ewkb() {
+        // SELECT ST_AsEWKB('SRID=4326;POINT(116.404 39.915 50)'::geometry)
+        let hex = "01010000A0E610000060E5D022DB195D4085EB51B81EF543400000000000004940";
+        let geometry = read_hex(hex).unwrap();
+        assert_eq!(
+            geometry.to_string(),
+            "SRID=4326;POINT Z (116.404 39.915 50)"
+        );
+        assert_eq!(write_hex(&geometry, WkbOptions::default()), hex);
+
+        let converted =
+            read_hex(&convert_hex(hex, CoordSystem::Wgs84, CoordSystem::Epsg3857).unwrap())
+                .unwrap();
+        assert_eq!(converted.srid, Some(3857));
+        let GeometryKind::Point(Some(point)) = converted.kind else {
+            panic!("geometry type changed");
+        };
+        assert_eq!(
+            (point.x, point.y),
+            crate::wgs84_to_epsg3857(116.404, 39.915)
+        );
+        assert_eq!(point.z, Some(50.0));
+    }
+
+    #[test]
+    fn test_iso_drops_srid() {
+        let geometry = wkt::read("SRID=4326;POINT (1 2)").unwrap();
+        let options = WkbOptions {
+            format: WkbFormat::Iso,
+            ..WkbOptions::default()
+        };
+        assert_eq!(read(&write(&geometry, options)).unwrap().srid, None);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(read(&[]), Err(GeometryError::UnexpectedEnd));
+        assert_eq!(read(&[2]), Err(GeometryError::InvalidByteOrder(2)));
+        assert_eq!(read_hex("0101"), Err(GeometryError::UnexpectedEnd));
+        assert_eq!(read_hex("01010"), Err(GeometryError::InvalidHex));
+        assert_eq!(read_hex("zz"), Err(GeometryError::InvalidHex));
+        assert!(matches!(
+            read_hex("0108000000"),
+            Err(GeometryError::UnknownType(_))
+        ));
+        // 声称有大量元素的伪造输入 Forged input claiming a huge number of items
+        assert_eq!(
+            read_hex("0102000000FFFFFFFF"),
+            Err(GeometryError::UnexpectedEnd)
+        );
+
+        let mut bytes = write(&wkt::read("POINT (1 2)").unwrap(), WkbOptions::default());
+        bytes.push(0);
+        assert_eq!(
+            read(&bytes),
+            Err(GeometryError::TrailingData { offset: 21 })
+        );
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let nested = |depth: usize| {
+            // 小端序、类型7、1个成员 Little endian, type 7, one member
+            let mut bytes = [1, 7, 0, 0, 0, 1, 0, 0, 0].repeat(depth);
+            bytes.extend(write(
+                &wkt::read("POINT (1 2)").unwrap(),
+                WkbOptions::default(),
+            ));
+            bytes
+        };
+        assert!(read(&nested(MAX_NESTING_DEPTH)).is_ok());
+        assert_eq!(
+            read(&nested(MAX_NESTING_DEPTH + 1)),
+            Err(GeometryError::TooDeeplyNested {
+                offset: 9 * (MAX_NESTING_DEPTH + 1)
+            })
+        );
+        // 恶意输入不会导致栈溢出 Crafted input does not overflow the stack
+        assert!(matches!(
+            read(&nested(1_000_000)),
+            Err(GeometryError::TooDeeplyNested { .. })
+        ));
+        // 多点成员中的集合同样计入层数 Collections inside multi-point members count as well
+        let mut bytes = [1, 4, 0, 0, 0, 1, 0, 0, 0].to_vec();
+        bytes.extend(nested(1_000_000));
+        assert!(matches!(
+            read(&bytes),
+            Err(GeometryError::TooDeeplyNested { .. })
+        ));
+    }
+}
@@ -0,0 +1,539 @@
+//! WKT与EWKT读写
+//! WKT and EWKT reading and writing
+//!
+//! 读取时接受ISO写法 (`POINT Z (1 2 3)`)、PostGIS写法 (`POINTM(1 2 3)`、省略维度标记的三维或四维坐标)
+//! 以及 `SRID=4326;` 前缀；写出时使用ISO写法，有SRID时加上EWKT前缀。关键字不区分大小写。
+//!
+//! Reading accepts the ISO form (`POINT Z (1 2 3)`), the PostGIS forms (`POINTM(1 2 3)` and 3D or 4D
+//! coordinates without a dimension marker) and the `SRID=4326;` prefix; writing uses the ISO form
+//! plus the EWKT prefix when an SRID is set. Keywords are case-insensitive.
+//!
+//! ```rust
+//! use coordtransform::{wkt, CoordSystem};
+//!
+//! let output = wkt::convert_str(
+//!     "SRID=4326;LINESTRING(116.404 39.915, 121.4737 31.2304)",
+//!     CoordSystem::Wgs84,
+//!     CoordSystem::Gcj02,
+//! )
+//! .unwrap();
+//! assert!(output.starts_with("SRID=4326;LINESTRING ("));
+//! ```
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::CoordSystem;
+use crate::geometry::{Coord, Dimension, Geometry, GeometryError, GeometryKind, MAX_NESTING_DEPTH};
+
+/// 解析WKT或EWKT
+/// Parse WKT or EWKT
+///
+/// # Parameters 参数
+///
+/// * `input` - WKT文本 WKT text
+///
+/// # Return Value 返回值
+///
+/// 返回解析得到的几何对象 Returns the parsed geometry
+pub fn read(input: &str) -> Result<Geometry, GeometryError> {
+    let mut parser = Parser { input, pos: 0 };
+    let geometry = parser.geometry(0)?;
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(GeometryError::TrailingData { offset: parser.pos });
+    }
+    Ok(geometry)
+}
+
+/// 写出WKT，有SRID时写出EWKT
+/// Write WKT, or EWKT when an SRID is set
+pub fn write(geometry: &Geometry) -> String {
+    geometry.to_string()
+}
+
+/// 转换WKT或EWKT文本中的所有坐标，并按 [`Geometry::convert_in_place`] 改写SRID
+/// Convert every coordinate of a WKT or EWKT text, rewriting the SRID as
+/// [`Geometry::convert_in_place`] does
+///
+/// # Parameters 参数
+///
+/// * `input` - WKT文本 WKT text
+/// * `from` - 源坐标系 Source coordinate system
+/// * `to` - 目标坐标系 Target coordinate system
+pub fn convert_str(
+    input: &str,
+    from: CoordSystem,
+    to: CoordSystem,
+) -> Result<String, GeometryError> {
+    let mut geometry = read(input)?;
+    geometry.convert_in_place(from, to);
+    Ok(write(&geometry))
+}
+
+impl FromStr for Geometry {
+    type Err = GeometryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        read(s)
+    }
+}
+
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(srid) = self.srid {
+            write!(f, "SRID={};", srid)?;
+        }
+        write_geometry(f, self)
+    }
+}
+
+fn write_geometry(f: &mut fmt::Formatter<'_>, geometry: &Geometry) -> fmt::Result {
+    f.write_str(geometry.kind.keyword())?;
+    f.write_str(match geometry.dimension {
+        Dimension::Xy => "",
+        Dimension::Xyz => " Z",
+        Dimension::Xym => " M",
+        Dimension::Xyzm => " ZM",
+    })?;
+    if geometry.kind.is_empty() {
+        return f.write_str(" EMPTY");
+    }
+
+    f.write_char(' ')?;
+    let dimension = geometry.dimension;
+    match &geometry.kind {
+        GeometryKind::Point(point) => {
+            write_list(f, point.iter(), |f, c| write_coord(f, c, dimension))
+        }
+        GeometryKind::LineString(line) => write_coords(f, line, dimension),
+        GeometryKind::Polygon(lines) | GeometryKind::MultiLineString(lines) => {
+            write_list(f, lines.iter(), |f, line| write_coords(f, line, dimension))
+        }
+        GeometryKind::MultiPoint(points) => write_list(f, points.iter(), |f, point| {
+            write_list(f, [point].into_iter(), |f, c| write_coord(f, c, dimension))
+        }),
+        GeometryKind::MultiPolygon(polygons) => write_list(f, polygons.iter(), |f, polygon| {
+            write_list(f, polygon.iter(), |f, ring| {
+                write_coords(f, ring, dimension)
+            })
+        }),
+        GeometryKind::GeometryCollection(geometries) => {
+            write_list(f, geometries.iter(), write_geometry)
+        }
+    }
+}
+
+/// 写出括号包围、逗号分隔的列表，空列表写作 `EMPTY`
+/// Write a parenthesised comma-separated list; an empty list is written as `EMPTY`
+fn write_list<'a, T: 'a>(
+    f: &mut fmt::Formatter<'_>,
+    items: impl ExactSizeIterator<Item = &'a T>,
+    mut write_item: impl FnMut(&mut fmt::Formatter<'_>, &'a T) -> fmt::Result,
+) -> fmt::Result {
+    if items.len() == 0 {
+        return f.write_str("EMPTY");
+    }
+    f.write_char('(')?;
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write_item(f, item)?;
+    }
+    f.write_char(')')
+}
+
+fn write_coords(f: &mut fmt::Formatter<'_>, coords: &[Coord], dimension: Dimension) -> fmt::Result {
+    write_list(f, coords.iter(), |f, c| write_coord(f, c, dimension))
+}
+
+fn write_coord(f: &mut fmt::Formatter<'_>, coord: &Coord, dimension: Dimension) -> fmt::Result {
+    for (i, value) in coord.ordinates(dimension).enumerate() {
+        if i > 0 {
+            f.write_char(' ')?;
+        }
+        write!(f, "{}", value)?;
+    }
+    Ok(())
+}
+
+/// WKT递归下降解析器 Recursive descent WKT parser
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+const KEYWORDS: [&str; 7] = [
+    "GEOMETRYCOLLECTION",
+    "MULTILINESTRING",
+    "MULTIPOLYGON",
+    "MULTIPOINT",
+    "LINESTRING",
+    "POLYGON",
+    "POINT",
+];
+
+impl Parser<'_> {
+    /// 解析几何对象，`depth` 为所在几何集合的嵌套层数，只有顶层可带SRID
+    /// Parse a geometry nested `depth` collections deep; only the top level may carry an SRID
+    fn geometry(&mut self, depth: usize) -> Result<Geometry, GeometryError> {
+        self.skip_whitespace();
+        if depth > MAX_NESTING_DEPTH {
+            return Err(GeometryError::TooDeeplyNested { offset: self.pos });
+        }
+        let srid = if depth == 0
+            && self
+                .rest()
+                .get(..5)
+                .is_some_and(|s| s.eq_ignore_ascii_case("SRID="))
+        {
+            self.pos += 5;
+            let start = self.pos;
+            let end = self.rest().find(';').ok_or(GeometryError::UnexpectedEnd)?;
+            self.pos += end + 1;
+            let srid = self.input[start..start + end].trim().parse().map_err(|_| {
+                GeometryError::Unexpected {
+                    offset: start,
+                    expected: "SRID",
+                }
+            })?;
+            Some(srid)
+        } else {
+            None
+        };
+
+        self.skip_whitespace();
+        let start = self.pos;
+        let word = self.word().to_ascii_uppercase();
+        let (keyword, suffix) = KEYWORDS
+            .iter()
+            .find_map(|keyword| word.strip_prefix(keyword).map(|suffix| (*keyword, suffix)))
+            .ok_or_else(|| GeometryError::UnknownType(self.input[start..self.pos].to_owned()))?;
+        let mut dimension = match suffix {
+            "" => self.dimension_marker(),
+            "Z" => Some(Dimension::Xyz),
+            "M" => Some(Dimension::Xym),
+            "ZM" => Some(Dimension::Xyzm),
+            _ => {
+                return Err(GeometryError::UnknownType(
+                    self.input[start..self.pos].to_owned(),
+                ));
+            }
+        };
+
+        let empty = self.empty();
+        let kind = match keyword {
+            "POINT" if empty => GeometryKind::Point(None),
+            "POINT" => {
+                self.expect('(', "`(`")?;
+                let coord = self.coord(&mut dimension)?;
+                self.expect(')', "`)`")?;
+                GeometryKind::Point(Some(coord))
+            }
+            _ if empty => match keyword {
+                "LINESTRING" => GeometryKind::LineString(Vec::new()),
+                "POLYGON" => GeometryKind::Polygon(Vec::new()),
+                "MULTIPOINT" => GeometryKind::MultiPoint(Vec::new()),
+                "MULTILINESTRING" => GeometryKind::MultiLineString(Vec::new()),
+                "MULTIPOLYGON" => GeometryKind::MultiPolygon(Vec::new()),
+                _ => GeometryKind::GeometryCollection(Vec::new()),
+            },
+            "LINESTRING" => GeometryKind::LineString(self.coords(&mut dimension)?),
+            "POLYGON" => GeometryKind::Polygon(self.list(|p| p.coords(&mut dimension))?),
+            "MULTILINESTRING" => {
+                GeometryKind::MultiLineString(self.list(|p| p.coords(&mut dimension))?)
+            }
+            "MULTIPOLYGON" => {
+                GeometryKind::MultiPolygon(self.list(|p| p.list(|p| p.coords(&mut dimension)))?)
+            }
+            "MULTIPOINT" => GeometryKind::MultiPoint(self.list(|p| {
+                // 同时接受 `(1 2, 3 4)` 与 `((1 2), (3 4))`
+                // Accept both `(1 2, 3 4)` and `((1 2), (3 4))`
+                if p.eat('(') {
+                    let coord = p.coord(&mut dimension)?;
+                    p.expect(')', "`)`")?;
+                    Ok(coord)
+                } else {
+                    p.coord(&mut dimension)
+                }
+            })?),
+            _ => GeometryKind::GeometryCollection(self.list(|p| p.geometry(depth + 1))?),
+        };
+
+        Ok(Geometry {
+            srid,
+            dimension: dimension.unwrap_or_default(),
+            kind,
+        })
+    }
+
+    /// 读取可选的维度标记 `Z`、`M` 或 `ZM` Read an optional `Z`, `M` or `ZM` marker
+    fn dimension_marker(&mut self) -> Option<Dimension> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let dimension = match self.word().to_ascii_uppercase().as_str() {
+            "Z" => Some(Dimension::Xyz),
+            "M" => Some(Dimension::Xym),
+            "ZM" => Some(Dimension::Xyzm),
+            _ => None,
+        };
+        if dimension.is_none() {
+            self.pos = start;
+        }
+        dimension
+    }
+
+    /// 读取可选的 `EMPTY` Read an optional `EMPTY`
+    fn empty(&mut self) -> bool {
+        self.skip_whitespace();
+        let start = self.pos;
+        if self.word().eq_ignore_ascii_case("EMPTY") {
+            true
+        } else {
+            self.pos = start;
+            false
+        }
+    }
+
+    /// 读取括号包围、逗号分隔的列表，`EMPTY` 为空列表
+    /// Read a parenthesised comma-separated list; `EMPTY` is an empty list
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, GeometryError>,
+    ) -> Result<Vec<T>, GeometryError> {
+        if self.empty() {
+            return Ok(Vec::new());
+        }
+        self.expect('(', "`(`")?;
+        let mut items = vec![item(self)?];
+        while self.eat(',') {
+            items.push(item(self)?);
+        }
+        self.expect(')', "`,` or `)`")?;
+        Ok(items)
+    }
+
+    fn coords(&mut self, dimension: &mut Option<Dimension>) -> Result<Vec<Coord>, GeometryError> {
+        self.list(|p| p.coord(dimension))
+    }
+
+    /// 读取一个坐标，未指定维度时按分量个数推断
+    /// Read a coordinate, inferring the dimension from the number of ordinates when unspecified
+    fn coord(&mut self, dimension: &mut Option<Dimension>) -> Result<Coord, GeometryError> {
+        let start = self.pos;
+        let mut ordinates = [0.0; 4];
+        let mut count = 0;
+        while count < 4 && self.at_number() {
+            ordinates[count] = self.number()?;
+            count += 1;
+        }
+        if count == 0 {
+            self.skip_whitespace();
+            return Err(self.unexpected("coordinate"));
+        }
+
+        let expected = match *dimension {
+            Some(dimension) => dimension,
+            None => {
+                let inferred = match count {
+                    2 => Dimension::Xy,
+                    3 => Dimension::Xyz,
+                    4 => Dimension::Xyzm,
+                    _ => return Err(GeometryError::DimensionMismatch { offset: start }),
+                };
+                *dimension = Some(inferred);
+                inferred
+            }
+        };
+        if count != expected.size() || self.at_number() {
+            return Err(GeometryError::DimensionMismatch { offset: start });
+        }
+        Ok(Coord::from_ordinates(expected, &ordinates[..count]))
+    }
+
+    fn at_number(&mut self) -> bool {
+        self.skip_whitespace();
+        self.rest()
+            .starts_with(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'N' | 'n'))
+            && !self
+                .rest()
+                .get(..5)
+                .is_some_and(|s| s.eq_ignore_ascii_case("EMPTY"))
+    }
+
+    fn number(&mut self) -> Result<f64, GeometryError> {
+        let start = self.pos;
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')))
+            .unwrap_or(self.rest().len());
+        self.pos += len;
+        self.input[start..self.pos]
+            .parse()
+            .map_err(|_| GeometryError::Unexpected {
+                offset: start,
+                expected: "number",
+            })
+    }
+
+    fn word(&mut self) -> &str {
+        let start = self.pos;
+        let len = self
+            .rest()
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(self.rest().len());
+        self.pos += len;
+        &self.input[start..self.pos]
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, expected: &'static str) -> Result<(), GeometryError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn unexpected(&self, expected: &'static str) -> GeometryError {
+        if self.pos >= self.input.len() {
+            GeometryError::UnexpectedEnd
+        } else {
+            GeometryError::Unexpected {
+                offset: self.pos,
+                expected,
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let cases = [
+            "POINT (1 2)",
+            "POINT Z (1 2 3)",
+            "POINT M (1 2 4)",
+            "POINT ZM (1 2 3 4)",
+            "POINT EMPTY",
+            "LINESTRING (1 2, 3.5 -4.25)",
+            "POLYGON ((0 0, 10 0, 10 10, 0 0), (1 1, 2 1, 2 2, 1 1))",
+            "MULTIPOINT Z ((1 2 3), (4 5 6))",
+            "MULTILINESTRING ((1 2, 3 4), EMPTY)",
+            "MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5)))",
+            "GEOMETRYCOLLECTION (POINT (1 2), LINESTRING M (1 2 3, 4 5 6), POLYGON EMPTY)",
+            "GEOMETRYCOLLECTION EMPTY",
+            "SRID=4326;MULTIPOINT EMPTY",
+        ];
+        for wkt in cases {
+            assert_eq!(read(wkt).unwrap().to_string(), wkt);
+        }
+    }
+
+    #[test]
+    fn test_postgis_forms() {
+        let geometry = read("srid=3857;pointm(1 2 3)").unwrap();
+        assert_eq!(geometry.srid, Some(3857));
+        assert_eq!(geometry.dimension, Dimension::Xym);
+
+        let geometry = read("LINESTRING(1 2 3,4 5 6)").unwrap();
+        assert_eq!(geometry.dimension, Dimension::Xyz);
+        assert_eq!(geometry.to_string(), "LINESTRING Z (1 2 3, 4 5 6)");
+
+        let geometry = read("MULTIPOINT(1 2,3 4)").unwrap();
+        assert_eq!(geometry.to_string(), "MULTIPOINT ((1 2), (3 4))");
+    }
+
+    #[test]
+    fn test_convert() {
+        let output = convert_str(
+            "SRID=4326;POLYGON Z ((116 39 5, 117 39 6, 117 40 7, 116 39 5))",
+            CoordSystem::Wgs84,
+            CoordSystem::Epsg3857,
+        )
+        .unwrap();
+        let geometry = read(&output).unwrap();
+        assert_eq!(geometry.srid, Some(3857));
+        let GeometryKind::Polygon(rings) = geometry.kind else {
+            panic!("geometry type changed");
+        };
+        let (x, y) = crate::wgs84_to_epsg3857(117.0, 40.0);
+        assert_eq!(
+            (rings[0][2].x, rings[0][2].y, rings[0][2].z),
+            (x, y, Some(7.0))
+        );
+
+        let output = convert_str(
+            "SRID=3857;POINT (12958752 4825923)",
+            CoordSystem::Epsg3857,
+            CoordSystem::Bd09,
+        )
+        .unwrap();
+        assert!(output.starts_with("SRID=4326;POINT ("), "{}", output);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            read("CIRCLE (1 2)"),
+            Err(GeometryError::UnknownType(_))
+        ));
+        assert_eq!(read("POINT (1 2"), Err(GeometryError::UnexpectedEnd));
+        assert!(matches!(
+            read("LINESTRING (1 2, 3 4 5)"),
+            Err(GeometryError::DimensionMismatch { .. })
+        ));
+        assert!(matches!(
+            read("POINT Z (1 2)"),
+            Err(GeometryError::DimensionMismatch { .. })
+        ));
+        assert!(matches!(
+            read("POINT (1 2) POINT (3 4)"),
+            Err(GeometryError::TrailingData { .. })
+        ));
+        assert!(matches!(
+            read("SRID=abc;POINT (1 2)"),
+            Err(GeometryError::Unexpected { .. })
+        ));
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let nested = |depth: usize| {
+            "GEOMETRYCOLLECTION (".repeat(depth) + "POINT (1 2)" + &")".repeat(depth)
+        };
+        let geometry = read(&nested(MAX_NESTING_DEPTH)).unwrap();
+        assert_eq!(geometry.to_string(), nested(MAX_NESTING_DEPTH));
+        assert!(matches!(
+            read(&nested(MAX_NESTING_DEPTH + 1)),
+            Err(GeometryError::TooDeeplyNested { .. })
+        ));
+        // 恶意输入不会导致栈溢出 Crafted input does not overflow the stack
+        assert!(matches!(
+            read(&"GEOMETRYCOLLECTION(".repeat(1_000_000)),
+            Err(GeometryError::TooDeeplyNested { .. })
+        ));
+    }
+}
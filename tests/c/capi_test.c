@@ -0,0 +1,68 @@
+/* Exercises the coordtransform C API; exits non-zero on the first failure. */
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "coordtransform.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                        \
+    do {                                                                   \
+        if (!(cond)) {                                                     \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,         \
+                    __LINE__, #cond);                                      \
+            failures++;                                                    \
+        }                                                                  \
+    } while (0)
+
+static int close_to(double a, double b, double eps) { return fabs(a - b) < eps; }
+
+int main(void) {
+    /* Single point */
+    CtPoint gcj = ct_wgs84_to_gcj02(116.404, 39.915);
+    CHECK(close_to(gcj.lon, 116.41024449916938, 1e-9));
+    CHECK(close_to(gcj.lat, 39.91640428150164, 1e-9));
+
+    CtPoint wgs = ct_gcj02_to_wgs84_precise(gcj.lon, gcj.lat);
+    CHECK(close_to(wgs.lon, 116.404, 1e-9));
+    CHECK(close_to(wgs.lat, 39.915, 1e-9));
+
+    /* Batch into a separate buffer and in place */
+    double input[4] = {116.404, 39.915, 121.4737, 31.2304};
+    double output[4];
+    CHECK(ct_wgs84_to_bd09_batch(input, output, 2) == CT_OK);
+    CtPoint bd = ct_wgs84_to_bd09(121.4737, 31.2304);
+    CHECK(output[2] == bd.lon && output[3] == bd.lat);
+
+    CHECK(ct_wgs84_to_bd09_batch(input, input, 2) == CT_OK);
+    CHECK(memcmp(input, output, sizeof output) == 0);
+
+    double mercator[4] = {116.404, 39.915, 121.4737, 31.2304};
+    CHECK(ct_convert_batch(CT_WGS84, CT_EPSG3857, mercator, mercator, 2) == CT_OK);
+    CtPoint xy = ct_wgs84_to_epsg3857(116.404, 39.915);
+    CHECK(mercator[0] == xy.lon && mercator[1] == xy.lat);
+
+    /* Conversion by system id */
+    CtPoint out = {0.0, 0.0};
+    CHECK(ct_convert(CT_WGS84, CT_GCJ02, 116.404, 39.915, &out) == CT_OK);
+    CHECK(out.lon == gcj.lon && out.lat == gcj.lat);
+
+    /* Errors */
+    CHECK(ct_convert(CT_WGS84, 42, 116.404, 39.915, &out) == CT_ERROR_UNKNOWN_SYSTEM);
+    CHECK(ct_convert(CT_WGS84, CT_GCJ02, 116.404, 39.915, NULL) == CT_ERROR_NULL_POINTER);
+    CHECK(ct_try_convert(CT_WGS84, CT_GCJ02, NAN, 39.915, &out) == CT_ERROR_NON_FINITE);
+    CHECK(ct_try_convert(CT_WGS84, CT_GCJ02, 116.404, 95.0, &out) == CT_ERROR_OUT_OF_RANGE);
+    CHECK(ct_wgs84_to_gcj02_batch(NULL, output, 1) == CT_ERROR_NULL_POINTER);
+    CHECK(ct_wgs84_to_gcj02_batch(NULL, NULL, 0) == CT_OK);
+    CHECK(strcmp(ct_error_message(CT_ERROR_NON_FINITE), "coordinate is NaN or infinite") == 0);
+    CHECK(strlen(ct_version()) > 0);
+
+    if (failures != 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("coordtransform %s: all checks passed\n", ct_version());
+    return 0;
+}
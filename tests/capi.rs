@@ -0,0 +1,90 @@
+//! 将本库构建为静态库并编译、运行C测试程序 `tests/c/capi_test.c`
+//! Build the library as a static library, then compile and run the C test program
+//! `tests/c/capi_test.c`
+#![cfg(feature = "capi")]
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 构建静态库，返回库路径与需要链接的系统库
+/// Build the static library, returning its path and the native libraries it needs
+fn build_staticlib(target_dir: &Path) -> (PathBuf, Vec<String>) {
+    let output = Command::new(env!("CARGO"))
+        .args([
+            "rustc",
+            "--lib",
+            "--features",
+            "capi",
+            "--crate-type",
+            "staticlib",
+        ])
+        .arg("--manifest-path")
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .arg("--target-dir")
+        .arg(target_dir)
+        .args(["--", "--print", "native-static-libs"])
+        .output()
+        .expect("failed to run cargo");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "building the static library failed:\n{}",
+        stderr
+    );
+
+    let native_libs = stderr
+        .lines()
+        .find_map(|line| line.split_once("native-static-libs:"))
+        .map(|(_, libs)| libs.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+    let name = if cfg!(windows) {
+        "coordtransform.lib"
+    } else {
+        "libcoordtransform.a"
+    };
+    (target_dir.join("debug").join(name), native_libs)
+}
+
+#[test]
+fn test_c_program() {
+    // 没有C编译器时测试失败，需显式设置环境变量才跳过
+    // Without a C compiler the test fails; skipping requires setting the variable explicitly
+    if env::var_os("COORDTRANSFORM_SKIP_CAPI_TEST").is_some() {
+        eprintln!("skipping the C API test: COORDTRANSFORM_SKIP_CAPI_TEST is set");
+        return;
+    }
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".into());
+    assert!(
+        Command::new(&compiler).arg("--version").output().is_ok(),
+        "no C compiler `{}`; set CC, or COORDTRANSFORM_SKIP_CAPI_TEST=1 to skip this test",
+        compiler
+    );
+
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("capi");
+    let (library, native_libs) = build_staticlib(&target_dir);
+    let executable = target_dir.join("capi_test");
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let status = Command::new(&compiler)
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg(root.join("tests/c/capi_test.c"))
+        .arg(&library)
+        .args(&native_libs)
+        .arg("-lm")
+        .arg("-o")
+        .arg(&executable)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "compiling the C test program failed");
+
+    let output = Command::new(&executable)
+        .output()
+        .expect("failed to run the C test program");
+    assert!(
+        output.status.success(),
+        "the C test program failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}